@@ -0,0 +1,51 @@
+//! The simulator compiled to WebAssembly, for the page in `index.html`.
+//!
+//! The scenarios are simulated by [`scenario::simulate`], which does not
+//! use threads, so the functions below also work in a browser.
+
+use std::num::NonZeroUsize;
+
+use processor::scenario::{self, Scenario};
+use processor::{export, format_logs, Log};
+use scheduler::Params;
+use wasm_bindgen::prelude::*;
+
+/// Runs a scenario and returns the logs as a JSON array, the format of
+/// [`export::json`].
+///
+/// * `policy` - the name of the scheduler, like `round-robin`.
+/// * `timeslice` - the time slice of the scheduler.
+/// * `min_remaining` - the minimum remaining time slice of a process
+///   that is not preempted.
+/// * `cpu_time` - the CPU time divided by the CFS scheduler.
+/// * `scenario` - the scenario, in the TOML format of [`Scenario`].
+#[wasm_bindgen]
+pub fn run(policy: &str, timeslice: usize, min_remaining: usize, cpu_time: usize, scenario: &str) -> Result<String, String> {
+    simulate(policy, timeslice, min_remaining, cpu_time, scenario).map(|logs| export::json(&logs))
+}
+
+/// Runs a scenario and returns the logs in the format of the reference
+/// logs, see [`format_logs`].
+///
+/// The parameters are the ones of [`run`].
+#[wasm_bindgen]
+pub fn run_text(policy: &str, timeslice: usize, min_remaining: usize, cpu_time: usize, scenario: &str) -> Result<String, String> {
+    simulate(policy, timeslice, min_remaining, cpu_time, scenario).map(|logs| format_logs(&logs))
+}
+
+/// The names of the schedulers that [`run`] accepts.
+#[wasm_bindgen]
+pub fn policies() -> Vec<String> {
+    scheduler::Policy::ALL.iter().map(|policy| policy.name().to_string()).collect()
+}
+
+fn simulate(policy: &str, timeslice: usize, min_remaining: usize, cpu_time: usize, scenario: &str) -> Result<Vec<Log>, String> {
+    let scenario = scenario.parse::<Scenario>().map_err(|error| error.to_string())?;
+    let params = Params {
+        timeslice: NonZeroUsize::new(timeslice).ok_or("the timeslice must be at least 1")?,
+        minimum_remaining_timeslice: min_remaining,
+        cpu_time: NonZeroUsize::new(cpu_time).ok_or("the CPU time must be at least 1")?,
+    };
+    let scheduler = scheduler::by_name(policy, &params).ok_or_else(|| format!("unknown scheduler {policy}"))?;
+    Ok(scenario::simulate(scheduler, &scenario))
+}
@@ -0,0 +1,73 @@
+use std::io;
+use std::num::NonZeroUsize;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use processor::{workloads, Process, Processor};
+use scheduler::Scheduler;
+
+const EVENTS: [usize; 3] = [10, 1_000, 100_000];
+
+/// PID 1 forks a process for every two events, every child executes
+/// once and exits.
+fn workload<S: Scheduler>(process: &Process<S>, events: usize) {
+    for _ in 0..events / 2 {
+        process.fork(|process| process.exec(), 0);
+    }
+}
+
+fn run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Processor::run");
+    group.sample_size(10);
+    for events in EVENTS {
+        group.throughput(Throughput::Elements(events as u64));
+        group.bench_with_input(BenchmarkId::new("round robin", events), &events, |b, &events| {
+            b.iter(|| {
+                // the narration would measure the terminal
+                Processor::builder().narrate(io::sink()).run(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), |process| {
+                    workload(process, events)
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("priority queue", events), &events, |b, &events| {
+            b.iter(|| {
+                // the narration would measure the terminal
+                Processor::builder().narrate(io::sink()).run(scheduler::priority_queue(NonZeroUsize::new(3).unwrap(), 1), |process| {
+                    workload(process, events)
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("cfs", events), &events, |b, &events| {
+            b.iter(|| {
+                // the narration would measure the terminal
+                Processor::builder().narrate(io::sink()).run(scheduler::cfs(NonZeroUsize::new(10).unwrap(), 1), |process| {
+                    workload(process, events)
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+/// The standard workloads, with every scheduler.
+fn standard(c: &mut Criterion) {
+    let mut group = c.benchmark_group("workloads");
+    group.sample_size(10);
+    for workload in workloads::standard().iter().copied() {
+        for policy in scheduler::Policy::ALL {
+            let config = match policy {
+                scheduler::Policy::Cfs => scheduler::SchedulerConfig::new(policy).cpu_time(10),
+                _ => scheduler::SchedulerConfig::new(policy).timeslice(3),
+            };
+            group.bench_with_input(BenchmarkId::new(policy.name(), workload), &workload, |b, &workload| {
+                b.iter(|| {
+                    // the narration would measure the terminal
+                    Processor::builder().narrate(io::sink()).run(config.build().unwrap(), move |process| workload.run(process))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, run, standard);
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+//! The threads that run the processes of a simulation.
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+
+/// How the [`Processor`](crate::Processor) runs the simulated processes.
+///
+/// Only one process runs at a time, but every process that has not
+/// exited keeps its own stack, so each one is backed by an OS thread.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{Backend, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let workload = |process: &processor::Process<_>| {
+///     for _ in 0..100 {
+///         process.fork(|process| process.exec(), 0);
+///     }
+/// };
+///
+/// let threads = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), workload);
+/// let pooled = Processor::builder()
+///     .backend(Backend::pooled())
+///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), workload);
+/// assert_eq!(threads, pooled);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// Every process runs on a new thread with the default stack size.
+    #[default]
+    Threads,
+
+    /// Processes run on a pool of threads with small stacks. A thread
+    /// whose process has exited runs the next process that is forked,
+    /// so large simulations create as few threads as possible.
+    Pooled {
+        /// The stack size of the threads, in bytes.
+        stack_size: usize,
+    },
+}
+
+impl Backend {
+    /// The stack size used by [`Backend::pooled`].
+    pub const DEFAULT_STACK_SIZE: usize = 64 * 1024;
+
+    /// Returns a [`Backend::Pooled`] with
+    /// [`DEFAULT_STACK_SIZE`](Backend::DEFAULT_STACK_SIZE).
+    pub fn pooled() -> Backend {
+        Backend::Pooled {
+            stack_size: Backend::DEFAULT_STACK_SIZE,
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Starts the threads of the processes for a [`Backend`].
+pub(crate) struct Spawner {
+    pool: Option<Arc<Pool>>,
+}
+
+/// The threads of a [`Backend::Pooled`] that wait for a process to run.
+struct Pool {
+    stack_size: usize,
+    idle: Mutex<Vec<Sender<Job>>>,
+}
+
+impl Spawner {
+    pub(crate) fn new(backend: Backend) -> Spawner {
+        let pool = match backend {
+            Backend::Threads => None,
+            Backend::Pooled { stack_size } => Some(Arc::new(Pool {
+                stack_size,
+                idle: Mutex::new(vec![]),
+            })),
+        };
+        Spawner { pool }
+    }
+
+    /// Runs `job` on a thread, reusing an idle one if there is a pool.
+    pub(crate) fn spawn<F: FnOnce() + Send + 'static>(&self, job: F) {
+        let Some(pool) = &self.pool else {
+            thread::spawn(job);
+            return;
+        };
+
+        let idle = pool.idle.lock().unwrap().pop();
+        if let Some(worker) = idle {
+            // a parked worker waits for a job as long as the pool exists
+            worker.send(Box::new(job)).expect("A parked process thread has stopped");
+            return;
+        }
+
+        let weak = Arc::downgrade(pool);
+        thread::Builder::new()
+            .stack_size(pool.stack_size)
+            .spawn(move || {
+                job();
+                while let Some(job) = Pool::park(&weak) {
+                    job();
+                }
+            })
+            .expect("Unable to start a process thread");
+    }
+}
+
+impl Pool {
+    /// Adds the current thread to the idle threads and waits for a
+    /// job, returns `None` once the pool does not exist anymore.
+    fn park(pool: &Weak<Pool>) -> Option<Job> {
+        let (sender, receiver) = mpsc::channel();
+        pool.upgrade()?.idle.lock().unwrap().push(sender);
+        receiver.recv().ok()
+    }
+}
@@ -0,0 +1,230 @@
+//! Collect the logs of a simulation on a separate thread.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use scheduler::{StopReason, SyscallResult};
+#[cfg(feature = "memory-usage")]
+use scheduler::{MemoryUsage, Pid};
+
+use crate::{Log, LogSink};
+#[cfg(feature = "memory-usage")]
+use crate::ProcessInfo;
+
+/// A message for the thread that collects the logs.
+enum Record {
+    /// A new iteration, the previous one is complete.
+    Iteration(Log),
+
+    /// The reason that the process of the last iteration has stopped.
+    Stopped(StopReason, SyscallResult),
+
+    /// The memory used by the logs is sent back.
+    #[cfg(feature = "memory-usage")]
+    Memory(Sender<MemoryUsage>),
+
+    /// The simulation has ended, the logs are sent back.
+    Finish(Sender<Result<Vec<Log>, String>>),
+}
+
+/// Sends the logs of a simulation to a thread that stores them and
+/// writes them to the sink, so that recording an iteration only
+/// sends a message.
+pub(crate) struct Collector {
+    sender: Sender<Record>,
+}
+
+impl Collector {
+    /// * `sink` - the destination of the complete iterations.
+    /// * `retained` - how many iterations are returned, all of them if [`None`].
+    /// * `capacity` - the number of iterations to allocate memory for.
+    pub(crate) fn start(sink: Option<Box<dyn LogSink>>, retained: Option<usize>, capacity: usize) -> Collector {
+        let (sender, receiver) = mpsc::channel();
+        let logs = Logs {
+            complete: VecDeque::with_capacity(retained.map_or(capacity, |retained| retained.min(capacity))),
+            current: None,
+            retained,
+            #[cfg(feature = "memory-usage")]
+            owned: 0,
+            #[cfg(feature = "memory-usage")]
+            memory: MemoryUsage::default(),
+        };
+        thread::spawn(move || collect(receiver, sink, logs));
+        Collector { sender }
+    }
+
+    /// Add a new iteration, the previous one is now complete.
+    pub(crate) fn record(&self, log: Log) {
+        self.send(Record::Iteration(log));
+    }
+
+    /// Set the stop reason of the last iteration.
+    pub(crate) fn stopped(&self, reason: StopReason, result: SyscallResult) {
+        self.send(Record::Stopped(reason, result));
+    }
+
+    /// Returns the memory used by the logs kept in memory, including
+    /// the ones recorded so far.
+    #[cfg(feature = "memory-usage")]
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        let (sender, receiver) = mpsc::channel();
+        self.send(Record::Memory(sender));
+        receiver.recv().unwrap_or_default()
+    }
+
+    /// Wait for the logs to be written and return them.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the sink could not write the logs.
+    pub(crate) fn finish(&self) -> Vec<Log> {
+        let (sender, receiver) = mpsc::channel();
+        self.send(Record::Finish(sender));
+        match receiver.recv() {
+            Ok(Ok(logs)) => logs,
+            Ok(Err(error)) => panic!("{error}"),
+            Err(_) => panic!("The logs have already been collected"),
+        }
+    }
+
+    fn send(&self, record: Record) {
+        // the collector only stops once the logs have been collected,
+        // anything recorded after that is not part of the simulation
+        let _ = self.sender.send(record);
+    }
+}
+
+/// Writes the complete iterations to the sink, if there is one.
+struct Writer {
+    sink: Option<Box<dyn LogSink>>,
+    iteration: usize,
+    // the first error stops the sink, it is reported when the logs are collected
+    error: Option<String>,
+}
+
+impl Writer {
+    fn emit(&mut self, log: &Log) {
+        let iteration = self.iteration;
+        self.iteration += 1;
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(sink) = self.sink.as_mut() {
+            if let Err(error) = sink.write(iteration, log) {
+                self.error = Some(format!("Unable to write the log of iteration {}: {error}", iteration + 1));
+            }
+        }
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        match self.sink.as_mut().map(|sink| sink.finish()) {
+            Some(Err(error)) => Err(format!("Unable to finish writing the logs: {error}")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The iterations that are kept in memory.
+struct Logs {
+    complete: VecDeque<Log>,
+    // the iteration whose process is running, it can still get a stop reason
+    current: Option<Log>,
+    retained: Option<usize>,
+    // the memory owned by the complete iterations
+    #[cfg(feature = "memory-usage")]
+    owned: usize,
+    #[cfg(feature = "memory-usage")]
+    memory: MemoryUsage,
+}
+
+impl Logs {
+    /// Returns the iteration that was running, if there was one.
+    fn push(&mut self, log: Log) -> Option<&Log> {
+        let Some(previous) = self.current.replace(log) else {
+            #[cfg(feature = "memory-usage")]
+            self.measure();
+            return None;
+        };
+        Some(self.keep(previous))
+    }
+
+    fn keep(&mut self, log: Log) -> &Log {
+        if self.retained.is_some_and(|retained| self.complete.len() >= retained) {
+            #[cfg(feature = "memory-usage")]
+            if let Some(oldest) = self.complete.front() {
+                self.owned -= owned_memory(oldest);
+            }
+            self.complete.pop_front();
+        }
+        #[cfg(feature = "memory-usage")]
+        {
+            self.owned += owned_memory(&log);
+        }
+        self.complete.push_back(log);
+        #[cfg(feature = "memory-usage")]
+        self.measure();
+        self.complete.back().unwrap()
+    }
+
+    // updates the memory used by the complete iterations and the one
+    // that is running
+    #[cfg(feature = "memory-usage")]
+    fn measure(&mut self) {
+        let current = self.current.as_ref().map_or(0, owned_memory);
+        self.memory.update(self.complete.capacity() * std::mem::size_of::<Log>() + self.owned + current);
+    }
+
+    fn finish(mut self, writer: &mut Writer) -> Vec<Log> {
+        if let Some(last) = self.current.take() {
+            writer.emit(&last);
+            self.keep(last);
+        }
+        if self.retained == Some(0) {
+            self.complete.clear();
+        }
+        self.complete.into()
+    }
+}
+
+fn collect(receiver: Receiver<Record>, sink: Option<Box<dyn LogSink>>, mut logs: Logs) {
+    let mut writer = Writer {
+        sink,
+        iteration: 0,
+        error: None,
+    };
+
+    for record in receiver {
+        match record {
+            Record::Iteration(log) => {
+                if let Some(previous) = logs.push(log) {
+                    writer.emit(previous);
+                }
+            }
+            Record::Stopped(reason, result) => {
+                if let Some(log) = logs.current.as_mut() {
+                    log.stop_reason = Some((reason, result));
+                }
+            }
+            #[cfg(feature = "memory-usage")]
+            Record::Memory(reply) => {
+                let _ = reply.send(logs.memory);
+            }
+            Record::Finish(reply) => {
+                let logs = logs.finish(&mut writer);
+                let _ = reply.send(writer.finish().map(|()| logs));
+                return;
+            }
+        }
+    }
+}
+
+// the memory owned by a log, outside of the buffer that stores it
+#[cfg(feature = "memory-usage")]
+fn owned_memory(log: &Log) -> usize {
+    log.processes.capacity() * std::mem::size_of::<(Pid, ProcessInfo)>()
+        + log.processes.values().map(|info| info.extra.capacity()).sum::<usize>()
+}
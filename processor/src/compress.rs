@@ -0,0 +1,107 @@
+//! Collapse repetitive iterations of the [`Processor`](crate::Processor)'s logs.
+
+use std::ops::Range;
+
+use crate::Log;
+
+/// An entry of the compressed logs returned by [`compress`].
+#[derive(Debug, PartialEq)]
+pub enum LogEntry {
+    /// An iteration that is different from its neighbours.
+    Single(Log),
+
+    /// Consecutive iterations that differ only by the process timings.
+    Repeated {
+        /// The last iteration of the run, holding the final timings.
+        log: Log,
+
+        /// The number of iterations that were collapsed.
+        count: usize,
+    },
+}
+
+impl LogEntry {
+    /// Returns the log of the entry.
+    ///
+    /// For [`LogEntry::Repeated`] entries, this is the last collapsed iteration.
+    pub fn log(&self) -> &Log {
+        match self {
+            LogEntry::Single(log) => log,
+            LogEntry::Repeated { log, .. } => log,
+        }
+    }
+
+    /// Returns the number of iterations represented by the entry.
+    pub fn count(&self) -> usize {
+        match self {
+            LogEntry::Single(_) => 1,
+            LogEntry::Repeated { count, .. } => *count,
+        }
+    }
+}
+
+/// Returns `true` if two iterations differ only by the process timings.
+fn similar(a: &Log, b: &Log) -> bool {
+    a.decision == b.decision
+        && a.stop_reason == b.stop_reason
+        && a.processes.len() == b.processes.len()
+        && a.processes.iter().all(|(pid, a)| {
+            b.processes.get(pid).is_some_and(|b| {
+                a.state == b.state && a.priority == b.priority && a.extra == b.extra
+            })
+        })
+}
+
+/// Returns the ranges of consecutive similar iterations.
+pub(crate) fn runs(logs: &[Log]) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = vec![];
+    for (iteration, log) in logs.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if similar(&logs[run.start], log) => run.end = iteration + 1,
+            _ => runs.push(iteration..iteration + 1),
+        }
+    }
+    runs
+}
+
+/// Collapse consecutive iterations that differ only by the process
+/// timings into a single [`LogEntry::Repeated`] entry.
+///
+/// Long CPU bound simulations produce many iterations where the
+/// scheduler takes the same decision and the processes keep the
+/// same state, so only the timings change.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{compress, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     for _ in 0..10 {
+///         process.exec();
+///     }
+/// });
+///
+/// let iterations = logs.len();
+/// let entries = compress(logs);
+/// assert!(entries.len() < iterations);
+/// assert_eq!(entries.iter().map(|entry| entry.count()).sum::<usize>(), iterations);
+/// ```
+pub fn compress(logs: Vec<Log>) -> Vec<LogEntry> {
+    let runs = runs(&logs);
+    let mut logs = logs.into_iter();
+    runs.into_iter()
+        .map(|run| {
+            let count = run.len();
+            let log = logs.by_ref().take(count).last().unwrap();
+            if count == 1 {
+                LogEntry::Single(log)
+            } else {
+                LogEntry::Repeated { log, count }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,234 @@
+//! Find which behaviors of a scheduler a set of simulations has exercised.
+//!
+//! Every [`Behavior`] is a path of a scheduler that a test suite should
+//! reach, like a process that signals an event that no process waits
+//! for. A [`Coverage`] counts the behaviors found in the logs of every
+//! simulation that is added to it, so the behaviors that were never
+//! found are the untested paths.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use processor::coverage::{Behavior, Coverage};
+//! use processor::Processor;
+//! use std::num::NonZeroUsize;
+//!
+//! let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+//!     process.exec();
+//!     process.signal(1usize);
+//! });
+//!
+//! let mut coverage = Coverage::new();
+//! coverage.add(&logs);
+//! assert_eq!(coverage.count(Behavior::SignalWithoutWaiters), 1);
+//! assert!(coverage.missing().contains(&Behavior::Deadlock));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use scheduler::{ProcessState, SchedulingDecision, StopReason, Syscall, SyscallResult};
+
+use crate::Log;
+
+/// A behavior of a scheduler, found in the logs of a simulation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Behavior {
+    /// The scheduler has decided to run a process.
+    Run,
+    /// The scheduler has asked the processor to sleep.
+    Sleep,
+    /// The scheduler has reported a deadlock.
+    Deadlock,
+    /// The scheduler has reported a panic.
+    Panic,
+    /// The scheduler has reported that every process has ended.
+    Done,
+    /// The timeslice of a process has expired.
+    Expired,
+    /// The scheduler has preempted a process before its timeslice expired.
+    Preempted,
+    /// A process has forked with time left from its timeslice.
+    ForkWithRemaining,
+    /// A system call has used the last time unit of a timeslice.
+    SyscallAtEndOfTimeslice,
+    /// The scheduler has rejected the priority of a fork.
+    InvalidPriority,
+    /// A process has gone to sleep.
+    SleepSyscall,
+    /// A process has waited for an event.
+    Wait,
+    /// A process has signaled an event that no process waits for.
+    SignalWithoutWaiters,
+    /// A process has signaled an event that one process waits for.
+    SignalOneWaiter,
+    /// A process has signaled an event that several processes wait for.
+    SignalSeveralWaiters,
+    /// A process has exited.
+    Exit,
+    /// The scheduler has run the same process again after a system call.
+    ContinueAfterSyscall,
+    /// The scheduler has run another process after a system call.
+    SwitchAfterSyscall,
+}
+
+impl Behavior {
+    /// Every behavior, in the order of the report.
+    pub const ALL: [Behavior; 18] = [
+        Behavior::Run,
+        Behavior::Sleep,
+        Behavior::Deadlock,
+        Behavior::Panic,
+        Behavior::Done,
+        Behavior::Expired,
+        Behavior::Preempted,
+        Behavior::ForkWithRemaining,
+        Behavior::SyscallAtEndOfTimeslice,
+        Behavior::InvalidPriority,
+        Behavior::SleepSyscall,
+        Behavior::Wait,
+        Behavior::SignalWithoutWaiters,
+        Behavior::SignalOneWaiter,
+        Behavior::SignalSeveralWaiters,
+        Behavior::Exit,
+        Behavior::ContinueAfterSyscall,
+        Behavior::SwitchAfterSyscall,
+    ];
+
+    /// A short description of the behavior.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Behavior::Run => "run decision",
+            Behavior::Sleep => "sleep decision",
+            Behavior::Deadlock => "deadlock decision",
+            Behavior::Panic => "panic decision",
+            Behavior::Done => "done decision",
+            Behavior::Expired => "timeslice expired",
+            Behavior::Preempted => "preempted by the scheduler",
+            Behavior::ForkWithRemaining => "fork during a partial timeslice",
+            Behavior::SyscallAtEndOfTimeslice => "system call at the end of the timeslice",
+            Behavior::InvalidPriority => "fork with an invalid priority",
+            Behavior::SleepSyscall => "sleep system call",
+            Behavior::Wait => "wait for an event",
+            Behavior::SignalWithoutWaiters => "signal with no waiters",
+            Behavior::SignalOneWaiter => "signal with one waiter",
+            Behavior::SignalSeveralWaiters => "signal with several waiters",
+            Behavior::Exit => "exit",
+            Behavior::ContinueAfterSyscall => "same process after a system call",
+            Behavior::SwitchAfterSyscall => "other process after a system call",
+        }
+    }
+}
+
+impl Display for Behavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// The behaviors found in the logs of several simulations.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    counts: BTreeMap<Behavior, usize>,
+}
+
+impl Coverage {
+    /// A coverage without any simulation.
+    pub fn new() -> Coverage {
+        Coverage::default()
+    }
+
+    /// Adds the behaviors found in the logs of a simulation.
+    ///
+    /// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+    pub fn add(&mut self, logs: &[Log]) {
+        for behavior in behaviors(logs) {
+            *self.counts.entry(behavior).or_default() += 1;
+        }
+    }
+
+    /// The number of times that `behavior` was found.
+    pub fn count(&self, behavior: Behavior) -> usize {
+        self.counts.get(&behavior).copied().unwrap_or(0)
+    }
+
+    /// The behaviors that were never found, in the order of [`Behavior::ALL`].
+    pub fn missing(&self) -> Vec<Behavior> {
+        Behavior::ALL.into_iter().filter(|behavior| self.count(*behavior) == 0).collect()
+    }
+}
+
+/// A line for every behavior with the number of times that it was found,
+/// followed by the number of behaviors that were found.
+impl Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = Behavior::ALL.iter().map(|behavior| behavior.description().len()).max().unwrap_or(0);
+        for behavior in Behavior::ALL {
+            match self.count(behavior) {
+                0 => writeln!(f, "{:<width$}  MISSING", behavior.description())?,
+                count => writeln!(f, "{:<width$}  {count}", behavior.description())?,
+            }
+        }
+        let found = Behavior::ALL.len() - self.missing().len();
+        write!(f, "{found}/{} behaviors covered", Behavior::ALL.len())
+    }
+}
+
+/// The behaviors found in the logs of a simulation, once for every time
+/// that they happen.
+pub fn behaviors(logs: &[Log]) -> Vec<Behavior> {
+    let mut behaviors = Vec::new();
+    for (index, log) in logs.iter().enumerate() {
+        behaviors.push(match log.decision {
+            SchedulingDecision::Run { .. } => Behavior::Run,
+            SchedulingDecision::Sleep(_) => Behavior::Sleep,
+            SchedulingDecision::Deadlock => Behavior::Deadlock,
+            SchedulingDecision::Panic => Behavior::Panic,
+            SchedulingDecision::Done => Behavior::Done,
+        });
+        let (SchedulingDecision::Run { pid, timeslice }, Some((reason, result))) = (log.decision, log.stop_reason) else {
+            continue;
+        };
+        let StopReason::Syscall { syscall, remaining } = reason else {
+            behaviors.push(match reason {
+                StopReason::Expired => Behavior::Expired,
+                _ => Behavior::Preempted,
+            });
+            continue;
+        };
+
+        if remaining == 0 && timeslice.get() > 1 {
+            behaviors.push(Behavior::SyscallAtEndOfTimeslice);
+        }
+        match syscall {
+            Syscall::Fork(_) if result == SyscallResult::InvalidPriority => behaviors.push(Behavior::InvalidPriority),
+            Syscall::Fork(_) if remaining > 0 => behaviors.push(Behavior::ForkWithRemaining),
+            Syscall::Fork(_) => {}
+            Syscall::Sleep(_) => behaviors.push(Behavior::SleepSyscall),
+            Syscall::Wait(_) => behaviors.push(Behavior::Wait),
+            Syscall::Signal(event) => {
+                // the processes that wait for an event only change when
+                // it is signaled, so they are the ones of the decision
+                let waiters = log
+                    .processes
+                    .values()
+                    .filter(|info| info.state == ProcessState::Waiting { event: Some(event) })
+                    .count();
+                behaviors.push(match waiters {
+                    0 => Behavior::SignalWithoutWaiters,
+                    1 => Behavior::SignalOneWaiter,
+                    _ => Behavior::SignalSeveralWaiters,
+                });
+            }
+            Syscall::Exit => behaviors.push(Behavior::Exit),
+        }
+        if !matches!(syscall, Syscall::Exit) {
+            match logs.get(index + 1).map(|next| next.decision) {
+                Some(SchedulingDecision::Run { pid: next, .. }) if next == pid => behaviors.push(Behavior::ContinueAfterSyscall),
+                Some(SchedulingDecision::Run { .. }) => behaviors.push(Behavior::SwitchAfterSyscall),
+                _ => {}
+            }
+        }
+    }
+    behaviors
+}
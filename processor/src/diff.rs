@@ -0,0 +1,250 @@
+//! Compare the logs of two simulations.
+
+use std::fmt::{self, Display};
+
+use scheduler::{Pid, SchedulingDecision, StopReason, SyscallResult};
+
+use crate::{Column, Log, ProcessInfo};
+
+/// A difference found between two iterations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// The scheduling decisions are different.
+    Decision {
+        expected: SchedulingDecision,
+        actual: SchedulingDecision,
+    },
+
+    /// The reasons that the processes stopped are different.
+    StopReason {
+        expected: Option<(StopReason, SyscallResult)>,
+        actual: Option<(StopReason, SyscallResult)>,
+    },
+
+    /// A process was expected, but it is not listed.
+    MissingProcess { pid: Pid },
+
+    /// A process is listed, but it was not expected.
+    UnexpectedProcess { pid: Pid },
+
+    /// A column of a process's row is different.
+    Process {
+        pid: Pid,
+        column: Column,
+        expected: String,
+        actual: String,
+    },
+
+    /// An iteration was expected, but the simulation ended earlier.
+    MissingIteration,
+
+    /// The simulation has more iterations than expected.
+    UnexpectedIteration,
+}
+
+fn stop_reason(stop: &Option<(StopReason, SyscallResult)>) -> String {
+    match stop {
+        Some((reason, result)) => format!("{reason} -> {result:?}"),
+        None => String::from("none"),
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Decision { expected, actual } => {
+                write!(f, "decision: expected `{expected}`, found `{actual}`")
+            }
+            Difference::StopReason { expected, actual } => write!(
+                f,
+                "stop reason: expected `{}`, found `{}`",
+                stop_reason(expected),
+                stop_reason(actual)
+            ),
+            Difference::MissingProcess { pid } => write!(f, "process {pid} is missing"),
+            Difference::UnexpectedProcess { pid } => write!(f, "process {pid} is not expected"),
+            Difference::Process {
+                pid,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "process {pid} {column}: expected `{expected}`, found `{actual}`"
+            ),
+            Difference::MissingIteration => write!(f, "the iteration is missing"),
+            Difference::UnexpectedIteration => write!(f, "the iteration is not expected"),
+        }
+    }
+}
+
+/// The result of comparing two lists of logs using [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogDiff {
+    /// The first iteration (starting from 0) where the logs are
+    /// different, or [`None`] if the logs are identical.
+    pub iteration: Option<usize>,
+
+    /// The differences found in the first divergent iteration.
+    pub differences: Vec<Difference>,
+
+    expected: Option<String>,
+    actual: Option<String>,
+}
+
+impl LogDiff {
+    /// Returns `true` if the logs are identical.
+    pub fn is_empty(&self) -> bool {
+        self.iteration.is_none()
+    }
+}
+
+fn cells(process: &ProcessInfo) -> [(Column, String); 6] {
+    Column::ALL.map(|column| (column, column.cell(process)))
+}
+
+fn differences(expected: &Log, actual: &Log) -> Vec<Difference> {
+    let mut differences = vec![];
+    if expected.decision != actual.decision {
+        differences.push(Difference::Decision {
+            expected: expected.decision,
+            actual: actual.decision,
+        });
+    }
+    if expected.stop_reason != actual.stop_reason {
+        differences.push(Difference::StopReason {
+            expected: expected.stop_reason,
+            actual: actual.stop_reason,
+        });
+    }
+
+    let mut pids = expected
+        .processes
+        .keys()
+        .chain(actual.processes.keys())
+        .collect::<Vec<&Pid>>();
+    pids.sort();
+    pids.dedup();
+    for pid in pids {
+        match (expected.processes.get(pid), actual.processes.get(pid)) {
+            (Some(expected), Some(actual)) => {
+                for ((column, expected), (_, actual)) in cells(expected).into_iter().zip(cells(actual))
+                {
+                    if expected != actual {
+                        differences.push(Difference::Process {
+                            pid: *pid,
+                            column,
+                            expected,
+                            actual,
+                        });
+                    }
+                }
+            }
+            (Some(_), None) => differences.push(Difference::MissingProcess { pid: *pid }),
+            (None, Some(_)) => differences.push(Difference::UnexpectedProcess { pid: *pid }),
+            (None, None) => {}
+        }
+    }
+    differences
+}
+
+/// Compare the logs of two simulations and report the first iteration
+/// where they are different.
+///
+/// * `expected` - the reference logs.
+/// * `actual` - the logs that are verified.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{diff, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let run = || {
+///     Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///         process.exec();
+///         process.sleep(2);
+///     })
+/// };
+///
+/// let result = diff(&run(), &run());
+/// assert!(result.is_empty(), "{result}");
+/// ```
+pub fn diff(expected: &[Log], actual: &[Log]) -> LogDiff {
+    let len = expected.len().max(actual.len());
+    for iteration in 0..len {
+        let differences = match (expected.get(iteration), actual.get(iteration)) {
+            (Some(expected), Some(actual)) => differences(expected, actual),
+            (Some(_), None) => vec![Difference::MissingIteration],
+            (None, Some(_)) => vec![Difference::UnexpectedIteration],
+            (None, None) => vec![],
+        };
+        if !differences.is_empty() {
+            return LogDiff {
+                iteration: Some(iteration),
+                differences,
+                expected: expected.get(iteration).map(|log| log.to_string()),
+                actual: actual.get(iteration).map(|log| log.to_string()),
+            };
+        }
+    }
+    LogDiff {
+        iteration: None,
+        differences: vec![],
+        expected: None,
+        actual: None,
+    }
+}
+
+/// Replace the tabs of a line with spaces.
+fn expand_tabs(line: &str) -> String {
+    let mut s = String::new();
+    for c in line.chars() {
+        if c == '\t' {
+            s.push(' ');
+            while !s.chars().count().is_multiple_of(8) {
+                s.push(' ');
+            }
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
+
+impl Display for LogDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(iteration) = self.iteration else {
+            return writeln!(f, "The logs are identical");
+        };
+        writeln!(f, "The logs diverge at iteration {}", iteration + 1)?;
+        for difference in &self.differences {
+            writeln!(f, " - {difference}")?;
+        }
+        writeln!(f)?;
+
+        let lines = |text: &Option<String>| {
+            text.as_deref()
+                .unwrap_or_default()
+                .lines()
+                .map(expand_tabs)
+                .collect::<Vec<_>>()
+        };
+        let expected = lines(&self.expected);
+        let actual = lines(&self.actual);
+        let width = expected
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max("Expected".len());
+        writeln!(f, "{:<width$} | Actual", "Expected")?;
+        for index in 0..expected.len().max(actual.len()) {
+            let left = expected.get(index).map_or("", String::as_str);
+            let right = actual.get(index).map_or("", String::as_str);
+            let marker = if left == right { ' ' } else { '*' };
+            writeln!(f, "{left:<width$} {marker} {right}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Wake only the thread of the process that runs next.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use scheduler::Pid;
+
+/// The process that runs on the [`Processor`](crate::Processor) and
+/// the threads of the processes that are suspended.
+///
+/// Every suspended process waits on its own condition variable, so
+/// scheduling a process wakes a single thread, however many processes
+/// there are.
+pub(crate) struct Dispatcher {
+    state: Mutex<Dispatch>,
+    // notified when the simulation ends
+    finished: Condvar,
+}
+
+/// The state of a [`Dispatcher`], changed while its lock is held.
+pub(crate) struct Dispatch {
+    current: Option<Pid>,
+    suspended: HashMap<Pid, Arc<Condvar>>,
+}
+
+impl Dispatcher {
+    pub(crate) fn new() -> Dispatcher {
+        Dispatcher {
+            state: Mutex::new(Dispatch {
+                current: None,
+                suspended: HashMap::new(),
+            }),
+            finished: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Dispatch> {
+        self.state.lock().unwrap()
+    }
+
+    /// Blocks the thread of `pid` until the process is scheduled or
+    /// `running` returns false.
+    ///
+    /// * `condvar` - the condition variable of the process.
+    /// * `parked` - called once, while the lock is held, when the process
+    ///   is registered as suspended or, if it does not have to wait, when
+    ///   it continues.
+    pub(crate) fn suspend<'a>(
+        &'a self,
+        pid: Pid,
+        condvar: &Arc<Condvar>,
+        running: impl Fn() -> bool,
+        parked: impl FnOnce(),
+    ) -> MutexGuard<'a, Dispatch> {
+        let mut parked = Some(parked);
+        let mut dispatch = self.lock();
+        while running() && dispatch.current != Some(pid) {
+            dispatch.suspended.insert(pid, condvar.clone());
+            if let Some(parked) = parked.take() {
+                parked();
+            }
+            dispatch = condvar.wait(dispatch).unwrap();
+        }
+        if let Some(parked) = parked.take() {
+            parked();
+        }
+        dispatch.suspended.remove(&pid);
+        dispatch
+    }
+
+    /// Blocks the current thread until [`Dispatcher::finish`] is called,
+    /// while `running` returns true.
+    pub(crate) fn wait_finished(&self, running: impl Fn() -> bool) {
+        let mut dispatch = self.lock();
+        while running() {
+            dispatch = self.finished.wait(dispatch).unwrap();
+        }
+    }
+
+    /// Wakes the threads of all the suspended processes, and the ones
+    /// that wait for the simulation to end.
+    pub(crate) fn finish(&self, dispatch: &mut Dispatch) {
+        for condvar in dispatch.suspended.values() {
+            condvar.notify_one();
+        }
+        self.finished.notify_all();
+    }
+}
+
+impl Dispatch {
+    /// Marks that no process is running.
+    pub(crate) fn clear(&mut self) {
+        self.current = None;
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        self.current.is_none()
+    }
+
+    /// Runs `pid` and wakes its thread, if it is suspended.
+    pub(crate) fn run(&mut self, pid: Pid) {
+        self.current = Some(pid);
+        if let Some(condvar) = self.suspended.get(&pid) {
+            condvar.notify_one();
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! A processor that simulates a [`Scenario`] without threads.
+//!
+//! The instructions of a scenario are data, so the engine can keep the
+//! position of every process and run them one after another on the
+//! calling thread, with a [`Session`] that asks the scheduler the same
+//! questions as the [`Processor`](crate::Processor) in the same order,
+//! so the logs are the same. Without threads, it also runs where
+//! threads are not available, like WebAssembly.
+
+use std::collections::HashMap;
+
+use scheduler::{Pid, Priority, Scheduler, Syscall, SyscallResult};
+
+use crate::scenario::{Child, Instruction, Scenario};
+use crate::{Log, Session};
+
+/// The next thing a process does.
+enum Step<'a> {
+    Exec,
+    Syscall(Syscall),
+    Fork(&'a Child),
+    Exit,
+}
+
+/// The position of a process in its instructions.
+struct Cursor<'a> {
+    frames: Vec<Frame<'a>>,
+}
+
+struct Frame<'a> {
+    instructions: &'a [Instruction],
+    index: usize,
+    /// How many times the instructions still have to run, including this one.
+    times: usize,
+    /// The time units left from the current [`Instruction::Exec`].
+    exec: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(instructions: &'a [Instruction]) -> Cursor<'a> {
+        Cursor {
+            frames: vec![Frame::new(instructions, 1)],
+        }
+    }
+
+    fn next(&mut self) -> Step<'a> {
+        loop {
+            let Some(frame) = self.frames.last_mut() else {
+                return Step::Exit;
+            };
+            if frame.exec > 0 {
+                frame.exec -= 1;
+                return Step::Exec;
+            }
+            let Some(instruction) = frame.instructions.get(frame.index) else {
+                if frame.times > 1 {
+                    frame.times -= 1;
+                    frame.index = 0;
+                } else {
+                    self.frames.pop();
+                }
+                continue;
+            };
+            frame.index += 1;
+            match instruction {
+                Instruction::Exec(units) => frame.exec = *units,
+                Instruction::Sleep(units) => return Step::Syscall(Syscall::Sleep(*units)),
+                Instruction::Wait(event) => return Step::Syscall(Syscall::Wait(*event)),
+                Instruction::Signal(event) => return Step::Syscall(Syscall::Signal(*event)),
+                Instruction::Fork(child) => return Step::Fork(child),
+                Instruction::Repeat(repeat) => {
+                    if repeat.times > 0 {
+                        self.frames.push(Frame::new(&repeat.instructions, repeat.times));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Frame<'a> {
+    fn new(instructions: &'a [Instruction], times: usize) -> Frame<'a> {
+        Frame {
+            instructions,
+            index: 0,
+            times,
+            exec: 0,
+        }
+    }
+}
+
+/// Runs a simulation of `scenario` on the calling thread.
+///
+/// ## Panics
+///
+/// Panics if the scheduler rejects the priority of a process, like
+/// [`Process::fork`](crate::Process::fork).
+pub(crate) fn run<S: Scheduler>(scheduler: S, scenario: &Scenario) -> Vec<Log> {
+    let mut session = Session::new(scheduler);
+    let mut processes = HashMap::new();
+    processes.insert(Pid::new(1), Cursor::new(&scenario.instructions));
+
+    while let Some(pid) = session.running() {
+        // a scheduler can only run the processes that it has created
+        let step = processes.get_mut(&pid).map_or(Step::Exit, Cursor::next);
+        match step {
+            Step::Exec => session.exec(),
+            Step::Syscall(syscall) => {
+                session.syscall(syscall);
+            }
+            Step::Fork(child) => {
+                let priority = Priority::new(child.priority);
+                match session.syscall(Syscall::Fork(priority)) {
+                    SyscallResult::Pid(child_pid) => {
+                        processes.insert(child_pid, Cursor::new(&child.instructions));
+                    }
+                    SyscallResult::InvalidPriority => panic!("The scheduler rejected priority {priority}"),
+                    _ => panic!("Fork did not return a pid"),
+                }
+            }
+            Step::Exit => {
+                processes.remove(&pid);
+                session.syscall(Syscall::Exit);
+            }
+        }
+    }
+    session.into_logs()
+}
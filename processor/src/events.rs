@@ -0,0 +1,134 @@
+//! Present the [`Processor`](crate::Processor)'s logs as a stream of changes.
+
+use scheduler::{Pid, SchedulingDecision, StopReason, SyscallResult};
+
+use crate::{Log, ProcessInfo};
+
+/// A change that happened during an iteration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LogEvent<'a> {
+    /// The scheduler has taken a decision.
+    Decision {
+        /// The iteration, starting from 0.
+        iteration: usize,
+
+        /// The decision of the scheduler.
+        decision: SchedulingDecision,
+    },
+
+    /// A process was listed differently than in the previous iteration.
+    ProcessChanged {
+        /// The iteration, starting from 0.
+        iteration: usize,
+
+        /// The PID of the process.
+        pid: Pid,
+
+        /// The process in the previous iteration, [`None`] if
+        /// the process was not listed.
+        before: Option<&'a ProcessInfo>,
+
+        /// The process in this iteration, [`None`] if the process
+        /// is not listed anymore.
+        after: Option<&'a ProcessInfo>,
+    },
+
+    /// The running process has stopped.
+    Stopped {
+        /// The iteration, starting from 0.
+        iteration: usize,
+
+        /// The reason the process has stopped.
+        reason: StopReason,
+
+        /// The result returned to the process.
+        result: SyscallResult,
+    },
+}
+
+impl LogEvent<'_> {
+    /// Returns the iteration (starting from 0) of the event.
+    pub fn iteration(&self) -> usize {
+        match self {
+            LogEvent::Decision { iteration, .. }
+            | LogEvent::ProcessChanged { iteration, .. }
+            | LogEvent::Stopped { iteration, .. } => *iteration,
+        }
+    }
+}
+
+/// Returns the events of an iteration, compared to the previous one.
+fn changes<'a>(iteration: usize, previous: Option<&'a Log>, log: &'a Log) -> Vec<LogEvent<'a>> {
+    let mut events = vec![LogEvent::Decision {
+        iteration,
+        decision: log.decision,
+    }];
+
+    let mut pids = log
+        .processes
+        .keys()
+        .chain(previous.iter().flat_map(|previous| previous.processes.keys()))
+        .collect::<Vec<&Pid>>();
+    pids.sort();
+    pids.dedup();
+    for pid in pids {
+        let before = previous.and_then(|previous| previous.processes.get(pid));
+        let after = log.processes.get(pid);
+        if before != after {
+            events.push(LogEvent::ProcessChanged {
+                iteration,
+                pid: *pid,
+                before,
+                after,
+            });
+        }
+    }
+
+    if let Some((reason, result)) = log.stop_reason {
+        events.push(LogEvent::Stopped {
+            iteration,
+            reason,
+            result,
+        });
+    }
+    events
+}
+
+/// Returns the changes between consecutive iterations as a stream of events.
+///
+/// For every iteration, the stream contains a [`LogEvent::Decision`], a
+/// [`LogEvent::ProcessChanged`] for every process (sorted by PID) that
+/// is different from the previous iteration and, if the running process
+/// has stopped, a [`LogEvent::Stopped`]. All the processes listed in the
+/// first iteration are reported as changed.
+///
+/// The events are computed while the stream is consumed.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{events, LogEvent, Processor};
+/// use scheduler::{Pid, ProcessState};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     process.fork(|process| process.exec(), 0);
+///     process.sleep(3);
+/// });
+///
+/// let created = events(&logs).find_map(|event| match event {
+///     LogEvent::ProcessChanged { pid, before: None, after: Some(after), .. } if pid == Pid::new(2) => {
+///         Some(after.state)
+///     }
+///     _ => None,
+/// });
+/// assert_eq!(created, Some(ProcessState::Ready));
+/// ```
+pub fn events(logs: &[Log]) -> impl Iterator<Item = LogEvent<'_>> {
+    logs.iter().enumerate().flat_map(move |(iteration, log)| {
+        let previous = iteration.checked_sub(1).map(|previous| &logs[previous]);
+        changes(iteration, previous, log)
+    })
+}
@@ -0,0 +1,283 @@
+//! Export the [`Processor`](crate::Processor)'s logs to formats
+//! understood by external tools.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use scheduler::{Event, ProcessState, SchedulingDecision, StopReason, Syscall, SyscallResult};
+
+use crate::json;
+use crate::timeline::{self, Activity};
+use crate::Log;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::sqlite;
+
+/// Returns `value` as a quoted and escaped JSON string, for tools that
+/// write their own JSON next to the exports.
+///
+/// * `value` - the text of the string.
+pub fn json_string(value: &str) -> String {
+    json::string(value)
+}
+
+/// Export the logs as a JSON array with an object for every iteration,
+/// the objects written by [`SinkFormat::Json`](crate::SinkFormat::Json).
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Processor;
+/// use processor::export;
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| process.exec());
+///
+/// let json = export::json(&logs);
+/// assert!(json.starts_with("[{") && json.ends_with("}]"));
+/// ```
+pub fn json(logs: &[Log]) -> String {
+    let iterations = logs
+        .iter()
+        .enumerate()
+        .map(|(iteration, log)| json::log(iteration, log))
+        .collect::<Vec<_>>();
+    format!("[{}]", iterations.join(","))
+}
+
+/// The thread id used for the idle (sleep) track.
+const IDLE_TID: usize = 0;
+
+/// Export the logs as a Chrome `trace_event` JSON document.
+///
+/// The document can be loaded in `chrome://tracing` or in the
+/// [Perfetto UI](https://ui.perfetto.dev). Every process is displayed
+/// as a separate track and every time it runs becomes a duration event.
+/// The time the processor spends sleeping is displayed on an `Idle` track.
+///
+/// One virtual time unit is exported as one microsecond.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Processor;
+/// use processor::export::chrome_trace;
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     process.exec();
+///     process.sleep(2);
+/// });
+///
+/// std::fs::write(std::env::temp_dir().join("trace.json"), chrome_trace(&logs)).unwrap();
+/// ```
+pub fn chrome_trace(logs: &[Log]) -> String {
+    let segments = timeline::segments(logs);
+    let mut events = vec![];
+
+    let mut pids = logs
+        .iter()
+        .flat_map(|log| log.processes.keys())
+        .collect::<Vec<_>>();
+    pids.sort();
+    pids.dedup();
+    events.push(thread_name(IDLE_TID, "Idle"));
+    for pid in pids {
+        events.push(thread_name(pid.get(), &format!("PID {pid}")));
+    }
+
+    for segment in &segments {
+        let (tid, name, stop) = match segment.activity {
+            Activity::Run { pid, stop_reason } => {
+                (pid.get(), format!("Run {pid}"), stop_reason.to_string())
+            }
+            Activity::Sleep => (IDLE_TID, String::from("Sleep"), String::new()),
+        };
+        events.push(format!(
+            "{{\"name\":{},\"cat\":\"scheduler\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":0,\"tid\":{},\"args\":{{\"iteration\":{},\"stop\":{}}}}}",
+            json::string(&name),
+            segment.start,
+            segment.duration(),
+            tid,
+            segment.iteration + 1,
+            json::string(&stop)
+        ));
+    }
+
+    if let Some(log) = logs.last() {
+        if !matches!(
+            log.decision,
+            SchedulingDecision::Run { .. } | SchedulingDecision::Sleep(_)
+        ) {
+            let end = segments.last().map_or(0, |segment| segment.end);
+            events.push(format!(
+                "{{\"name\":{},\"cat\":\"scheduler\",\"ph\":\"i\",\"s\":\"g\",\"ts\":{},\"pid\":0,\"tid\":{}}}",
+                json::string(&log.decision.to_string()),
+                end,
+                IDLE_TID
+            ));
+        }
+    }
+
+    let mut s = String::from("{\"displayTimeUnit\":\"ms\",\"traceEvents\":[\n");
+    for (index, event) in events.iter().enumerate() {
+        let separator = if index + 1 < events.len() { "," } else { "" };
+        writeln!(s, "{event}{separator}").unwrap();
+    }
+    s.push_str("]}\n");
+    s
+}
+
+fn thread_name(tid: usize, name: &str) -> String {
+    format!(
+        "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":{},\"args\":{{\"name\":{}}}}}",
+        tid,
+        json::string(name)
+    )
+}
+
+/// The kind of an edge in the graph exported by [`dot`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Edge {
+    Fork(usize),
+    Wait(Event),
+    Signal(Event),
+}
+
+/// Returns the identifier of the node of an event in the graph
+/// exported by [`dot`].
+fn event_node(event: &Event) -> String {
+    format!("e{}", event.to_string().replace(':', "_"))
+}
+
+/// Export the relations between processes and events as a GraphViz DOT graph.
+///
+/// Processes are displayed as boxes and events as ellipses. The graph contains:
+/// * the fork tree, with an edge from every parent to its children;
+/// * a dashed edge from a process to every event it has waited for;
+/// * a dotted edge from a process to every event it has signaled.
+///
+/// Edges that appear several times are merged and labeled with the
+/// number of occurrences. Processes that have exited are grayed out,
+/// while processes that were still waiting when the simulation ended
+/// (together with the events they were waiting for) are highlighted in
+/// red, which helps explaining deadlocks.
+///
+/// The graph can be rendered with `dot -Tsvg graph.dot -o graph.svg`.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Processor;
+/// use processor::export::dot;
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     process.fork(|process| process.signal(1), 0);
+///     process.wait(1);
+/// });
+///
+/// assert!(dot(&logs).starts_with("digraph scheduler {"));
+/// ```
+pub fn dot(logs: &[Log]) -> String {
+    let mut pids = BTreeSet::new();
+    let mut exited = BTreeSet::new();
+    let mut events = BTreeSet::new();
+    let mut edges = BTreeMap::new();
+
+    for log in logs {
+        pids.extend(log.processes.keys().map(|pid| pid.get()));
+        let (
+            SchedulingDecision::Run { pid, .. },
+            Some((StopReason::Syscall { syscall, .. }, result)),
+        ) = (log.decision, log.stop_reason)
+        else {
+            continue;
+        };
+        let edge = match (syscall, result) {
+            (Syscall::Fork(_), SyscallResult::Pid(child)) => Edge::Fork(child.get()),
+            (Syscall::Wait(event), _) => Edge::Wait(event),
+            (Syscall::Signal(event), _) => Edge::Signal(event),
+            (Syscall::Exit, _) => {
+                exited.insert(pid.get());
+                continue;
+            }
+            _ => continue,
+        };
+        if let Edge::Wait(event) | Edge::Signal(event) = edge {
+            events.insert(event);
+        }
+        *edges.entry((pid.get(), edge)).or_insert(0) += 1;
+    }
+
+    let mut blocked = BTreeSet::new();
+    let mut blocking = BTreeSet::new();
+    if let Some(log) = logs.last() {
+        for (pid, process) in &log.processes {
+            if let ProcessState::Waiting { event: Some(event) } = process.state {
+                blocked.insert(pid.get());
+                blocking.insert(event);
+            }
+        }
+    }
+
+    let mut s = String::from("digraph scheduler {\n    rankdir=LR;\n");
+    if let Some(log) = logs.last() {
+        writeln!(
+            s,
+            "    label={};\n    labelloc=t;",
+            json::string(&log.decision.to_string())
+        )
+        .unwrap();
+    }
+    for pid in &pids {
+        let style = if blocked.contains(pid) {
+            ", color=red, fontcolor=red, penwidth=2"
+        } else if exited.contains(pid) {
+            ", style=filled, fillcolor=lightgray"
+        } else {
+            ""
+        };
+        writeln!(s, "    p{pid} [shape=box, label=\"PID {pid}\"{style}];").unwrap();
+    }
+    for event in &events {
+        let style = if blocking.contains(event) {
+            ", color=red, fontcolor=red, penwidth=2"
+        } else {
+            ""
+        };
+        writeln!(
+            s,
+            "    {} [shape=ellipse, label=\"event {event}\"{style}];",
+            event_node(event)
+        )
+        .unwrap();
+    }
+    for ((pid, edge), count) in &edges {
+        let (target, name, style) = match edge {
+            Edge::Fork(child) => (format!("p{child}"), "fork", ""),
+            Edge::Wait(event) if blocked.contains(pid) && blocking.contains(event) => {
+                (event_node(event), "wait", ", style=dashed, color=red")
+            }
+            Edge::Wait(event) => (event_node(event), "wait", ", style=dashed"),
+            Edge::Signal(event) => (event_node(event), "signal", ", style=dotted, color=blue"),
+        };
+        let label = if *count > 1 {
+            format!("{name} ({count})")
+        } else {
+            name.to_string()
+        };
+        writeln!(s, "    p{pid} -> {target} [label=\"{label}\"{style}];").unwrap();
+    }
+    s.push_str("}\n");
+    s
+}
@@ -0,0 +1,203 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+use scheduler::{ProcessState, SchedulingDecision};
+
+use crate::metrics::Metrics;
+use crate::timeline;
+use crate::Log;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run TEXT PRIMARY KEY,
+    iterations INTEGER NOT NULL,
+    makespan INTEGER NOT NULL,
+    busy INTEGER NOT NULL,
+    idle INTEGER NOT NULL,
+    context_switches INTEGER NOT NULL,
+    outcome TEXT
+);
+CREATE TABLE IF NOT EXISTS iterations (
+    run TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    start INTEGER NOT NULL,
+    end INTEGER NOT NULL,
+    stop_reason TEXT,
+    result TEXT,
+    PRIMARY KEY (run, iteration)
+);
+CREATE TABLE IF NOT EXISTS decisions (
+    run TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    decision TEXT NOT NULL,
+    pid INTEGER,
+    amount INTEGER,
+    PRIMARY KEY (run, iteration)
+);
+CREATE TABLE IF NOT EXISTS processes (
+    run TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    pid INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    event INTEGER,
+    priority INTEGER NOT NULL,
+    total INTEGER NOT NULL,
+    syscall INTEGER NOT NULL,
+    execute INTEGER NOT NULL,
+    extra TEXT NOT NULL,
+    PRIMARY KEY (run, iteration, pid)
+);
+CREATE TABLE IF NOT EXISTS process_metrics (
+    run TEXT NOT NULL,
+    pid INTEGER NOT NULL,
+    priority INTEGER NOT NULL,
+    arrival INTEGER NOT NULL,
+    first_run INTEGER,
+    completion INTEGER,
+    runs INTEGER NOT NULL,
+    running INTEGER NOT NULL,
+    ready INTEGER NOT NULL,
+    waiting INTEGER NOT NULL,
+    PRIMARY KEY (run, pid)
+);
+";
+
+/// Export the logs and the metrics of a simulation to an SQLite database.
+///
+/// The database is created if it does not exist. Several simulations can
+/// be stored in the same database, every one of them identified by `run`,
+/// which allows comparing schedulers and parameters using SQL. Exporting
+/// a `run` that already exists replaces it.
+///
+/// The database contains the following tables:
+/// * `runs` - the [`Metrics`] of every simulation;
+/// * `iterations` - the interval of virtual time `[start, end)` covered
+///   by every iteration and the reason the running process has stopped;
+/// * `decisions` - the decision of the scheduler for every iteration;
+/// * `processes` - the process table of every iteration;
+/// * `process_metrics` - the [`ProcessMetrics`](crate::metrics::ProcessMetrics)
+///   of every process.
+///
+/// Iterations are numbered starting from 1, like in the output
+/// of [`format_logs`](crate::format_logs).
+///
+/// This function is available only if the `sqlite` feature is enabled.
+///
+/// * `path` - the path of the database.
+/// * `run` - the name of the simulation.
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+/// * `metrics` - the statistics computed from the same logs.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::metrics::Metrics;
+/// use processor::{export, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     process.fork(|process| process.exec(), 0);
+///     process.sleep(3);
+/// });
+///
+/// let path = std::env::temp_dir().join("sweep.db");
+/// export::sqlite(&path, "round-robin-2", &logs, &Metrics::new(&logs)).unwrap();
+/// ```
+pub fn sqlite(
+    path: impl AsRef<Path>,
+    run: &str,
+    logs: &[Log],
+    metrics: &Metrics,
+) -> rusqlite::Result<()> {
+    let mut connection = Connection::open(path)?;
+    connection.execute_batch(SCHEMA)?;
+
+    let transaction = connection.transaction()?;
+    for table in ["runs", "iterations", "decisions", "processes", "process_metrics"] {
+        transaction.execute(&format!("DELETE FROM {table} WHERE run = ?1"), [run])?;
+    }
+
+    transaction.execute(
+        "INSERT INTO runs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            run,
+            metrics.iterations,
+            metrics.makespan,
+            metrics.busy,
+            metrics.idle,
+            metrics.context_switches,
+            metrics.outcome.map(|outcome| format!("{outcome:?}")),
+        ],
+    )?;
+
+    {
+        let mut iteration_row =
+            transaction.prepare("INSERT INTO iterations VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+        let mut decision_row =
+            transaction.prepare("INSERT INTO decisions VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        let mut process_row = transaction.prepare(
+            "INSERT INTO processes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+        let times = timeline::iteration_times(logs);
+        for (iteration, (log, (start, end))) in logs.iter().zip(times).enumerate() {
+            let (name, pid, amount) = match log.decision {
+                SchedulingDecision::Run { pid, timeslice } => {
+                    ("Run", Some(pid.get()), Some(timeslice.get()))
+                }
+                SchedulingDecision::Sleep(amount) => ("Sleep", None, Some(amount.get())),
+                SchedulingDecision::Deadlock => ("Deadlock", None, None),
+                SchedulingDecision::Panic => ("Panic", None, None),
+                SchedulingDecision::Done => ("Done", None, None),
+            };
+            iteration_row.execute(params![
+                run,
+                iteration + 1,
+                start,
+                end,
+                log.stop_reason.map(|(reason, _)| reason.to_string()),
+                log.stop_reason.map(|(_, result)| format!("{result:?}")),
+            ])?;
+            decision_row.execute(params![run, iteration + 1, name, pid, amount])?;
+
+            for info in log.processes.values() {
+                let event = match info.state {
+                    // user events are stored as numbers, the other ones
+                    // keep the prefix of their namespace
+                    ProcessState::Waiting { event } => event.map(|event| event.to_string()),
+                    _ => None,
+                };
+                process_row.execute(params![
+                    run,
+                    iteration + 1,
+                    info.pid.get(),
+                    info.state.to_string(),
+                    event,
+                    info.priority.get(),
+                    info.timings.total,
+                    info.timings.syscall,
+                    info.timings.execution,
+                    info.extra,
+                ])?;
+            }
+        }
+
+        let mut metrics_row = transaction.prepare(
+            "INSERT INTO process_metrics VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+        for metrics in &metrics.processes {
+            metrics_row.execute(params![
+                run,
+                metrics.pid.get(),
+                metrics.priority.get(),
+                metrics.arrival,
+                metrics.first_run,
+                metrics.completion,
+                metrics.runs,
+                metrics.running,
+                metrics.ready,
+                metrics.waiting,
+            ])?;
+        }
+    }
+    transaction.commit()
+}
@@ -0,0 +1,619 @@
+//! Format the [`Processor`](crate::Processor)'s logs.
+//!
+//! The plain format produced by [`format_logs`] is the one used
+//! by the reference outputs and should not be changed.
+
+use std::fmt::{self, Display, Write};
+
+use scheduler::{Pid, ProcessState, SchedulingDecision};
+
+use crate::compress::{self, LogEntry};
+use crate::metrics::Metrics;
+use crate::timeline;
+use crate::{Log, ProcessInfo};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const MAGENTA: &str = "\x1b[35m";
+const CYAN: &str = "\x1b[36m";
+
+pub(crate) const HEADER: [&str; 7] = ["PID", "STATE", "PRI", "TOTAL", "SYSCALL", "EXECUTE", "EXTRA"];
+
+/// A column of the process table.
+///
+/// The `PID` column is always displayed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The PID of the parent process, `-` if the scheduler does not
+    /// report it. It is not displayed by default.
+    Parent,
+    State,
+    Priority,
+    Total,
+    Syscall,
+    Execute,
+    Extra,
+}
+
+impl Column {
+    /// The columns displayed by [`format_logs`], in order.
+    pub const ALL: [Column; 6] = [
+        Column::State,
+        Column::Priority,
+        Column::Total,
+        Column::Syscall,
+        Column::Execute,
+        Column::Extra,
+    ];
+
+    /// Returns the value of the column for a process, without any styling.
+    pub(crate) fn cell(&self, process: &ProcessInfo) -> String {
+        match self {
+            Column::Parent => match process.parent {
+                Some(parent) => parent.to_string(),
+                None => String::from("-"),
+            },
+            Column::State => process.state.to_string(),
+            Column::Priority => process.priority.to_string(),
+            Column::Total => process.timings.total.to_string(),
+            Column::Syscall => process.timings.syscall.to_string(),
+            Column::Execute => process.timings.execution.to_string(),
+            Column::Extra => process.extra.clone(),
+        }
+    }
+}
+
+impl Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Column::Parent => "PPID",
+            Column::State => "STATE",
+            Column::Priority => "PRI",
+            Column::Total => "TOTAL",
+            Column::Syscall => "SYSCALL",
+            Column::Execute => "EXECUTE",
+            Column::Extra => "EXTRA",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Options accepted by the formatters.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{Column, FormatOptions, LogFormatter, PrettyFormatter};
+///
+/// // hide the `EXTRA` column
+/// let formatter = PrettyFormatter::new(FormatOptions::new().hide(Column::Extra));
+///
+/// // show only the total time
+/// let formatter = PrettyFormatter::new(FormatOptions::new().columns([Column::State, Column::Total]));
+///
+/// // show the parent PID before the other columns
+/// let columns = [Column::Parent].into_iter().chain(Column::ALL);
+/// let formatter = PrettyFormatter::new(FormatOptions::new().columns(columns));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    columns: Vec<Column>,
+}
+
+impl FormatOptions {
+    /// Creates options that display the columns in [`Column::ALL`].
+    pub fn new() -> FormatOptions {
+        FormatOptions {
+            columns: Column::ALL.to_vec(),
+        }
+    }
+
+    /// Display only `columns`, in the given order.
+    ///
+    /// * `columns` - the columns displayed after the `PID` column.
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Column>) -> FormatOptions {
+        self.columns = columns.into_iter().collect();
+        self
+    }
+
+    /// Do not display a column.
+    ///
+    /// * `column` - the hidden column.
+    pub fn hide(mut self, column: Column) -> FormatOptions {
+        self.columns.retain(|other| *other != column);
+        self
+    }
+
+    /// Returns the displayed columns, in order, without the `PID` column.
+    pub fn displayed(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Returns the titles of the displayed columns, including `PID`.
+    fn header(&self) -> Vec<String> {
+        let mut header = vec![String::from("PID")];
+        header.extend(self.columns.iter().map(Column::to_string));
+        header
+    }
+
+    /// Returns the displayed cells of a process, including the `PID`.
+    fn row(&self, process: &ProcessInfo) -> Vec<String> {
+        let mut row = vec![process.pid.to_string()];
+        row.extend(self.columns.iter().map(|column| column.cell(process)));
+        row
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions::new()
+    }
+}
+
+/// Formats the [`Processor`](crate::Processor)'s logs.
+///
+/// All the methods have default implementations that produce the
+/// plain format used by [`format_logs`], so a formatter only needs
+/// to override the parts it wants to change.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{Log, LogFormatter};
+///
+/// struct Decisions;
+///
+/// impl LogFormatter for Decisions {
+///     fn format_iteration(&self, log: &Log, iteration: usize) -> String {
+///         format!("{}: {}\n", iteration + 1, log.decision)
+///     }
+/// }
+///
+/// assert_eq!(Decisions.format_logs(&[]), "");
+/// ```
+pub trait LogFormatter {
+    /// Format a single iteration.
+    ///
+    /// * `log` - the iteration's log.
+    /// * `iteration` - the iteration number, starting from 0.
+    fn format_iteration(&self, log: &Log, iteration: usize) -> String {
+        format!("===== Iteration: {} =====\n{}\n", iteration + 1, log)
+    }
+
+    /// Format a section that is added after all the iterations.
+    ///
+    /// * `logs` - all the logs of the simulation.
+    fn format_summary(&self, logs: &[Log]) -> String {
+        let _ = logs;
+        String::new()
+    }
+
+    /// Format all the logs of a simulation.
+    ///
+    /// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+    fn format_logs(&self, logs: &[Log]) -> String {
+        let mut s = String::new();
+        for (iteration, log) in logs.iter().enumerate() {
+            s.push_str(&self.format_iteration(log, iteration));
+        }
+        s.push_str(&self.format_summary(logs));
+        s
+    }
+}
+
+/// The [`LogFormatter`] used by [`format_logs`].
+///
+/// With the default [`FormatOptions`], the output is the one used
+/// by the reference outputs.
+#[derive(Debug, Default, Clone)]
+pub struct PlainFormatter {
+    options: FormatOptions,
+}
+
+impl PlainFormatter {
+    /// Creates a formatter that displays the columns selected by `options`.
+    pub fn new(options: FormatOptions) -> PlainFormatter {
+        PlainFormatter { options }
+    }
+}
+
+/// Returns a row of the plain format, where the `STATE`
+/// column is followed by two tabs.
+///
+/// * `columns` - the displayed columns, without the `PID` column.
+/// * `cells` - the cells of the row, starting with the `PID` column.
+fn plain_row(columns: &[Column], cells: Vec<String>) -> String {
+    let mut cells = cells.into_iter();
+    let mut line = cells.next().unwrap_or_default();
+    let mut previous = None;
+    for (column, cell) in columns.iter().zip(cells) {
+        line.push_str(if previous == Some(Column::State) { "\t\t" } else { "\t" });
+        line.push_str(&cell);
+        previous = Some(*column);
+    }
+    line
+}
+
+impl LogFormatter for PlainFormatter {
+    fn format_iteration(&self, log: &Log, iteration: usize) -> String {
+        let columns = self.options.displayed();
+        let mut s = String::new();
+        writeln!(s, "===== Iteration: {} =====", iteration + 1).unwrap();
+        writeln!(s, "{}", log.decision).unwrap();
+        writeln!(s, "{}", plain_row(columns, self.options.header())).unwrap();
+        for process in sorted(log) {
+            writeln!(s, "{}", plain_row(columns, self.options.row(process))).unwrap();
+        }
+        if let Some((reason, result)) = log.stop_reason {
+            writeln!(s, "{reason} -> {result:?}").unwrap();
+        }
+        writeln!(s).unwrap();
+        writeln!(s).unwrap();
+        s
+    }
+}
+
+/// Format the [`Processor`](crate::Processor)'s logs to a [`String`].
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Processor;
+/// use std::num::NonZeroUsize;
+/// use processor::format_logs;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     /* ... */
+/// });
+///
+/// println!("{}", format_logs(&logs));
+/// ```
+pub fn format_logs(logs: &[Log]) -> String {
+    PlainFormatter::default().format_logs(logs)
+}
+
+/// Format a run of `count` iterations, starting from `first`, using the plain format.
+fn format_run(first: usize, count: usize, log: &Log) -> String {
+    if count == 1 {
+        PlainFormatter::default().format_iteration(log, first)
+    } else {
+        format!(
+            "===== Iterations: {}-{} (repeated {} times) =====\n{}\n",
+            first + 1,
+            first + count,
+            count,
+            log
+        )
+    }
+}
+
+/// Format the [`Processor`](crate::Processor)'s logs like [`format_logs`],
+/// collapsing consecutive iterations that differ only by the process timings.
+///
+/// Collapsed iterations are displayed once, with the timings of the
+/// last iteration. Use [`format_logs`] for comparing outputs.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn format_logs_compressed(logs: &[Log]) -> String {
+    let mut s = String::new();
+    for run in compress::runs(logs) {
+        s.push_str(&format_run(run.start, run.len(), &logs[run.end - 1]));
+    }
+    s
+}
+
+/// Format the entries returned by [`compress`](crate::compress).
+///
+/// The output is the same as [`format_logs_compressed`] for the
+/// logs that were compressed.
+///
+/// * `entries` - the compressed logs.
+pub fn format_log_entries(entries: &[LogEntry]) -> String {
+    let mut s = String::new();
+    let mut first = 0;
+    for entry in entries {
+        s.push_str(&format_run(first, entry.count(), entry.log()));
+        first += entry.count();
+    }
+    s
+}
+
+/// Returns the cells of a process table row, without any styling.
+pub(crate) fn cells(process: &ProcessInfo) -> [String; 7] {
+    [
+        process.pid.to_string(),
+        process.state.to_string(),
+        process.priority.to_string(),
+        process.timings.total.to_string(),
+        process.timings.syscall.to_string(),
+        process.timings.execution.to_string(),
+        process.extra.clone(),
+    ]
+}
+
+/// Returns the processes of a log sorted by their PID.
+pub(crate) fn sorted(log: &Log) -> Vec<&ProcessInfo> {
+    let mut pids = log.processes.keys().collect::<Vec<&Pid>>();
+    pids.sort();
+    pids.into_iter().map(|pid| &log.processes[pid]).collect()
+}
+
+fn state_color(state: &ProcessState) -> Option<&'static str> {
+    match state {
+        ProcessState::Running => Some(GREEN),
+        ProcessState::Waiting { .. } => Some(YELLOW),
+        ProcessState::Stopped => Some(RED),
+        ProcessState::Zombie => Some(MAGENTA),
+        _ => None,
+    }
+}
+
+fn decision_color(decision: &SchedulingDecision) -> &'static str {
+    match decision {
+        SchedulingDecision::Run { .. } => GREEN,
+        SchedulingDecision::Sleep(_) => YELLOW,
+        SchedulingDecision::Deadlock | SchedulingDecision::Panic => RED,
+        SchedulingDecision::Done => CYAN,
+    }
+}
+
+/// A [`LogFormatter`] for terminals.
+///
+/// The process table columns are padded to the width of their
+/// longest value, process states are colored (running processes
+/// are green, waiting processes are yellow) and stop reasons are
+/// highlighted using ANSI escape codes.
+#[derive(Debug, Default, Clone)]
+pub struct PrettyFormatter {
+    options: FormatOptions,
+}
+
+impl PrettyFormatter {
+    /// Creates a formatter that displays the columns selected by `options`.
+    pub fn new(options: FormatOptions) -> PrettyFormatter {
+        PrettyFormatter { options }
+    }
+}
+
+impl LogFormatter for PrettyFormatter {
+    fn format_iteration(&self, log: &Log, iteration: usize) -> String {
+        let mut s = String::new();
+        writeln!(s, "{BOLD}===== Iteration: {} ====={RESET}", iteration + 1).unwrap();
+        writeln!(
+            s,
+            "{BOLD}{}{}{RESET}",
+            decision_color(&log.decision),
+            log.decision
+        )
+        .unwrap();
+
+        let processes = sorted(log);
+        let header = self.options.header();
+        let rows = processes
+            .iter()
+            .map(|process| self.options.row(process))
+            .collect::<Vec<_>>();
+        let mut widths = header.iter().map(String::len).collect::<Vec<_>>();
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut line = String::new();
+        for (column, title) in header.iter().enumerate() {
+            write!(line, "{:<width$}  ", title, width = widths[column]).unwrap();
+        }
+        writeln!(s, "{BOLD}{}{RESET}", line.trim_end()).unwrap();
+
+        for (process, row) in processes.iter().zip(&rows) {
+            let mut line = String::new();
+            for (column, cell) in row.iter().enumerate() {
+                let padded = format!("{:<width$}", cell, width = widths[column]);
+                let state = column > 0 && self.options.displayed()[column - 1] == Column::State;
+                match (state, state_color(&process.state)) {
+                    (true, Some(color)) => write!(line, "{color}{padded}{RESET}  ").unwrap(),
+                    _ => write!(line, "{padded}  ").unwrap(),
+                }
+            }
+            writeln!(s, "{}", line.trim_end()).unwrap();
+        }
+
+        if let Some((reason, result)) = log.stop_reason {
+            writeln!(s, "{MAGENTA}{reason}{RESET} -> {BOLD}{result:?}{RESET}").unwrap();
+        }
+        writeln!(s).unwrap();
+        s
+    }
+}
+
+/// Format the [`Processor`](crate::Processor)'s logs for a terminal
+/// using the [`PrettyFormatter`].
+///
+/// Use [`format_logs`] for comparing outputs.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn format_logs_pretty(logs: &[Log]) -> String {
+    PrettyFormatter::default().format_logs(logs)
+}
+
+/// Escape the characters that have a special meaning inside a Markdown table cell.
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+/// A [`LogFormatter`] that writes GitHub-flavored Markdown.
+///
+/// Every iteration becomes a section with the scheduling decision,
+/// a table with the processes and the reason the running process stopped.
+#[derive(Debug, Default, Clone)]
+pub struct MarkdownFormatter {
+    options: FormatOptions,
+}
+
+impl MarkdownFormatter {
+    /// Creates a formatter that displays the columns selected by `options`.
+    pub fn new(options: FormatOptions) -> MarkdownFormatter {
+        MarkdownFormatter { options }
+    }
+}
+
+impl LogFormatter for MarkdownFormatter {
+    fn format_iteration(&self, log: &Log, iteration: usize) -> String {
+        let mut s = String::new();
+        writeln!(s, "### Iteration {}\n", iteration + 1).unwrap();
+        writeln!(s, "**{}**\n", log.decision).unwrap();
+
+        writeln!(s, "| {} |", self.options.header().join(" | ")).unwrap();
+        let mut separator = String::from("| --- |");
+        for column in self.options.displayed() {
+            match column {
+                Column::State | Column::Extra => separator.push_str(" --- |"),
+                _ => separator.push_str(" ---: |"),
+            }
+        }
+        writeln!(s, "{separator}").unwrap();
+        for process in sorted(log) {
+            let row = self
+                .options
+                .row(process)
+                .iter()
+                .map(|cell| markdown_cell(cell))
+                .collect::<Vec<_>>();
+            writeln!(s, "| {} |", row.join(" | ")).unwrap();
+        }
+
+        if let Some((reason, result)) = log.stop_reason {
+            writeln!(s, "\nStopped: `{reason}` → `{result:?}`").unwrap();
+        }
+        writeln!(s).unwrap();
+        s
+    }
+}
+
+/// Format the [`Processor`](crate::Processor)'s logs as GitHub-flavored
+/// Markdown using the [`MarkdownFormatter`].
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn format_logs_markdown(logs: &[Log]) -> String {
+    MarkdownFormatter::default().format_logs(logs)
+}
+
+/// A [`LogFormatter`] that uses the plain format and appends a summary.
+///
+/// The summary contains the last timings of every process, the number
+/// of context switches, the time the processor was idle and the
+/// last decision of the scheduler (see [`Metrics`]).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SummaryFormatter;
+
+impl LogFormatter for SummaryFormatter {
+    fn format_summary(&self, logs: &[Log]) -> String {
+        summary(&Metrics::new(logs))
+    }
+}
+
+/// Format the summary section of the [`SummaryFormatter`].
+fn summary(metrics: &Metrics) -> String {
+    let mut s = String::from("===== Summary =====\n");
+    if let Some(outcome) = metrics.outcome {
+        writeln!(s, "Outcome: {outcome}").unwrap();
+    }
+    writeln!(s, "Iterations: {}", metrics.iterations).unwrap();
+    writeln!(s, "Makespan: {}", metrics.makespan).unwrap();
+    writeln!(s, "Idle: {}", metrics.idle).unwrap();
+    writeln!(s, "Context switches: {}", metrics.context_switches).unwrap();
+    if let Some(stats) = metrics.scheduler {
+        writeln!(
+            s,
+            "Scheduler: {} context switches, {} preemptions, {} idle, {} wakeups",
+            stats.context_switches, stats.preemptions, stats.idle, stats.wakeups
+        )
+        .unwrap();
+    }
+    writeln!(s, "PID\tPRI\tTOTAL\tSYSCALL\tEXECUTE\tRUNS\tREADY\tWAITING\tTURNAROUND").unwrap();
+    for process in &metrics.processes {
+        writeln!(
+            s,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            process.pid,
+            process.priority,
+            process.timings.total,
+            process.timings.syscall,
+            process.timings.execution,
+            process.runs,
+            process.ready,
+            process.waiting,
+            process
+                .turnaround()
+                .map_or(String::from("-"), |turnaround| turnaround.to_string())
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Format the [`Processor`](crate::Processor)'s logs like
+/// [`format_logs`] and append a summary using the [`SummaryFormatter`].
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn format_logs_with_summary(logs: &[Log]) -> String {
+    SummaryFormatter.format_logs(logs)
+}
+
+/// Format the [`Processor`](crate::Processor)'s logs grouped by process.
+///
+/// For every process, this lists the states it went through. Consecutive
+/// iterations where the process had the same state are merged into a
+/// single line showing the range of iterations (starting from 1) and
+/// the range of virtual time `[start, end)`.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn format_logs_by_process(logs: &[Log]) -> String {
+    let times = timeline::iteration_times(logs);
+    let mut pids = logs
+        .iter()
+        .flat_map(|log| log.processes.keys())
+        .collect::<Vec<&Pid>>();
+    pids.sort();
+    pids.dedup();
+
+    let mut s = String::new();
+    for pid in pids {
+        writeln!(s, "===== PID {pid} =====").unwrap();
+        writeln!(s, "ITERATIONS\tTIME\t\tSTATE").unwrap();
+        let mut current: Option<(usize, usize, ProcessState)> = None;
+        let mut ranges = vec![];
+        for (iteration, log) in logs.iter().enumerate() {
+            match (log.processes.get(pid), current) {
+                (Some(process), Some((first, _, state))) if process.state == state => {
+                    current = Some((first, iteration, state));
+                }
+                (process, _) => {
+                    ranges.extend(current.take());
+                    current = process.map(|process| (iteration, iteration, process.state));
+                }
+            }
+        }
+        ranges.extend(current);
+        for (first, last, state) in ranges {
+            writeln!(
+                s,
+                "{}-{}\t\t{}-{}\t\t{}",
+                first + 1,
+                last + 1,
+                times[first].0,
+                times[last].1,
+                state
+            )
+            .unwrap();
+        }
+        writeln!(s).unwrap();
+    }
+    s
+}
@@ -0,0 +1,124 @@
+//! The metadata written as comment lines at the start of recorded logs,
+//! like the scheduler and the parameters that produced them.
+//!
+//! Every line of a header is `# key: value`. [`parse_logs`](crate::parse_logs)
+//! skips these lines, and [`split`] separates a header from the logs
+//! after it, so the logs can be compared with a simulation.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use processor::header::{self, Header};
+//!
+//! let header = Header::new().with("scheduler", "round-robin").with("timeslice", 3);
+//! let text = format!("{header}===== Iteration: 1 =====\nDone, no more processes\n");
+//! assert!(text.starts_with("# scheduler: round-robin\n# timeslice: 3\n"));
+//!
+//! let (parsed, logs) = header::split(&text);
+//! assert_eq!(parsed, header);
+//! assert_eq!(parsed.get("timeslice"), Some("3"));
+//! assert!(logs.starts_with("===== Iteration: 1 ====="));
+//! assert_eq!(processor::parse_logs(&text).unwrap().len(), 1);
+//! ```
+
+use std::fmt::{self, Display};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The version of the processor crate, for the `version` of a header.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The key of the field that changes every time the logs are written,
+/// ignored by [`Header::same_origin`].
+pub const DATE: &str = "date";
+
+/// The fields of a header, in the order they are written.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Header {
+    fields: Vec<(String, String)>,
+}
+
+impl Header {
+    /// A header without any field.
+    pub fn new() -> Header {
+        Header::default()
+    }
+
+    /// Sets the field `key`, replacing its previous value.
+    ///
+    /// * `key` - the name of the field, without spaces or `:`.
+    /// * `value` - the value of the field, on a single line.
+    pub fn with(mut self, key: &str, value: impl Display) -> Header {
+        let value = value.to_string();
+        match self.fields.iter_mut().find(|(name, _)| name == key) {
+            Some((_, previous)) => *previous = value,
+            None => self.fields.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    /// The value of the field `key`, if the header has one.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+    }
+
+    /// The fields of the header, in the order they are written.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns `true` if the header has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Returns `true` if both headers have the same fields, apart from
+    /// the [`DATE`].
+    pub fn same_origin(&self, other: &Header) -> bool {
+        let fields = |header: &Header| header.fields.iter().filter(|(key, _)| key != DATE).cloned().collect::<Vec<_>>();
+        fields(self) == fields(other)
+    }
+}
+
+/// A `# key: value` line for every field.
+impl Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.fields {
+            writeln!(f, "# {key}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Separates the comment lines at the start of `text` from the rest of it.
+/// The comments that are not `# key: value` are left out of the header.
+///
+/// * `text` - the contents of a log file, with or without a header.
+pub fn split(text: &str) -> (Header, &str) {
+    let mut header = Header::new();
+    let mut rest = text;
+    while let Some(comment) = rest.strip_prefix('#') {
+        let (line, next) = comment.split_once('\n').unwrap_or((comment, ""));
+        if let Some((key, value)) = line.split_once(':') {
+            header = header.with(key.trim(), value.trim());
+        }
+        rest = next;
+    }
+    (header, rest)
+}
+
+/// The current date in UTC, like `2024-05-31`, for the [`DATE`] of a header.
+pub fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    // the civil date of a number of days since 1970-01-01, from the
+    // algorithm of Howard Hinnant, with years starting in March
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
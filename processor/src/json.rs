@@ -0,0 +1,89 @@
+//! Minimal helpers for writing JSON by hand.
+
+use std::fmt::Write;
+
+use scheduler::{SchedulingDecision, StopReason};
+
+use crate::Log;
+
+/// Returns `value` as a quoted and escaped JSON string.
+pub(crate) fn string(value: &str) -> String {
+    let mut s = String::with_capacity(value.len() + 2);
+    s.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(s, "\\u{:04x}", c as u32).unwrap(),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
+
+/// Returns a log iteration as a JSON object.
+pub(crate) fn log(iteration: usize, log: &Log) -> String {
+    let mut s = format!("{{\"iteration\":{},\"decision\":", iteration + 1);
+    match log.decision {
+        SchedulingDecision::Run { pid, timeslice } => write!(
+            s,
+            "{{\"type\":\"Run\",\"pid\":{pid},\"timeslice\":{timeslice}}}"
+        ),
+        SchedulingDecision::Sleep(amount) => {
+            write!(s, "{{\"type\":\"Sleep\",\"amount\":{amount}}}")
+        }
+        decision => write!(s, "{{\"type\":{}}}", string(&format!("{decision:?}"))),
+    }
+    .unwrap();
+
+    s.push_str(",\"stop\":");
+    match log.stop_reason {
+        Some((StopReason::Syscall { syscall, remaining }, result)) => write!(
+            s,
+            "{{\"reason\":\"Syscall\",\"syscall\":{},\"remaining\":{remaining},\"result\":{}}}",
+            string(&format!("{syscall:?}")),
+            string(&format!("{result:?}"))
+        ),
+        Some((StopReason::Preempted { remaining }, result)) => write!(
+            s,
+            "{{\"reason\":\"Preempted\",\"remaining\":{remaining},\"result\":{}}}",
+            string(&format!("{result:?}"))
+        ),
+        Some((reason, result)) => write!(
+            s,
+            "{{\"reason\":{},\"result\":{}}}",
+            string(&format!("{reason:?}")),
+            string(&format!("{result:?}"))
+        ),
+        None => write!(s, "null"),
+    }
+    .unwrap();
+
+    s.push_str(",\"processes\":[");
+    let mut pids = log.processes.keys().collect::<Vec<_>>();
+    pids.sort();
+    for (index, pid) in pids.into_iter().enumerate() {
+        let process = &log.processes[pid];
+        if index > 0 {
+            s.push(',');
+        }
+        write!(
+            s,
+            "{{\"pid\":{},\"state\":{},\"priority\":{},\"total\":{},\"syscall\":{},\"execute\":{},\"extra\":{}}}",
+            process.pid,
+            string(&process.state.to_string()),
+            process.priority,
+            process.timings.total,
+            process.timings.syscall,
+            process.timings.execution,
+            string(&process.extra)
+        )
+        .unwrap();
+    }
+    s.push_str("]}");
+    s
+}
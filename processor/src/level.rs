@@ -0,0 +1,58 @@
+//! How much of a simulation the processor records.
+
+/// What the [`Processor`](crate::Processor) records on every iteration.
+///
+/// Taking a snapshot of the processes is the most expensive part of an
+/// iteration, so long simulations where only the decisions matter can
+/// skip it.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{LogLevel, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let workload = |process: &processor::Process<_>| {
+///     process.fork(|process| process.exec(), 0);
+///     process.exec();
+/// };
+///
+/// let decisions = Processor::builder()
+///     .log_level(LogLevel::DecisionsOnly)
+///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), workload);
+/// assert_eq!(decisions[0].decision.to_string(), "Run 1 for 2 slices");
+/// assert!(decisions.iter().all(|log| log.processes.is_empty()));
+///
+/// let nothing = Processor::builder()
+///     .log_level(LogLevel::Off)
+///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), workload);
+/// assert!(nothing.is_empty());
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Every iteration records the decision, the stop reason, the
+    /// statistics of the scheduler and the processes it lists.
+    #[default]
+    Full,
+
+    /// Every iteration records the decision, the stop reason and the
+    /// statistics of the scheduler, [`Log::processes`](crate::Log::processes)
+    /// is empty.
+    DecisionsOnly,
+
+    /// No iteration is recorded, the simulation returns no logs and
+    /// nothing is written to the [`LogSink`](crate::LogSink).
+    Off,
+}
+
+impl LogLevel {
+    /// Returns `true` if the iterations are recorded.
+    pub(crate) fn records(self) -> bool {
+        self != LogLevel::Off
+    }
+
+    /// Returns `true` if the iterations record the processes.
+    pub(crate) fn snapshots(self) -> bool {
+        self == LogLevel::Full
+    }
+}
@@ -2,16 +2,77 @@
 //!
 //! This is used for simulating scheduler from the [`scheduler`] crate.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{self, Display};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::Duration;
 use std::{mem, thread};
 
+use scheduler::deadlock::WaitForGraph;
 use scheduler::{
-    Pid, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    Event, Parameter, Pid, PidPolicy, Priority, ProcessState, Scheduler, SchedulerStats, SchedulingDecision, Seed,
+    StopReason, Syscall, SyscallResult, Timings,
 };
 
+#[macro_use]
+mod trace;
+
+mod backend;
+mod collector;
+mod compress;
+pub mod coverage;
+mod diff;
+mod dispatcher;
+#[cfg(feature = "scenario")]
+mod engine;
+mod events;
+pub mod export;
+mod format;
+pub mod header;
+mod json;
+mod level;
+pub mod metrics;
+mod parse;
+mod query;
+pub mod replay;
+pub mod report;
+#[cfg(feature = "scenario")]
+pub mod scenario;
+mod session;
+mod sink;
+#[cfg(feature = "scenario")]
+pub mod strace;
+pub mod test_workloads;
+pub mod timeline;
+mod tree;
+pub mod utilization;
+mod validation;
+pub mod workloads;
+
+pub use backend::Backend;
+use backend::Spawner;
+use collector::Collector;
+use dispatcher::{Dispatch, Dispatcher};
+pub use compress::{compress, LogEntry};
+pub use diff::{diff, Difference, LogDiff};
+pub use events::{events, LogEvent};
+pub use level::LogLevel;
+pub use format::{
+    format_log_entries, format_logs, format_logs_by_process, format_logs_compressed,
+    format_logs_markdown, format_logs_pretty, format_logs_with_summary, LogFormatter,
+    Column, FormatOptions, MarkdownFormatter, PlainFormatter, PrettyFormatter, SummaryFormatter,
+};
+pub use parse::{parse_logs, ParseError};
+pub use query::LogQuery;
+pub use session::Session;
+pub use sink::{FileSink, LogSink, SinkFormat, WriterSink};
+pub use validation::{check_processes, check_remaining, check_timings, Violation};
+use validation::{Pids, Validator};
+
 /// Running iteration log
 #[derive(Debug)]
 pub struct Log {
@@ -24,6 +85,14 @@ pub struct Log {
     /// The list of processes and their corresponding states
     /// returned by the scheduler.
     pub processes: HashMap<Pid, ProcessInfo>,
+
+    /// The counters of the scheduler after it has taken the decision,
+    /// if the scheduler keeps them (see [`Scheduler::stats`]).
+    pub stats: Option<SchedulerStats>,
+
+    /// The invariant broken by the decision, if the processor validates
+    /// the decisions (see [`ProcessorBuilder::validate`]).
+    pub violation: Option<Violation>,
 }
 
 impl Log {
@@ -36,8 +105,34 @@ impl Log {
             decision,
             stop_reason,
             processes,
+            stats: None,
+            violation: None,
         }
     }
+
+    /// The log of a decision, with the processes of the scheduler if
+    /// `processes` is true.
+    fn snapshot<S: Scheduler + ?Sized>(scheduler: &mut S, decision: SchedulingDecision, processes: bool) -> Log {
+        let mut process_map = HashMap::new();
+        if processes {
+            scheduler.for_each_process(&mut |process| {
+                let mut info = ProcessInfo::new(
+                    process.pid(),
+                    process.parent(),
+                    process.state(),
+                    process.timings(),
+                    process.priority(),
+                    process.extra().into_owned(),
+                );
+                info.created_at = process.created_at();
+                info.last_scheduled_at = process.last_scheduled_at();
+                process_map.insert(process.pid(), info);
+            });
+        }
+        let mut log = Log::new(decision, None, process_map);
+        log.stats = scheduler.stats();
+        log
+    }
 }
 
 impl Display for Log {
@@ -71,14 +166,24 @@ pub struct ProcessInfo {
     /// The PID of the process.
     pub pid: Pid,
 
+    /// The PID of the parent process, if the scheduler reports it.
+    pub parent: Option<Pid>,
+
+    /// The virtual time when the process was created, if the scheduler reports it.
+    pub created_at: Option<usize>,
+
+    /// The virtual time when the process was last scheduled, if the
+    /// scheduler reports it.
+    pub last_scheduled_at: Option<usize>,
+
     /// The process state.
     pub state: ProcessState,
 
-    /// The process timings (total time, system call time, running time).
-    pub timings: (usize, usize, usize),
+    /// The process timings.
+    pub timings: Timings,
 
     /// The process priority
-    pub priority: i8,
+    pub priority: Priority,
 
     /// Extra details about the process
     pub extra: String,
@@ -87,13 +192,17 @@ pub struct ProcessInfo {
 impl ProcessInfo {
     fn new(
         pid: Pid,
+        parent: Option<Pid>,
         state: ProcessState,
-        timings: (usize, usize, usize),
-        priority: i8,
+        timings: Timings,
+        priority: Priority,
         extra: String,
     ) -> ProcessInfo {
         ProcessInfo {
             pid,
+            parent,
+            created_at: None,
+            last_scheduled_at: None,
             state,
             timings,
             priority,
@@ -110,24 +219,592 @@ impl Display for ProcessInfo {
             self.pid,
             self.state,
             self.priority,
-            self.timings.0,
-            self.timings.1,
-            self.timings.2,
+            self.timings.total,
+            self.timings.syscall,
+            self.timings.execution,
             self.extra
         )
     }
 }
 
+/// Configures and starts a [`Processor`] simulation.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{Processor, SinkFormat, WriterSink};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::builder()
+///     .sink(WriterSink::new(std::io::sink(), SinkFormat::Json))
+///     .retain_logs(false)
+///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///         process.exec();
+///     });
+///
+/// assert!(logs.is_empty());
+/// ```
+pub struct ProcessorBuilder<S: Scheduler + 'static> {
+    sink: Option<Box<dyn LogSink>>,
+    narration: Option<Box<dyn Write + Send>>,
+    retained: Option<usize>,
+    log_capacity: usize,
+    log_level: LogLevel,
+    validate: bool,
+    check_timings: bool,
+    backend: Backend,
+    shutdown_timeout: Duration,
+    pid_policy: Option<PidPolicy>,
+    tuning: Vec<(usize, Parameter)>,
+    processes: Vec<(Seed, SeededProcess<S>)>,
+    scheduler: PhantomData<fn(S)>,
+}
+
+type SeededProcess<S> = Box<dyn FnOnce(&Process<S>) + Send>;
+
+impl<S: Scheduler + 'static> Default for ProcessorBuilder<S> {
+    fn default() -> Self {
+        ProcessorBuilder::new()
+    }
+}
+
+impl<S: Scheduler + 'static> ProcessorBuilder<S> {
+    /// Returns a builder with the default configuration, the same
+    /// one used by [`Processor::run`].
+    pub fn new() -> ProcessorBuilder<S> {
+        ProcessorBuilder {
+            sink: None,
+            narration: None,
+            retained: None,
+            log_capacity: 0,
+            log_level: LogLevel::Full,
+            validate: false,
+            check_timings: false,
+            backend: Backend::Threads,
+            shutdown_timeout: Duration::from_secs(1),
+            pid_policy: None,
+            tuning: vec![],
+            processes: vec![],
+            scheduler: PhantomData,
+        }
+    }
+
+    /// Write every iteration to `sink` as soon as it is complete.
+    ///
+    /// * `sink` - the destination of the logs.
+    pub fn sink<L: LogSink + 'static>(mut self, sink: L) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Write the narration of the simulation (the process that runs and
+    /// the system calls it sends) to `writer` instead of the standard
+    /// output.
+    ///
+    /// This keeps the output of simulations that run at the same time
+    /// apart. With the `tracing` feature, the narration is reported
+    /// with [`tracing`] events instead.
+    ///
+    /// * `writer` - the destination of the narration.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::io::Write;
+    /// use std::num::NonZeroUsize;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Narration(Arc<Mutex<Vec<u8>>>);
+    ///
+    /// impl Write for Narration {
+    ///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    ///         self.0.lock().unwrap().write(buf)
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> std::io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let narration = Narration::default();
+    /// Processor::builder()
+    ///     .narrate(narration.clone())
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         process.exec();
+    ///     });
+    ///
+    /// let narration = String::from_utf8(narration.0.lock().unwrap().clone()).unwrap();
+    /// # #[cfg(not(feature = "tracing"))]
+    /// assert!(narration.starts_with("RUNNING 1\n1: EXEC\n"));
+    /// ```
+    pub fn narrate<W: Write + Send + 'static>(mut self, writer: W) -> Self {
+        self.narration = Some(Box::new(writer));
+        self
+    }
+
+    /// Sets whether the logs are kept in memory and returned at
+    /// the end of the simulation.
+    ///
+    /// Disabling this is useful for very long simulations that
+    /// write their logs using a [`LogSink`].
+    ///
+    /// * `retain` - if `false`, the simulation returns no logs.
+    pub fn retain_logs(mut self, retain: bool) -> Self {
+        self.retained = if retain { None } else { Some(0) };
+        self
+    }
+
+    /// Keep only the last `iterations` iterations in memory and return
+    /// them at the end of the simulation.
+    ///
+    /// The older iterations are still written to the [`LogSink`], if
+    /// there is one, so long simulations use a bounded amount of memory.
+    ///
+    /// * `iterations` - the number of iterations to keep.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::builder()
+    ///     .retain_last(2)
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         for _ in 0..10 {
+    ///             process.exec();
+    ///         }
+    ///     });
+    ///
+    /// assert_eq!(logs.len(), 2);
+    /// assert_eq!(logs[1].decision.to_string(), "Done, no more processes");
+    /// ```
+    pub fn retain_last(mut self, iterations: usize) -> Self {
+        self.retained = Some(iterations);
+        self
+    }
+
+    /// Allocate memory for `iterations` iterations before the simulation
+    /// starts, for simulations whose number of iterations is known.
+    ///
+    /// This does not limit the number of iterations.
+    ///
+    /// * `iterations` - the expected number of iterations.
+    pub fn log_capacity(mut self, iterations: usize) -> Self {
+        self.log_capacity = iterations;
+        self
+    }
+
+    /// Sets what every iteration records, [`LogLevel::Full`] by default.
+    ///
+    /// * `level` - what is recorded.
+    pub fn log_level(mut self, level: LogLevel) -> Self {
+        self.log_level = level;
+        self
+    }
+
+    /// Sets whether every decision of the scheduler is checked against
+    /// its own process list.
+    ///
+    /// A [`SchedulingDecision::Run`] has to name a process that the
+    /// scheduler lists as ready or running, and a
+    /// [`SchedulingDecision::Sleep`] has to last until the first sleeping
+    /// process wakes up. The processes listed by the scheduler can only
+    /// change with the forks and the exits, see [`check_processes`], and
+    /// the time left to a process when it stops has to match the time
+    /// units it has executed, see [`check_remaining`]. A decision or a
+    /// stop that does not is recorded in [`Log::violation`] and narrated,
+    /// but the simulation continues. This points to the decision that causes the
+    /// differences found later in the logs.
+    ///
+    /// * `validate` - if `true`, every decision is checked.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::builder()
+    ///     .validate(true)
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         process.fork(|process| process.exec(), 0);
+    ///         process.sleep(3);
+    ///     });
+    ///
+    /// assert!(logs.iter().all(|log| log.violation.is_none()));
+    /// ```
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Sets whether the timings reported by the scheduler are checked at
+    /// every decision, see [`check_timings`] for the invariants.
+    ///
+    /// A broken invariant is recorded in [`Log::violation`] and narrated
+    /// like an invalid decision, which takes precedence if the decisions
+    /// are also validated.
+    ///
+    /// * `check` - if `true`, the timings are checked.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::builder()
+    ///     .check_timings(true)
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         process.fork(|process| process.sleep(2), 0);
+    ///         process.exec();
+    ///     });
+    ///
+    /// assert!(logs.iter().all(|log| log.violation.is_none()));
+    /// ```
+    pub fn check_timings(mut self, check: bool) -> Self {
+        self.check_timings = check;
+        self
+    }
+
+    /// Sets how the simulated processes are run.
+    ///
+    /// [`Backend::Pooled`] allows simulations with tens of thousands
+    /// of processes. The process with PID 1 always runs on its own
+    /// thread.
+    ///
+    /// * `backend` - the threads used by the processes.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Sets how long the end of the simulation waits for the threads of
+    /// the processes to stop, one second by default.
+    ///
+    /// Once the simulation ends, the closure of every process stops the
+    /// next time it calls its [`Process`] (see [`ProcessTerminated`]), so
+    /// the processes that wait or sleep when a deadlock or a panic ends
+    /// the simulation do not run outside of it. A closure that does not
+    /// call its [`Process`] keeps its thread running; the threads that
+    /// are still running after `timeout` are narrated as leaked.
+    ///
+    /// * `timeout` - the longest time to wait for the threads.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::SchedulingDecision;
+    /// use std::num::NonZeroUsize;
+    /// use std::time::Duration;
+    ///
+    /// let logs = Processor::builder()
+    ///     .shutdown_timeout(Duration::from_millis(100))
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         // stops when the deadlock ends the simulation
+    ///         process.fork(|process| loop { process.wait(1) }, 0);
+    ///         process.wait(2);
+    ///     });
+    ///
+    /// assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Deadlock);
+    /// ```
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Sets how the scheduler is expected to choose the PIDs of new
+    /// processes, by default the policy returned by
+    /// [`Scheduler::pid_policy`].
+    ///
+    /// The PID of a new process can never be the PID of a process that
+    /// has not exited, and with [`PidPolicy::Increasing`] it has to be
+    /// greater than all the PIDs before it. The simulation ends as soon
+    /// as the scheduler returns another PID, and the processor panics
+    /// with the reason.
+    ///
+    /// * `policy` - the policy of the scheduler.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::PidPolicy;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::builder()
+    ///     .pid_policy(PidPolicy::Recycle)
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         process.fork(|process| process.exec(), 0);
+    ///         process.sleep(3);
+    ///     });
+    ///
+    /// assert_eq!(logs.last().unwrap().decision.to_string(), "Done, no more processes");
+    /// ```
+    pub fn pid_policy(mut self, policy: PidPolicy) -> Self {
+        self.pid_policy = Some(policy);
+        self
+    }
+
+    /// Change a parameter of the scheduler before it takes the
+    /// decision of an iteration.
+    ///
+    /// This can be called several times to build a tuning schedule.
+    /// The scheduler has to be a
+    /// [`TunableScheduler`](scheduler::TunableScheduler) that uses
+    /// the parameter, otherwise the simulation panics when the
+    /// parameter is applied.
+    ///
+    /// * `iteration` - the iteration, starting from 0.
+    /// * `parameter` - the parameter and its new value.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::Parameter;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::builder()
+    ///     .tune(2, Parameter::Timeslice(NonZeroUsize::new(5).unwrap()))
+    ///     .run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///         for _ in 0..10 {
+    ///             process.exec();
+    ///         }
+    ///     });
+    ///
+    /// assert_eq!(logs[2].decision.to_string(), "Run 1 for 5 slices");
+    /// ```
+    pub fn tune(mut self, iteration: usize, parameter: Parameter) -> Self {
+        self.tuning.push((iteration, parameter));
+        self
+    }
+
+    /// Add a process that exists before the simulation starts.
+    ///
+    /// The processes are only used by [`ProcessorBuilder::run_seeded`].
+    ///
+    /// * `seed` - the PID, parent, priority and state of the process.
+    /// * `f` - a function with the instructions that the process
+    ///   executes after it is first scheduled.
+    pub fn process<F>(mut self, seed: Seed, f: F) -> Self
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.processes.push((seed, Box::new(f)));
+        self
+    }
+
+    /// Start the simulation.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `f` - a function with the instructions for the process with
+    ///   PID 1.
+    pub fn run<F>(self, scheduler: S, f: F) -> Vec<Log>
+    where
+        F: FnOnce(&Process<S>) + Send,
+    {
+        self.start(Arc::new(Mutex::new(scheduler)), f)
+    }
+
+    /// Start the simulation with a scheduler that is kept by the caller,
+    /// so that it can be used for several simulations.
+    ///
+    /// The scheduler is reset using [`Scheduler::reset`] before the
+    /// simulation starts.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `f` - a function with the instructions for the process with
+    ///   PID 1.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the scheduler cannot be reset.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::num::NonZeroUsize;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let scheduler = Arc::new(Mutex::new(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1)));
+    ///
+    /// let first = Processor::builder().run_shared(&scheduler, |process| process.sleep(2));
+    /// let second = Processor::builder().run_shared(&scheduler, |process| process.sleep(2));
+    /// assert_eq!(first, second);
+    /// ```
+    pub fn run_shared<F>(self, scheduler: &Arc<Mutex<S>>, f: F) -> Vec<Log>
+    where
+        F: FnOnce(&Process<S>) + Send,
+    {
+        if !scheduler.lock().unwrap().reset() {
+            panic!("The scheduler cannot be reset");
+        }
+        self.start(scheduler.clone(), f)
+    }
+
+    /// Start the simulation from the processes added using
+    /// [`ProcessorBuilder::process`] instead of forking PID 1.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the scheduler is not a
+    /// [`SeedableScheduler`](scheduler::SeedableScheduler) or if it
+    /// cannot be seeded with the processes.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::{Event, Pid, Priority, Seed, SeedState};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::builder()
+    ///     .process(
+    ///         Seed::new(Pid::new(1), Priority::new(0)).state(SeedState::Waiting(Event::user(1))),
+    ///         |process| process.exec(),
+    ///     )
+    ///     .process(Seed::new(Pid::new(2), Priority::new(0)).parent(Pid::new(1)), |process| {
+    ///         process.signal(1)
+    ///     })
+    ///     .run_seeded(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1));
+    ///
+    /// assert_eq!(logs[0].decision.to_string(), "Run 2 for 2 slices");
+    /// ```
+    pub fn run_seeded(mut self, mut scheduler: S) -> Vec<Log> {
+        let processes = mem::take(&mut self.processes);
+        let seeds: Vec<Seed> = processes.iter().map(|(seed, _)| *seed).collect();
+        let Some(seedable) = scheduler.as_seedable() else {
+            panic!("The scheduler cannot be seeded");
+        };
+        if let Err(error) = seedable.seed(&seeds) {
+            panic!("Unable to seed the scheduler: {error}");
+        }
+
+        let processor = self.processor(Arc::new(Mutex::new(scheduler)));
+        for (seed, f) in processes {
+            let process = Process {
+                pid: seed.pid,
+                condvar: Arc::default(),
+                processor: processor.clone(),
+            };
+            processor.threads.lock().unwrap().insert(seed.pid);
+            if let Err(error) = processor.pids.lock().unwrap().fork(seed.pid) {
+                panic!("Unable to seed the scheduler: {error}");
+            }
+            let (parked, handshake) = mpsc::channel();
+            processor.spawner.spawn(move || {
+                process.body(f, move || {
+                    let _ = parked.send(());
+                })
+            });
+            let _ = handshake.recv();
+        }
+
+        processor.schedule(&mut processor.scheduler.lock().unwrap());
+        processor.dispatcher.wait_finished(|| processor.is_running());
+        processor.shutdown();
+        processor.get_logs()
+    }
+
+    fn processor(self, scheduler: Arc<Mutex<S>>) -> Arc<Processor<S>> {
+        let pid_policy = self.pid_policy.unwrap_or_else(|| scheduler.lock().unwrap().pid_policy());
+        Arc::new(Processor {
+            scheduler,
+            dispatcher: Dispatcher::new(),
+            remaining: AtomicUsize::new(1),
+            narration: self.narration.map(Mutex::new),
+            collector: Collector::start(self.sink, self.retained, self.log_capacity),
+            log_level: self.log_level,
+            validator: (self.validate || self.check_timings).then(|| Mutex::new(Validator::new(self.validate, self.check_timings))),
+            signalers: Mutex::default(),
+            joins: Mutex::default(),
+            threads: Mutex::default(),
+            stopped: Condvar::new(),
+            pids: Mutex::new(Pids::new(pid_policy)),
+            failure: Mutex::default(),
+            shutdown_timeout: self.shutdown_timeout,
+            spawner: Spawner::new(self.backend),
+            tuning: self.tuning,
+            decisions: AtomicUsize::new(0),
+            running: AtomicBool::new(true),
+        })
+    }
+
+    fn start<F>(self, scheduler: Arc<Mutex<S>>, f: F) -> Vec<Log>
+    where
+        F: FnOnce(&Process<S>) + Send,
+    {
+        let processor = self.processor(scheduler);
+
+        let SyscallResult::Pid(pid) = processor.scheduler(StopReason::syscall(Syscall::Fork(Priority::new(0)))) else {
+            panic!("Fork did not return a pid");
+        };
+
+        if pid != 1 {
+            panic!("Scheduler did not return PID 1 for the first process");
+        }
+
+        thread::scope(|s| {
+            s.spawn(move || {
+                let process = Process {
+                    pid,
+                    condvar: Arc::default(),
+                    processor,
+                };
+                process.body(f, || {});
+                process.processor.shutdown();
+                process.processor.get_logs()
+            })
+            .join()
+            // the panic of the simulation, with its message
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+        })
+    }
+}
+
 /// The processor simulator.
 pub struct Processor<S: Scheduler + 'static> {
     scheduler: Arc<Mutex<S>>,
-    current_process: Arc<(Mutex<Option<Pid>>, Condvar)>,
+    dispatcher: Dispatcher,
     remaining: AtomicUsize,
-    logs: Mutex<Vec<Log>>,
+    narration: Option<Mutex<Box<dyn Write + Send>>>,
+    collector: Collector,
+    log_level: LogLevel,
+    validator: Option<Mutex<Validator>>,
+    /// The processes that have signaled every event, to explain deadlocks.
+    signalers: Mutex<Vec<(Event, Pid)>>,
+    /// The processes created by [`Process::fork_joinable`] that are
+    /// joined or have exited, the others are not listed.
+    joins: Mutex<HashMap<Pid, Join>>,
+    /// The processes whose threads run their closures, apart from PID 1.
+    threads: Mutex<BTreeSet<Pid>>,
+    // notified when the closure of a process stops
+    stopped: Condvar,
+    shutdown_timeout: Duration,
+    pids: Mutex<Pids>,
+    /// Why the simulation was stopped before its end, the processor
+    /// panics with it once the threads have stopped.
+    failure: Mutex<Option<String>>,
+    spawner: Spawner,
+    tuning: Vec<(usize, Parameter)>,
+    decisions: AtomicUsize,
     running: AtomicBool,
 }
 
 impl<S: Scheduler + 'static> Processor<S> {
+    /// Returns a [`ProcessorBuilder`] for configuring a simulation.
+    pub fn builder() -> ProcessorBuilder<S> {
+        ProcessorBuilder::new()
+    }
+
     /// Start a new processor simulation.
     ///
     /// * `scheduler` - the scheduler to use for the simulation.
@@ -162,42 +839,14 @@ impl<S: Scheduler + 'static> Processor<S> {
     where
         F: FnOnce(&Process<S>) + Send,
     {
-        let processor = Arc::new(Processor {
-            scheduler: Arc::new(Mutex::new(scheduler)),
-            current_process: Arc::new((Mutex::new(None), Condvar::new())),
-            remaining: AtomicUsize::new(1),
-            logs: Mutex::new(vec![]),
-            running: AtomicBool::new(true),
-        });
-
-        let SyscallResult::Pid(pid) = processor.scheduler(StopReason::syscall(Syscall::Fork(0))) else {
-            panic!("Fork did not return a pid");
-        };
-
-        if pid != 1 {
-            panic!("Scheduler did not return PID 1 for the first process");
-        }
-
-        let mutex = processor.current_process.clone();
-        thread::scope(|s| {
-            s.spawn(move || {
-                let process = Process {
-                    pid,
-                    mutex,
-                    processor,
-                };
-                process.suspend();
-                f(&process);
-                process.exit();
-                process.processor.get_logs()
-            })
-            .join()
-            .unwrap()
-        })
+        ProcessorBuilder::new().run(scheduler, f)
     }
 
     fn exec(&self) -> bool {
         if self.is_running() {
+            if let Some(validator) = &self.validator {
+                validator.lock().unwrap().exec();
+            }
             self.remaining.fetch_sub(1, Ordering::Relaxed);
             self.remaining.load(Ordering::Relaxed) != 0
         } else {
@@ -205,80 +854,192 @@ impl<S: Scheduler + 'static> Processor<S> {
         }
     }
 
-    fn scheduler(&self, mut reason: StopReason) -> SyscallResult {
+    fn preempt(&self) -> bool {
+        self.is_running()
+            && self
+                .scheduler
+                .lock()
+                .unwrap()
+                .preempt(self.remaining.load(Ordering::Relaxed))
+    }
+
+    fn scheduler(&self, reason: StopReason) -> SyscallResult {
+        self.scheduler_then(reason, |_| {})
+    }
+
+    /// Like [`Processor::scheduler`], but calls `stopped` with the result
+    /// of the system call before the next process is scheduled, unless
+    /// the simulation has ended.
+    fn scheduler_then(&self, mut reason: StopReason, stopped: impl FnOnce(SyscallResult)) -> SyscallResult {
         if self.is_running() {
-            self.remaining.fetch_sub(1, Ordering::Relaxed);
+            // a preemption does not use a time unit
+            if !matches!(reason, StopReason::Preempted { .. }) {
+                self.remaining.fetch_sub(1, Ordering::Relaxed);
+            }
             let mut scheduler = self.scheduler.lock().unwrap();
             reason.set_remaining(self.remaining.load(Ordering::Relaxed));
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("syscall", %reason).entered();
             let result = scheduler.stop(reason);
-            {
-                let mut logs = self.logs.lock().unwrap();
-                let len = logs.len();
-                if len > 0 {
-                    if let Some(log) = logs.get_mut(len - 1) {
-                        log.stop_reason = Some((reason, result));
-                    };
+            if let Some(validator) = &self.validator {
+                validator.lock().unwrap().stopped(reason, result);
+            }
+            if self.log_level.records() {
+                self.collector.stopped(reason, result);
+            }
+            if let (StopReason::Syscall { syscall: Syscall::Fork(_), .. }, SyscallResult::Pid(pid)) = (reason, result) {
+                // two processes with the same PID would both run
+                if let Err(error) = self.pids.lock().unwrap().fork(pid) {
+                    *self.failure.lock().unwrap() = Some(error);
+                    self.stop(&mut self.dispatcher.lock());
+                    return result;
                 }
             }
 
-            let mut current_process = self.current_process.0.lock().unwrap();
-            *current_process = None;
-            while self.is_running() && current_process.is_none() {
-                let next = scheduler.next();
-                let mut process_map = HashMap::new();
-                for process in scheduler.list() {
-                    process_map.insert(
-                        process.pid(),
-                        ProcessInfo::new(
-                            process.pid(),
-                            process.state(),
-                            process.timings(),
-                            process.priority(),
-                            process.extra(),
-                        ),
-                    );
+            stopped(result);
+            self.schedule(&mut scheduler);
+            result
+        } else {
+            SyscallResult::NoRunningProcess
+        }
+    }
+
+    /// Ask the scheduler for decisions until a process is scheduled
+    /// or the simulation ends.
+    fn schedule(&self, scheduler: &mut S) {
+        let mut dispatch = self.dispatcher.lock();
+        dispatch.clear();
+        while self.is_running() && dispatch.is_idle() {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!(
+                "iteration",
+                number = self.decisions.load(Ordering::Relaxed) + 1
+            )
+            .entered();
+            self.tune(scheduler);
+            let next = scheduler.next();
+            let violation = self
+                .validator
+                .as_ref()
+                .and_then(|validator| validator.lock().unwrap().decision(scheduler, next));
+            if let Some(violation) = violation {
+                diagnostic!(self, warn, { %violation, "invalid decision" }, "INVALID {violation}");
+            }
+            if self.log_level.records() {
+                self.record(scheduler, next, violation);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(decision = %next, "decision");
+            match next {
+                SchedulingDecision::Run { pid, timeslice } => {
+                    self.remaining.store(timeslice.into(), Ordering::Relaxed);
+                    dispatch.run(pid);
                 }
-                (*self.logs.lock().unwrap()).push(Log::new(next, None, process_map));
-                // println!("{}", next);
-                match next {
-                    SchedulingDecision::Run { pid, timeslice } => {
-                        self.remaining.store(timeslice.into(), Ordering::Relaxed);
-                        *current_process = Some(pid);
-                        self.current_process.1.notify_all();
-                    }
-                    SchedulingDecision::Sleep(time) => {
-                        println!("SLEEP {time}");
-                    }
-                    SchedulingDecision::Deadlock => {
-                        println!("DEADLOCK");
-                        self.stop();
-                    }
-                    SchedulingDecision::Panic => {
-                        println!("PANIC");
-                        self.stop();
-                    }
-                    SchedulingDecision::Done => {
-                        println!("DONE");
-                        self.stop();
+                SchedulingDecision::Sleep(time) => {
+                    scheduler.on_tick(time);
+                    diagnostic!(self, debug, { amount = time.get(), "sleep" }, "SLEEP {time}");
+                }
+                SchedulingDecision::Deadlock => {
+                    let mut graph = WaitForGraph::from_scheduler(scheduler);
+                    for (event, pid) in self.signalers.lock().unwrap().iter() {
+                        graph.signaler(*event, *pid);
                     }
+                    let deadlock = graph.deadlock();
+                    diagnostic!(self, warn, { %deadlock, "deadlock" }, "DEADLOCK\n{}", deadlock.to_string().trim_end());
+                    self.stop(&mut dispatch);
+                }
+                SchedulingDecision::Panic => {
+                    diagnostic!(self, error, { "panic" }, "PANIC");
+                    self.stop(&mut dispatch);
+                }
+                SchedulingDecision::Done => {
+                    diagnostic!(self, debug, { "done" }, "DONE");
+                    self.stop(&mut dispatch);
                 }
             }
-            result
-        } else {
-            SyscallResult::NoRunningProcess
+        }
+    }
+
+    /// Record a new iteration, with a snapshot of the processes if the
+    /// log level asks for it.
+    fn record(&self, scheduler: &mut S, next: SchedulingDecision, violation: Option<Violation>) {
+        let mut log = Log::snapshot(scheduler, next, self.log_level.snapshots());
+        log.violation = violation;
+        self.collector.record(log);
+    }
+
+    /// Apply the parameters scheduled for the next iteration.
+    fn tune(&self, scheduler: &mut S) {
+        let iteration = self.decisions.fetch_add(1, Ordering::Relaxed);
+        for (_, parameter) in self.tuning.iter().filter(|(at, _)| *at == iteration) {
+            let Some(tunable) = scheduler.as_tunable() else {
+                panic!("The scheduler cannot be tuned");
+            };
+            if !tunable.tune(*parameter) {
+                panic!("The scheduler does not use the {parameter:?} parameter");
+            }
+        }
+    }
+
+    /// Write a line of the narration of the simulation.
+    #[cfg_attr(feature = "tracing", allow(dead_code))]
+    fn narrate(&self, line: fmt::Arguments) {
+        match &self.narration {
+            // the narration is not part of the logs, it cannot fail the simulation
+            Some(writer) => {
+                let _ = writeln!(writer.lock().unwrap(), "{line}");
+            }
+            None => println!("{line}"),
         }
     }
 
     fn get_logs(&self) -> Vec<Log> {
-        let mut logs = self.logs.lock().unwrap();
-        let mut res = vec![];
-        mem::swap(&mut res, &mut *logs);
-        res
+        #[cfg(feature = "memory-usage")]
+        self.report_memory();
+        let logs = self.collector.finish();
+        if let Some(failure) = self.failure.lock().unwrap().take() {
+            panic!("{failure}");
+        }
+        logs
     }
 
-    fn stop(&self) {
+    /// Report the most memory used by the process table of the
+    /// scheduler and by the logs kept in memory.
+    ///
+    /// If the logs use too much memory, they can be written to a
+    /// [`LogSink`] and only the last ones kept using
+    /// [`ProcessorBuilder::retain_last`].
+    #[cfg(feature = "memory-usage")]
+    fn report_memory(&self) {
+        let logs = self.collector.memory_usage().peak;
+        match self.scheduler.lock().unwrap().memory_usage() {
+            Some(table) => {
+                let table = table.peak;
+                diagnostic!(self, info, { process_table = table, logs, "memory" }, "MEMORY process table {table} bytes, logs {logs} bytes");
+            }
+            None => {
+                diagnostic!(self, info, { logs, "memory" }, "MEMORY logs {logs} bytes");
+            }
+        }
+    }
+
+    /// Wait for the threads of the processes to stop, and narrate the
+    /// ones that are still running after the shutdown timeout.
+    fn shutdown(&self) {
+        let threads = self.threads.lock().unwrap();
+        let (threads, _) = self
+            .stopped
+            .wait_timeout_while(threads, self.shutdown_timeout, |threads| !threads.is_empty())
+            .unwrap();
+        if !threads.is_empty() {
+            let leaked = threads.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(", ");
+            diagnostic!(self, warn, { %leaked, "leaked threads" }, "LEAKED processes {leaked}");
+        }
+    }
+
+    fn stop(&self, dispatch: &mut Dispatch) {
         self.running.store(false, Ordering::Relaxed);
-        self.current_process.1.notify_all();
+        self.dispatcher.finish(dispatch);
     }
 
     fn is_running(&self) -> bool {
@@ -286,125 +1047,481 @@ impl<S: Scheduler + 'static> Processor<S> {
     }
 }
 
+/// How far a process created by [`Process::fork_joinable`] and its
+/// parent are.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Join {
+    /// The parent waits for the process to exit.
+    Waiting,
+    /// The process has exited before its parent joined it.
+    Exited,
+}
+
 /// The interface offered by the [`Processor`] to a [`Process`].
 pub struct Process<S: Scheduler + 'static> {
     /// The PID of the process.
     pub pid: Pid,
     processor: Arc<Processor<S>>,
-    mutex: Arc<(Mutex<Option<Pid>>, Condvar)>,
+    // the thread of the process waits on it while it is suspended
+    condvar: Arc<Condvar>,
+}
+
+/// A process created by [`Process::fork_joinable`].
+pub struct Child<'a, S: Scheduler + 'static> {
+    parent: &'a Process<S>,
+    pid: Pid,
+}
+
+impl<S: Scheduler + 'static> Child<'_, S> {
+    /// The PID of the process.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// Waits until the process exits, like `waitpid`.
+    ///
+    /// The parent sends a [`Syscall::Wait`] system call for
+    /// [`Event::child_exit`], unless the process has already exited.
+    pub fn join(self) {
+        let mut joins = self.parent.processor.joins.lock().unwrap();
+        if joins.get(&self.pid) == Some(&Join::Exited) {
+            joins.remove(&self.pid);
+            return;
+        }
+        joins.insert(self.pid, Join::Waiting);
+        drop(joins);
+
+        // the process signals the event, for the explanation of a deadlock
+        let event = Event::child_exit(self.pid);
+        let mut signalers = self.parent.processor.signalers.lock().unwrap();
+        if !signalers.contains(&(event, self.pid)) {
+            signalers.push((event, self.pid));
+        }
+        drop(signalers);
+        self.parent.wait(event);
+    }
+}
+
+/// The payload of the unwinding that stops the closure of a process
+/// once its simulation has ended with a [`SchedulingDecision::Deadlock`]
+/// or a [`SchedulingDecision::Panic`].
+///
+/// Every method of a [`Process`] whose simulation has ended unwinds with
+/// it, without calling the panic hook, instead of running against a
+/// stopped processor. The processor catches it around the closure and
+/// narrates that the process was terminated. Closures that catch
+/// unwinding panics can use it to recognize the end of the simulation.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{ProcessTerminated, Processor};
+/// use std::num::NonZeroUsize;
+/// use std::panic::{self, AssertUnwindSafe};
+/// use std::sync::{Arc, Mutex};
+///
+/// let terminated = Arc::new(Mutex::new(None));
+/// let result = terminated.clone();
+/// Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), move |process| {
+///     let error = panic::catch_unwind(AssertUnwindSafe(|| process.wait(1))).unwrap_err();
+///     *result.lock().unwrap() = error.downcast_ref::<ProcessTerminated>().map(|terminated| terminated.pid.get());
+///     panic::resume_unwind(error);
+/// });
+/// assert_eq!(*terminated.lock().unwrap(), Some(1));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ProcessTerminated {
+    /// The PID of the process that was terminated.
+    pub pid: Pid,
+}
+
+/// The error returned by a system call of a [`Process`] when the
+/// scheduler does not return the expected [`SyscallResult`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{Processor, SimulationError};
+/// use scheduler::{OutOfBounds, Policy, Priority, PriorityBounds, SchedulerConfig};
+/// use std::sync::{Arc, Mutex};
+///
+/// let bounds = PriorityBounds::new(Priority::new(0), Priority::new(5), OutOfBounds::Reject).unwrap();
+/// let scheduler = SchedulerConfig::new(Policy::RoundRobin).timeslice(3).priority_bounds(bounds).build().unwrap();
+///
+/// let result = Arc::new(Mutex::new(None));
+/// let fork = result.clone();
+/// Processor::run(scheduler, move |process| {
+///     *fork.lock().unwrap() = Some(process.try_fork(|_| {}, 10));
+/// });
+/// let error = result.lock().unwrap().unwrap().unwrap_err();
+/// assert_eq!(error, SimulationError::InvalidPriority(Priority::new(10)));
+/// assert_eq!(error.to_string(), "the scheduler rejected priority 10");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SimulationError {
+    /// The system call was sent while no process was running.
+    NoRunningProcess,
+
+    /// The scheduler rejected the priority of a [`Syscall::Fork`], no
+    /// process was created.
+    InvalidPriority(
+        /// The priority requested for the new process.
+        Priority,
+    ),
+
+    /// The scheduler returned a result that does not belong to the
+    /// system call.
+    UnexpectedResult {
+        /// The system call sent by the process.
+        syscall: Syscall,
+
+        /// The result returned by the scheduler.
+        result: SyscallResult,
+    },
+}
+
+impl SimulationError {
+    // the error for any result other than `expected`
+    fn check(syscall: Syscall, result: SyscallResult, expected: SyscallResult) -> Result<(), SimulationError> {
+        match result {
+            result if result == expected => Ok(()),
+            SyscallResult::NoRunningProcess => Err(SimulationError::NoRunningProcess),
+            result => Err(SimulationError::UnexpectedResult { syscall, result }),
+        }
+    }
+}
+
+impl Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::NoRunningProcess => write!(f, "no process is running"),
+            SimulationError::InvalidPriority(priority) => write!(f, "the scheduler rejected priority {priority}"),
+            SimulationError::UnexpectedResult { syscall, result } => {
+                write!(f, "the scheduler returned {result:?} for {syscall:?}")
+            }
+        }
+    }
 }
 
+impl std::error::Error for SimulationError {}
+
 impl<S: Scheduler + 'static> Process<S> {
-    fn suspend(&self) {
-        let mut wait = self.mutex.0.lock().unwrap();
-        while self.processor.is_running() && *wait != Some(self.pid) {
-            // println!("SUSPENDED {}", self.pid);
-            wait = self.mutex.1.wait(wait).unwrap();
+    /// Runs the closure of the process until it returns or the
+    /// simulation ends.
+    // `parked` is called once the thread waits for the process to be
+    // scheduled, or once it runs
+    fn body(&self, f: impl FnOnce(&Process<S>), parked: impl FnOnce()) {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.wait_turn(parked);
+            f(self);
+            self.exit();
+        }));
+        let mut threads = self.processor.threads.lock().unwrap();
+        threads.remove(&self.pid);
+        self.processor.stopped.notify_all();
+        drop(threads);
+        if let Err(payload) = result {
+            if !payload.is::<ProcessTerminated>() {
+                panic::resume_unwind(payload);
+            }
+            diagnostic!(self.processor, debug, { pid = self.pid.get(), "terminated" }, "TERMINATED {}", self.pid);
         }
-        if self.processor.is_running() {
-            println!("RUNNING {}", self.pid);
+    }
+
+    /// Stops the closure of the process if the simulation has ended.
+    fn check_terminated(&self) {
+        if !self.processor.is_running() {
+            // without the panic hook, the end of the simulation is not an error
+            panic::resume_unwind(Box::new(ProcessTerminated { pid: self.pid }));
         }
     }
 
+    fn suspend(&self) {
+        self.wait_turn(|| {});
+    }
+
+    fn wait_turn(&self, parked: impl FnOnce()) {
+        let dispatch = self.processor.dispatcher.suspend(self.pid, &self.condvar, || self.processor.is_running(), parked);
+        // unwinding while the lock is held would poison it
+        drop(dispatch);
+        self.check_terminated();
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), "running" }, "RUNNING {}", self.pid);
+    }
+
     /// Execute one unit of time.
     pub fn exec(&self) {
-        println!("{}: EXEC", self.pid);
+        self.check_terminated();
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), "exec" }, "{}: EXEC", self.pid);
         if !self.processor.exec() {
-            println!("PREEMPTED {}", self.pid);
+            diagnostic!(self.processor, trace, { pid = self.pid.get(), "preempted" }, "PREEMPTED {}", self.pid);
             self.processor.scheduler(StopReason::expired());
             self.suspend();
+        } else if self.processor.preempt() {
+            diagnostic!(self.processor, trace, { pid = self.pid.get(), "preempted" }, "PREEMPTED {}", self.pid);
+            self.processor.scheduler(StopReason::preempted());
+            self.suspend();
         }
     }
 
+    /// Execute `units` units of time, one after the other.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(5).unwrap(), 1), |process| {
+    ///     process.exec_n(7);
+    /// });
+    ///
+    /// assert_eq!(logs[0].decision.to_string(), "Run 1 for 5 slices");
+    /// assert_eq!(logs[1].stop_reason.unwrap().0.to_string(), "Syscall Exit, remaining 2");
+    /// ```
+    pub fn exec_n(&self, units: usize) {
+        for _ in 0..units {
+            self.exec();
+        }
+    }
+
+    /// The time units left from the timeslice of the process, that it
+    /// can execute before it is preempted.
+    ///
+    /// A system call uses a time unit, so a process that executes all of
+    /// them is preempted when its timeslice expires.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::StopReason;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), |process| {
+    ///     process.exec();
+    ///     // the rest of the timeslice
+    ///     process.exec_n(process.remaining_quantum());
+    ///     process.exec();
+    /// });
+    ///
+    /// assert_eq!(logs[0].stop_reason.unwrap().0, StopReason::Expired);
+    /// ```
+    pub fn remaining_quantum(&self) -> usize {
+        self.check_terminated();
+        self.processor.remaining.load(Ordering::Relaxed)
+    }
+
     /// Send a [`Syscall::Fork`] system call.
-    pub fn fork<F>(&self, f: F, priority: i8) -> Pid
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the scheduler rejects the priority, see
+    /// [`Process::try_fork`].
+    pub fn fork<F>(&self, f: F, priority: impl Into<Priority>) -> Pid
     where
         F: FnOnce(&Process<S>) + Send + 'static,
     {
-        let SyscallResult::Pid(pid) = self.processor.scheduler(StopReason::syscall(Syscall::Fork(priority))) else {
-            panic!("Fork did not return a pid");
+        match self.try_fork(f, priority) {
+            Ok(pid) => pid,
+            Err(SimulationError::InvalidPriority(priority)) => panic!("The scheduler rejected priority {priority}"),
+            Err(_) => panic!("Fork did not return a pid"),
+        }
+    }
+
+    /// Send a [`Syscall::Fork`] system call, and return the error if the
+    /// scheduler does not return the PID of a new process.
+    pub fn try_fork<F>(&self, f: F, priority: impl Into<Priority>) -> Result<Pid, SimulationError>
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.check_terminated();
+        let priority = priority.into();
+        let syscall = Syscall::Fork(priority);
+        // the child starts before the next process is scheduled, so the
+        // order of the output does not depend on the threads of the OS
+        let result = self.processor.scheduler_then(StopReason::syscall(syscall), |result| {
+            if let SyscallResult::Pid(pid) = result {
+                self.start_child(pid, f);
+            }
+        });
+        let pid = match result {
+            SyscallResult::Pid(pid) => pid,
+            result => {
+                // the scheduler has chosen the next process anyway
+                self.suspend();
+                return Err(match result {
+                    SyscallResult::InvalidPriority => SimulationError::InvalidPriority(priority),
+                    SyscallResult::NoRunningProcess => SimulationError::NoRunningProcess,
+                    result => SimulationError::UnexpectedResult { syscall, result },
+                });
+            }
         };
+        // the scheduler may have returned an invalid PID
+        self.check_terminated();
+        self.suspend();
+        Ok(pid)
+    }
 
-        println!("{}: FORK {}", self.pid, pid);
+    /// Send a [`Syscall::Fork`] system call, and return a [`Child`] that
+    /// the process can wait for.
+    ///
+    /// The child signals [`Event::child_exit`] when it exits, if its
+    /// parent waits for it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the scheduler rejects the priority, like
+    /// [`Process::fork`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::{EventNamespace, StopReason, Syscall};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///     let child = process.fork_joinable(|process| process.exec_n(5), 0);
+    ///     child.join();
+    ///
+    ///     // the child exits before it is joined, join does not wait
+    ///     let child = process.fork_joinable(|process| process.exec(), 0);
+    ///     process.sleep(10);
+    ///     child.join();
+    /// });
+    ///
+    /// let waits = logs
+    ///     .iter()
+    ///     .filter(|log| matches!(log.stop_reason, Some((StopReason::Syscall { syscall: Syscall::Wait(event), .. }, _)) if event.namespace() == EventNamespace::ChildExit))
+    ///     .count();
+    /// assert_eq!(waits, 1);
+    /// assert_eq!(logs.last().unwrap().decision.to_string(), "Done, no more processes");
+    /// ```
+    pub fn fork_joinable<F>(&self, f: F, priority: impl Into<Priority>) -> Child<'_, S>
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        let pid = self.fork(
+            |process| {
+                f(process);
+                process.exited_joinable();
+            },
+            priority,
+        );
+        Child { parent: self, pid }
+    }
+
+    // wakes the parent of a process created by `fork_joinable` if it
+    // waits, the process exits afterwards
+    fn exited_joinable(&self) {
+        let mut joins = self.processor.joins.lock().unwrap();
+        let waiting = joins.insert(self.pid, Join::Exited) == Some(Join::Waiting);
+        if waiting {
+            joins.remove(&self.pid);
+        }
+        drop(joins);
+        if waiting {
+            self.signal(Event::child_exit(self.pid));
+        }
+    }
+
+    // starts the thread of a forked process and waits until it is parked
+    fn start_child<F>(&self, pid: Pid, f: F)
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), child = pid.get(), "fork" }, "{}: FORK {}", self.pid, pid);
+        // a recycled PID is not joined by the parent of the process that had it
+        self.processor.joins.lock().unwrap().remove(&pid);
 
-        let mutex = self.mutex.clone();
         let processor = self.processor.clone();
 
-        thread::spawn(move || {
+        self.processor.threads.lock().unwrap().insert(pid);
+        let (parked, handshake) = mpsc::channel();
+        self.processor.spawner.spawn(move || {
             let process = Process {
                 pid,
-                mutex,
+                condvar: Arc::default(),
                 processor,
             };
-            process.suspend();
-            f(&process);
-            process.exit();
+            process.body(f, move || {
+                let _ = parked.send(());
+            });
         });
-        self.suspend();
-        pid
+        // the child has stopped only if its thread has panicked
+        let _ = handshake.recv();
     }
 
     /// Send a [`Syscall::Wait`] system call.
     ///
-    /// * `event` - the event number to wait for.
-    pub fn wait(&self, event: usize) {
-        println!("{}: WAIT {}", self.pid, event);
-        self.processor
-            .scheduler(StopReason::syscall(Syscall::Wait(event)));
+    /// * `event` - the event to wait for, numbers are user events.
+    pub fn wait(&self, event: impl Into<Event>) {
+        let _ = self.try_wait(event);
+    }
+
+    /// Send a [`Syscall::Wait`] system call, and return the error if the
+    /// scheduler does not return [`SyscallResult::Success`].
+    ///
+    /// * `event` - the event to wait for, numbers are user events.
+    pub fn try_wait(&self, event: impl Into<Event>) -> Result<(), SimulationError> {
+        self.check_terminated();
+        let event = event.into();
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), %event, "wait" }, "{}: WAIT {}", self.pid, event);
+        let syscall = Syscall::Wait(event);
+        let result = self.processor.scheduler(StopReason::syscall(syscall));
         self.suspend();
+        SimulationError::check(syscall, result, SyscallResult::Success)
     }
 
     /// Send a [`Syscall::Signal`] system call.
     ///
-    /// * `event` - the event number to signal.
-    pub fn signal(&self, event: usize) {
-        println!("{}: SIGNAL {}", self.pid, event);
-        self.processor
-            .scheduler(StopReason::syscall(Syscall::Signal(event)));
+    /// * `event` - the event to signal, numbers are user events.
+    pub fn signal(&self, event: impl Into<Event>) {
+        let _ = self.try_signal(event);
+    }
+
+    /// Send a [`Syscall::Signal`] system call, and return the error if the
+    /// scheduler does not return [`SyscallResult::Success`].
+    ///
+    /// * `event` - the event to signal, numbers are user events.
+    pub fn try_signal(&self, event: impl Into<Event>) -> Result<(), SimulationError> {
+        self.check_terminated();
+        let event = event.into();
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), %event, "signal" }, "{}: SIGNAL {}", self.pid, event);
+        let mut signalers = self.processor.signalers.lock().unwrap();
+        if !signalers.contains(&(event, self.pid)) {
+            signalers.push((event, self.pid));
+        }
+        drop(signalers);
+        let syscall = Syscall::Signal(event);
+        let result = self.processor.scheduler(StopReason::syscall(syscall));
         self.suspend();
+        SimulationError::check(syscall, result, SyscallResult::Success)
     }
 
     /// Send a [`Syscall::Sleep`] system call.
     ///
     /// * `timeslice` - the amout of time to sleep.
     pub fn sleep(&self, timeslice: usize) {
-        println!("{}: SLEEP {}", self.pid, timeslice);
-        self.processor
-            .scheduler(StopReason::syscall(Syscall::Sleep(timeslice)));
+        let _ = self.try_sleep(timeslice);
+    }
+
+    /// Send a [`Syscall::Sleep`] system call, and return the error if the
+    /// scheduler does not return [`SyscallResult::Success`].
+    ///
+    /// * `timeslice` - the amout of time to sleep.
+    pub fn try_sleep(&self, timeslice: usize) -> Result<(), SimulationError> {
+        self.check_terminated();
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), amount = timeslice, "sleep" }, "{}: SLEEP {}", self.pid, timeslice);
+        let syscall = Syscall::Sleep(timeslice);
+        let result = self.processor.scheduler(StopReason::syscall(syscall));
         self.suspend();
+        SimulationError::check(syscall, result, SyscallResult::Success)
     }
 
     fn exit(&self) {
-        println!("{}: EXIT", self.pid);
+        diagnostic!(self.processor, trace, { pid = self.pid.get(), "exit" }, "{}: EXIT", self.pid);
+        self.processor.pids.lock().unwrap().exit(self.pid);
         self.processor.scheduler(StopReason::syscall(Syscall::Exit));
     }
 }
-
-/// Format the [`Processor`]'s logs to a [`String`].
-///
-/// * `logs` - the logs returned by the [`Processor`].
-///
-/// ## Example
-///
-/// ```rust
-/// use processor::Processor;
-/// use std::num::NonZeroUsize;
-/// use processor::format_logs;
-///
-/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
-///     /* ... */
-/// });
-///
-/// println!("{}", format_logs(&logs));
-/// ```
-pub fn format_logs(logs: &[Log]) -> String {
-    let mut s = String::new();
-    for (iteration, log) in logs.iter().enumerate() {
-        fmt::write(
-            &mut s,
-            format_args!("===== Iteration: {} =====\n{}\n", iteration + 1, log),
-        )
-        .unwrap();
-    }
-    s
-}
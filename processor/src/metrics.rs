@@ -0,0 +1,277 @@
+//! Compute statistics about a simulation from its logs.
+
+use std::collections::BTreeMap;
+
+use scheduler::{
+    Pid, Priority, ProcessState, SchedulerStats, SchedulingDecision, StopReason, Syscall,
+    SyscallResult, Timings,
+};
+
+use crate::timeline::{self, Activity};
+use crate::Log;
+
+/// Statistics about a single process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessMetrics {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// The last known priority of the process.
+    pub priority: Priority,
+
+    /// The time when the process was created, as reported by the
+    /// scheduler or, if the scheduler does not keep track of time,
+    /// the time of the system call that has created the process.
+    pub arrival: usize,
+
+    /// The time when the process was scheduled for the first time.
+    pub first_run: Option<usize>,
+
+    /// The time when the process has exited.
+    pub completion: Option<usize>,
+
+    /// The number of times the process was scheduled.
+    pub runs: usize,
+
+    /// The time units the process has spent running.
+    pub running: usize,
+
+    /// The time units the process has spent in the ready queue.
+    pub ready: usize,
+
+    /// The time units the process has spent sleeping, waiting for events or stopped.
+    pub waiting: usize,
+
+    /// The last timings reported by the scheduler.
+    pub timings: Timings,
+}
+
+impl ProcessMetrics {
+    fn new(pid: Pid, arrival: usize) -> ProcessMetrics {
+        ProcessMetrics {
+            pid,
+            priority: Priority::default(),
+            arrival,
+            first_run: None,
+            completion: None,
+            runs: 0,
+            running: 0,
+            ready: 0,
+            waiting: 0,
+            timings: Timings::default(),
+        }
+    }
+
+    /// Returns the time between the creation and the exit of the process.
+    pub fn turnaround(&self) -> Option<usize> {
+        self.completion.map(|completion| completion - self.arrival)
+    }
+
+    /// Returns the time between the creation of the process
+    /// and the first time it was scheduled.
+    pub fn response(&self) -> Option<usize> {
+        self.first_run.map(|first_run| first_run - self.arrival)
+    }
+}
+
+/// Statistics about a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    /// The statistics of every process, sorted by PID.
+    pub processes: Vec<ProcessMetrics>,
+
+    /// The number of scheduling decisions.
+    pub iterations: usize,
+
+    /// The total virtual time of the simulation.
+    pub makespan: usize,
+
+    /// The time units when a process was running.
+    pub busy: usize,
+
+    /// The time units when the processor was sleeping.
+    pub idle: usize,
+
+    /// The number of times the running process has changed.
+    pub context_switches: usize,
+
+    /// The last decision of the scheduler.
+    pub outcome: Option<SchedulingDecision>,
+
+    /// The last counters reported by the scheduler, if it keeps them.
+    pub scheduler: Option<SchedulerStats>,
+}
+
+/// How evenly the processor time was shared between the processes.
+///
+/// The service of a process is the time it has spent running, see
+/// [`ProcessMetrics::running`]. The timings reported by the scheduler
+/// are not used, as they stop being updated when the process exits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fairness {
+    /// Jain's fairness index, `(Σx)² / (n·Σx²)` for the services `x` of
+    /// the `n` processes, between `1 / n` when a single process was
+    /// served and 1 when all the processes were served equally.
+    pub jain: f64,
+
+    /// The largest service divided by the smallest one, or [`None`] if
+    /// a process was not served at all.
+    pub max_min_ratio: Option<f64>,
+
+    /// The fraction of the total service received by the processes of
+    /// every priority.
+    pub shares: BTreeMap<Priority, f64>,
+}
+
+fn average(values: impl Iterator<Item = usize>) -> Option<f64> {
+    let (sum, count) = values.fold((0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum as f64 / count as f64)
+}
+
+impl Metrics {
+    /// Compute the statistics of a simulation.
+    ///
+    /// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+    pub fn new(logs: &[Log]) -> Metrics {
+        let segments = timeline::segments(logs);
+        let mut processes = BTreeMap::new();
+        if !logs.is_empty() {
+            processes.insert(Pid::new(1), ProcessMetrics::new(Pid::new(1), 0));
+        }
+
+        let mut busy = 0;
+        let mut idle = 0;
+        let mut context_switches = 0;
+        let mut last = None;
+        for segment in &segments {
+            let log = &logs[segment.iteration];
+            for (pid, info) in &log.processes {
+                let process = processes
+                    .entry(*pid)
+                    .or_insert_with(|| ProcessMetrics::new(*pid, segment.start));
+                process.priority = info.priority;
+                process.timings = info.timings;
+                if let Some(created_at) = info.created_at {
+                    process.arrival = created_at;
+                }
+                match info.state {
+                    ProcessState::Ready => process.ready += segment.duration(),
+                    ProcessState::Waiting { .. } | ProcessState::Stopped => {
+                        process.waiting += segment.duration()
+                    }
+                    _ => {}
+                }
+            }
+
+            match segment.activity {
+                Activity::Run { pid, stop_reason } => {
+                    busy += segment.duration();
+                    if last.is_some_and(|last| last != pid) {
+                        context_switches += 1;
+                    }
+                    last = Some(pid);
+
+                    let process = processes
+                        .entry(pid)
+                        .or_insert_with(|| ProcessMetrics::new(pid, segment.start));
+                    process.runs += 1;
+                    process.running += segment.duration();
+                    process.first_run.get_or_insert(segment.start);
+
+                    if let StopReason::Syscall { syscall, .. } = stop_reason {
+                        match (syscall, log.stop_reason) {
+                            (Syscall::Exit, _) => process.completion = Some(segment.end),
+                            (Syscall::Fork(_), Some((_, SyscallResult::Pid(child)))) => {
+                                processes.insert(child, ProcessMetrics::new(child, segment.end));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Activity::Sleep => idle += segment.duration(),
+            }
+        }
+
+        Metrics {
+            processes: processes.into_values().collect(),
+            iterations: logs.len(),
+            makespan: segments.last().map_or(0, |segment| segment.end),
+            busy,
+            idle,
+            context_switches,
+            outcome: logs.last().map(|log| log.decision),
+            scheduler: logs.iter().rev().find_map(|log| log.stats),
+        }
+    }
+
+    /// Returns the fraction of the time the processor was running a process.
+    pub fn utilization(&self) -> f64 {
+        if self.makespan == 0 {
+            0.0
+        } else {
+            self.busy as f64 / self.makespan as f64
+        }
+    }
+
+    /// Returns the average turnaround time of the processes that have exited.
+    pub fn average_turnaround(&self) -> Option<f64> {
+        average(self.processes.iter().filter_map(ProcessMetrics::turnaround))
+    }
+
+    /// Returns the average time processes have spent in the ready queue.
+    pub fn average_waiting(&self) -> Option<f64> {
+        average(self.processes.iter().map(|process| process.ready))
+    }
+
+    /// Returns the average response time of the processes that were scheduled.
+    pub fn average_response(&self) -> Option<f64> {
+        average(self.processes.iter().filter_map(ProcessMetrics::response))
+    }
+
+    /// Returns how evenly the processes were served, or [`None`] if no
+    /// process has run.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::metrics::Metrics;
+    /// use processor::Processor;
+    /// use scheduler::Priority;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///     process.fork(|process| process.exec(), 1);
+    ///     (0..7).for_each(|_| process.exec());
+    /// });
+    ///
+    /// // process 1 runs for 9 units and process 2 for 2 units
+    /// let fairness = Metrics::new(&logs).fairness().unwrap();
+    /// assert_eq!(fairness.jain, 121.0 / 170.0);
+    /// assert_eq!(fairness.max_min_ratio, Some(4.5));
+    /// assert_eq!(fairness.shares[&Priority::new(1)], 2.0 / 11.0);
+    /// ```
+    pub fn fairness(&self) -> Option<Fairness> {
+        let services = self
+            .processes
+            .iter()
+            .map(|process| (process.priority, process.running as f64))
+            .collect::<Vec<_>>();
+        let total = services.iter().map(|(_, service)| service).sum::<f64>();
+        let squares = services.iter().map(|(_, service)| service * service).sum::<f64>();
+        if squares == 0.0 {
+            return None;
+        }
+
+        let max = services.iter().map(|(_, service)| *service).fold(f64::MIN, f64::max);
+        let min = services.iter().map(|(_, service)| *service).fold(f64::MAX, f64::min);
+        let mut shares = BTreeMap::new();
+        for (priority, service) in &services {
+            *shares.entry(*priority).or_insert(0.0) += service / total;
+        }
+        Some(Fairness {
+            jain: total * total / (services.len() as f64 * squares),
+            max_min_ratio: (min > 0.0).then(|| max / min),
+            shares,
+        })
+    }
+}
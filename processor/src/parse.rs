@@ -0,0 +1,231 @@
+//! Parse logs written by [`format_logs`](crate::format_logs).
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use scheduler::{Event, Pid, ProcessState, SchedulingDecision, StopReason, Syscall, SyscallResult, Timings};
+
+use crate::{Log, ProcessInfo};
+
+/// An error returned when parsing logs fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The line (starting from 1) where the error was found.
+    pub line: usize,
+
+    /// The description of the error.
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn number<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| format!("`{value}` is not a valid number"))
+}
+
+fn event(value: &str) -> Result<Event, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{value}` is not a valid event"))
+}
+
+fn non_zero(value: &str) -> Result<NonZeroUsize, String> {
+    NonZeroUsize::new(number(value)?).ok_or_else(|| format!("`{value}` cannot be 0"))
+}
+
+fn pid(value: &str) -> Result<Pid, String> {
+    non_zero(value).map(|pid| Pid::new(pid.get()))
+}
+
+/// Returns the argument of `name(argument)`.
+fn call<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn decision(line: &str) -> Result<SchedulingDecision, String> {
+    if let Some(rest) = line.strip_prefix("Run ") {
+        let (pid_value, rest) = rest
+            .split_once(" for ")
+            .ok_or_else(|| format!("invalid run decision `{line}`"))?;
+        let timeslice = rest
+            .strip_suffix(" slices")
+            .ok_or_else(|| format!("invalid run decision `{line}`"))?;
+        return Ok(SchedulingDecision::Run {
+            pid: pid(pid_value)?,
+            timeslice: non_zero(timeslice)?,
+        });
+    }
+    if let Some(amount) = line
+        .strip_prefix("Sleep for ")
+        .and_then(|rest| rest.strip_suffix(" slices"))
+    {
+        return Ok(SchedulingDecision::Sleep(non_zero(amount)?));
+    }
+    if line.starts_with("Deadlock") {
+        return Ok(SchedulingDecision::Deadlock);
+    }
+    if line.starts_with("Panic") {
+        return Ok(SchedulingDecision::Panic);
+    }
+    if line.starts_with("Done") {
+        return Ok(SchedulingDecision::Done);
+    }
+    Err(format!("unknown decision `{line}`"))
+}
+
+fn state(value: &str) -> Result<ProcessState, String> {
+    match value {
+        "READY" => Ok(ProcessState::Ready),
+        "RUNNING" => Ok(ProcessState::Running),
+        "SLEEP" => Ok(ProcessState::Waiting { event: None }),
+        "STOPPED" => Ok(ProcessState::Stopped),
+        "ZOMBIE" => Ok(ProcessState::Zombie),
+        _ => match value.strip_prefix("EVENT ") {
+            Some(event) => Ok(ProcessState::Waiting {
+                event: Some(self::event(event)?),
+            }),
+            None => Err(format!("unknown process state `{value}`")),
+        },
+    }
+}
+
+fn process(line: &str) -> Result<ProcessInfo, String> {
+    let columns = line.splitn(8, '\t').collect::<Vec<_>>();
+    let [pid_value, state_value, _, priority, total, syscall, execute, extra] = columns[..] else {
+        return Err(format!("invalid process line `{line}`"));
+    };
+    Ok(ProcessInfo::new(
+        pid(pid_value)?,
+        None,
+        state(state_value)?,
+        Timings::new(number(total)?, number(syscall)?, number(execute)?),
+        number(priority)?,
+        extra.to_string(),
+    ))
+}
+
+fn syscall(value: &str) -> Result<Syscall, String> {
+    if value == "Exit" {
+        return Ok(Syscall::Exit);
+    }
+    if let Some(priority) = call(value, "Fork") {
+        return Ok(Syscall::Fork(number(priority)?));
+    }
+    if let Some(amount) = call(value, "Sleep") {
+        return Ok(Syscall::Sleep(number(amount)?));
+    }
+    if let Some(value) = call(value, "Wait") {
+        return Ok(Syscall::Wait(event(value)?));
+    }
+    if let Some(value) = call(value, "Signal") {
+        return Ok(Syscall::Signal(event(value)?));
+    }
+    Err(format!("unknown system call `{value}`"))
+}
+
+fn stop_reason(value: &str) -> Result<StopReason, String> {
+    if value == "Expired" {
+        return Ok(StopReason::Expired);
+    }
+    if let Some(remaining) = value.strip_prefix("Preempted, remaining ") {
+        return Ok(StopReason::Preempted {
+            remaining: number(remaining)?,
+        });
+    }
+    let rest = value
+        .strip_prefix("Syscall ")
+        .ok_or_else(|| format!("unknown stop reason `{value}`"))?;
+    let (syscall_value, remaining) = rest
+        .split_once(", remaining ")
+        .ok_or_else(|| format!("unknown stop reason `{value}`"))?;
+    Ok(StopReason::Syscall {
+        syscall: syscall(syscall_value)?,
+        remaining: number(remaining)?,
+    })
+}
+
+fn syscall_result(value: &str) -> Result<SyscallResult, String> {
+    match value {
+        "Success" => Ok(SyscallResult::Success),
+        "NoRunningProcess" => Ok(SyscallResult::NoRunningProcess),
+        "InvalidPriority" => Ok(SyscallResult::InvalidPriority),
+        _ => match call(value, "Pid") {
+            Some(value) => Ok(SyscallResult::Pid(pid(value)?)),
+            None => Err(format!("unknown system call result `{value}`")),
+        },
+    }
+}
+
+fn stop(line: &str) -> Result<(StopReason, SyscallResult), String> {
+    let (reason, result) = line
+        .rsplit_once(" -> ")
+        .ok_or_else(|| format!("invalid stop reason line `{line}`"))?;
+    Ok((stop_reason(reason)?, syscall_result(result)?))
+}
+
+/// Parse logs that were formatted using [`format_logs`](crate::format_logs).
+///
+/// This allows reference outputs stored as text to be compared
+/// with the logs of a simulation using [`diff`](crate::diff).
+///
+/// * `s` - the formatted logs.
+pub fn parse_logs(s: &str) -> Result<Vec<Log>, ParseError> {
+    let mut logs = vec![];
+    let mut lines = s.lines().enumerate().peekable();
+    while let Some((index, line)) = lines.next() {
+        let error = |message: String| ParseError {
+            line: index + 1,
+            message,
+        };
+        // the lines of a header are comments
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with("===== Iteration: ") {
+            return Err(error(format!("expected an iteration header, found `{line}`")));
+        }
+
+        let Some((index, line)) = lines.next() else {
+            return Err(error(String::from("missing decision")));
+        };
+        let decision = decision(line).map_err(|message| ParseError {
+            line: index + 1,
+            message,
+        })?;
+
+        let mut processes = HashMap::new();
+        let mut stop_reason = None;
+        while let Some((index, line)) = lines.next_if(|(_, line)| !line.is_empty()) {
+            let result = if line.starts_with("PID\t") {
+                Ok(())
+            } else if line.starts_with(|c: char| c.is_ascii_digit()) {
+                process(line).map(|process| {
+                    processes.insert(process.pid, process);
+                })
+            } else {
+                stop(line).map(|stop| stop_reason = Some(stop))
+            };
+            result.map_err(|message| ParseError {
+                line: index + 1,
+                message,
+            })?;
+        }
+        logs.push(Log::new(decision, stop_reason, processes));
+    }
+    Ok(logs)
+}
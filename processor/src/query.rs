@@ -0,0 +1,106 @@
+//! Helpers for searching through the [`Processor`](crate::Processor)'s logs.
+
+use scheduler::{Pid, ProcessState, SchedulingDecision, StopReason, SyscallResult};
+
+use crate::Log;
+
+/// Query helpers for a slice of [`Log`]s.
+///
+/// Iterations are identified by their index in the slice, starting
+/// from 0 (the output of [`format_logs`](crate::format_logs) numbers
+/// them starting from 1).
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{LogQuery, Processor};
+/// use scheduler::{Pid, ProcessState, SchedulingDecision};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     process.fork(|process| process.exec(), 0);
+///     process.sleep(5);
+/// });
+///
+/// assert!(logs.involving(Pid::new(2)).count() > 0);
+/// assert!(logs
+///     .with_decision(|decision| matches!(decision, SchedulingDecision::Sleep(_)))
+///     .next()
+///     .is_some());
+/// assert_eq!(logs.history(Pid::new(2))[0].1, ProcessState::Ready);
+/// ```
+pub trait LogQuery {
+    /// Returns the iterations that involve the process with PID `pid`.
+    ///
+    /// A process is involved in an iteration if the scheduler decided
+    /// to run it or if it was created by a [`scheduler::Syscall::Fork`]
+    /// during that iteration.
+    fn involving(&self, pid: Pid) -> impl Iterator<Item = (usize, &Log)>;
+
+    /// Returns the iterations whose decision matches `predicate`.
+    fn with_decision<P>(&self, predicate: P) -> impl Iterator<Item = (usize, &Log)>
+    where
+        P: Fn(&SchedulingDecision) -> bool;
+
+    /// Returns the iterations that ended with a stop reason
+    /// matching `predicate`.
+    fn stopped_by<P>(&self, predicate: P) -> impl Iterator<Item = (usize, &Log)>
+    where
+        P: Fn(&StopReason, &SyscallResult) -> bool;
+
+    /// Returns the states that the process with PID `pid` went
+    /// through, for every iteration where it was listed by the scheduler.
+    fn history(&self, pid: Pid) -> Vec<(usize, ProcessState)>;
+
+    /// Returns the first iteration that matches `predicate`.
+    fn first_where<P>(&self, predicate: P) -> Option<(usize, &Log)>
+    where
+        P: Fn(&Log) -> bool;
+}
+
+impl LogQuery for [Log] {
+    fn involving(&self, pid: Pid) -> impl Iterator<Item = (usize, &Log)> {
+        self.iter().enumerate().filter(move |(_, log)| {
+            matches!(log.decision, SchedulingDecision::Run { pid: run, .. } if run == pid)
+                || matches!(log.stop_reason, Some((_, SyscallResult::Pid(child))) if child == pid)
+        })
+    }
+
+    fn with_decision<P>(&self, predicate: P) -> impl Iterator<Item = (usize, &Log)>
+    where
+        P: Fn(&SchedulingDecision) -> bool,
+    {
+        self.iter()
+            .enumerate()
+            .filter(move |(_, log)| predicate(&log.decision))
+    }
+
+    fn stopped_by<P>(&self, predicate: P) -> impl Iterator<Item = (usize, &Log)>
+    where
+        P: Fn(&StopReason, &SyscallResult) -> bool,
+    {
+        self.iter().enumerate().filter(move |(_, log)| {
+            log.stop_reason
+                .as_ref()
+                .is_some_and(|(reason, result)| predicate(reason, result))
+        })
+    }
+
+    fn history(&self, pid: Pid) -> Vec<(usize, ProcessState)> {
+        self.iter()
+            .enumerate()
+            .filter_map(|(iteration, log)| {
+                log.processes
+                    .get(&pid)
+                    .map(|process| (iteration, process.state))
+            })
+            .collect()
+    }
+
+    fn first_where<P>(&self, predicate: P) -> Option<(usize, &Log)>
+    where
+        P: Fn(&Log) -> bool,
+    {
+        self.iter().enumerate().find(|(_, log)| predicate(log))
+    }
+}
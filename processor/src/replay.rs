@@ -0,0 +1,307 @@
+//! Replay the system calls of a simulation from a text trace.
+//!
+//! A trace has one system call on every line, prefixed by the PID of
+//! the process that issues it:
+//!
+//! ```text
+//! 1 FORK 0
+//! 2 EXEC 5
+//! 2 WAIT 1
+//! 1 SIGNAL 1
+//! 1 EXIT
+//! ```
+//!
+//! `EXEC n` executes `n` units of time, the other lines are the system
+//! calls of [`Process`], with `SLEEP n`, `WAIT event`, `SIGNAL event`,
+//! `FORK priority` and `EXIT`. The process with PID 1 starts the
+//! simulation, and the process created by the n-th `FORK` line has the
+//! PID n + 1. Empty lines and lines starting with `#` are ignored.
+//!
+//! The lines of every process are executed in order, the order of the
+//! lines of different processes is decided by the scheduler.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use scheduler::{Event, Pid, Priority, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult};
+
+use crate::{Log, ParseError, Process, Processor};
+
+/// The system calls issued by the processes of a simulation.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::replay::{self, SyscallTrace};
+/// use processor::Processor;
+/// use std::num::NonZeroUsize;
+///
+/// let scheduler = || scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1);
+/// let logs = Processor::run(scheduler(), |process| {
+///     process.fork(|process| process.wait(1), 0);
+///     process.exec();
+///     process.signal(1);
+/// });
+///
+/// let trace = SyscallTrace::record(&logs);
+/// let text = trace.to_string();
+/// assert!(text.starts_with("1 FORK 0\n"));
+///
+/// let replayed = replay::run(scheduler(), &text.parse().unwrap());
+/// assert_eq!(processor::format_logs(&replayed), processor::format_logs(&logs));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyscallTrace {
+    /// The lines of the trace, in order.
+    pub lines: Vec<TraceLine>,
+}
+
+/// A line of a [`SyscallTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceLine {
+    /// The process that issues the call.
+    pub pid: Pid,
+
+    /// The call.
+    pub call: TraceCall,
+}
+
+/// A call issued by a process of a [`SyscallTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceCall {
+    /// Execute for the given number of time units.
+    Exec(usize),
+
+    /// Create a process with the given priority.
+    Fork(Priority),
+
+    /// Sleep for the given number of time units.
+    Sleep(usize),
+
+    /// Wait for an event.
+    Wait(Event),
+
+    /// Signal an event.
+    Signal(Event),
+
+    /// Stop the process.
+    Exit,
+}
+
+impl Display for TraceCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceCall::Exec(units) => write!(f, "EXEC {units}"),
+            TraceCall::Fork(priority) => write!(f, "FORK {priority}"),
+            TraceCall::Sleep(units) => write!(f, "SLEEP {units}"),
+            TraceCall::Wait(event) => write!(f, "WAIT {event}"),
+            TraceCall::Signal(event) => write!(f, "SIGNAL {event}"),
+            TraceCall::Exit => f.write_str("EXIT"),
+        }
+    }
+}
+
+impl Display for TraceLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.pid, self.call)
+    }
+}
+
+impl Display for SyscallTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.lines.iter().try_for_each(|line| writeln!(f, "{line}"))
+    }
+}
+
+impl FromStr for SyscallTrace {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<SyscallTrace, ParseError> {
+        let mut trace = SyscallTrace::default();
+        // the processes that have been created and if they have exited
+        let mut exited = vec![false];
+        for (index, text) in s.lines().enumerate() {
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| ParseError { line: index + 1, message };
+            let line = line(text).map_err(error)?;
+            match exited.get(line.pid.get() - 1) {
+                None => return Err(error(format!("process {} has not been created", line.pid))),
+                Some(true) => return Err(error(format!("process {} has exited", line.pid))),
+                Some(false) => {}
+            }
+            match line.call {
+                TraceCall::Fork(_) => exited.push(false),
+                TraceCall::Exit => exited[line.pid.get() - 1] = true,
+                _ => {}
+            }
+            trace.lines.push(line);
+        }
+        Ok(trace)
+    }
+}
+
+fn line(text: &str) -> Result<TraceLine, String> {
+    let mut words = text.split_whitespace();
+    // the line is not empty
+    let pid = words.next().unwrap();
+    let pid = pid
+        .parse()
+        .ok()
+        .filter(|pid| *pid > 0)
+        .map(Pid::new)
+        .ok_or_else(|| format!("`{pid}` is not a valid pid"))?;
+    let name = words.next().ok_or_else(|| format!("missing system call in `{text}`"))?;
+    let argument = words.next();
+    if words.next().is_some() {
+        return Err(format!("too many arguments in `{text}`"));
+    }
+    let call = match (name, argument) {
+        ("EXEC", Some(units)) => TraceCall::Exec(number(units)?),
+        ("FORK", Some(priority)) => TraceCall::Fork(number(priority)?),
+        ("SLEEP", Some(units)) => TraceCall::Sleep(number(units)?),
+        ("WAIT", Some(event)) => TraceCall::Wait(self::event(event)?),
+        ("SIGNAL", Some(event)) => TraceCall::Signal(self::event(event)?),
+        ("EXIT", None) => TraceCall::Exit,
+        ("EXIT", Some(_)) => return Err(format!("EXIT has no argument in `{text}`")),
+        ("EXEC" | "FORK" | "SLEEP" | "WAIT" | "SIGNAL", None) => {
+            return Err(format!("missing argument in `{text}`"))
+        }
+        _ => return Err(format!("unknown system call `{name}`")),
+    };
+    Ok(TraceLine { pid, call })
+}
+
+fn number<T: FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("`{value}` is not a valid number"))
+}
+
+fn event(value: &str) -> Result<Event, String> {
+    value.parse().map_err(|_| format!("`{value}` is not a valid event"))
+}
+
+impl SyscallTrace {
+    /// Records the system calls of a simulation from its logs.
+    ///
+    /// The processes are numbered like in a trace, so the PIDs of the
+    /// trace differ from the logs if the scheduler reuses PIDs.
+    /// Replaying the trace with the same scheduler produces the same logs.
+    ///
+    /// * `logs` - the logs of the simulation.
+    pub fn record(logs: &[Log]) -> SyscallTrace {
+        let mut trace = SyscallTrace::default();
+        // the PIDs of the trace for the PIDs of the logs
+        let mut pids = HashMap::new();
+        let mut created = 1;
+        for log in logs {
+            let (SchedulingDecision::Run { pid, timeslice }, Some((reason, result))) = (log.decision, log.stop_reason) else {
+                continue;
+            };
+            // the first process that runs is the one started by the processor
+            let traced = *pids.entry(pid).or_insert(Pid::new(1));
+            let timeslice = timeslice.get();
+            match reason {
+                StopReason::Syscall { syscall, remaining } => {
+                    // the system call uses a unit of time
+                    trace.exec(traced, timeslice.saturating_sub(remaining + 1));
+                    let call = match syscall {
+                        Syscall::Fork(priority) => {
+                            created += 1;
+                            if let SyscallResult::Pid(child) = result {
+                                pids.insert(child, Pid::new(created));
+                            }
+                            TraceCall::Fork(priority)
+                        }
+                        Syscall::Sleep(units) => TraceCall::Sleep(units),
+                        Syscall::Wait(event) => TraceCall::Wait(event),
+                        Syscall::Signal(event) => TraceCall::Signal(event),
+                        Syscall::Exit => {
+                            pids.remove(&pid);
+                            TraceCall::Exit
+                        }
+                    };
+                    trace.lines.push(TraceLine { pid: traced, call });
+                }
+                StopReason::Expired => trace.exec(traced, timeslice),
+                StopReason::Preempted { remaining } => trace.exec(traced, timeslice.saturating_sub(remaining)),
+            }
+        }
+        trace
+    }
+
+    /// Executes the trace from the process with PID 1, like the closure
+    /// given to [`Processor::run`].
+    ///
+    /// * `process` - the process with PID 1.
+    pub fn execute<S: Scheduler + 'static>(&self, process: &Process<S>) {
+        execute(&Arc::new(self.programs()), Pid::new(1), process);
+    }
+
+    // adds execution time, merged with the previous line of the same process
+    fn exec(&mut self, pid: Pid, units: usize) {
+        if units == 0 {
+            return;
+        }
+        match self.lines.last_mut() {
+            Some(TraceLine { pid: last, call: TraceCall::Exec(executed) }) if *last == pid => *executed += units,
+            _ => self.lines.push(TraceLine { pid, call: TraceCall::Exec(units) }),
+        }
+    }
+
+    // the calls of every process and the processes that it creates
+    fn programs(&self) -> HashMap<Pid, Program> {
+        let mut programs: HashMap<Pid, Program> = HashMap::new();
+        let mut created = 1;
+        for line in &self.lines {
+            let program = programs.entry(line.pid).or_default();
+            program.calls.push(line.call);
+            if let TraceCall::Fork(_) = line.call {
+                created += 1;
+                program.children.push(Pid::new(created));
+            }
+        }
+        programs
+    }
+}
+
+#[derive(Default)]
+struct Program {
+    calls: Vec<TraceCall>,
+    children: Vec<Pid>,
+}
+
+/// Runs a simulation of `trace`, like [`Processor::run`].
+///
+/// * `scheduler` - the scheduler to use for the simulation.
+/// * `trace` - the system calls of the simulation.
+pub fn run<S: Scheduler + 'static>(scheduler: S, trace: &SyscallTrace) -> Vec<Log> {
+    Processor::run(scheduler, |process| trace.execute(process))
+}
+
+fn execute<S: Scheduler + 'static>(programs: &Arc<HashMap<Pid, Program>>, pid: Pid, process: &Process<S>) {
+    let Some(program) = programs.get(&pid) else {
+        return;
+    };
+    let mut children = program.children.iter();
+    for call in &program.calls {
+        match *call {
+            TraceCall::Exec(units) => {
+                process.exec_n(units);
+            }
+            TraceCall::Fork(priority) => {
+                // there is a child for every fork
+                let child = *children.next().unwrap();
+                let programs = programs.clone();
+                process.fork(move |process| execute(&programs, child, process), priority);
+            }
+            TraceCall::Sleep(units) => process.sleep(units),
+            TraceCall::Wait(event) => process.wait(event),
+            TraceCall::Signal(event) => process.signal(event),
+            TraceCall::Exit => return,
+        }
+    }
+}
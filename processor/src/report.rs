@@ -0,0 +1,393 @@
+//! Generate reports that can be shared with people that
+//! do not use Rust.
+
+use std::fmt::Write;
+
+use scheduler::ProcessState;
+
+use crate::format::{cells, sorted, HEADER};
+use crate::metrics::Metrics;
+use crate::timeline::{self, Activity};
+use crate::{diff, Log};
+
+/// The width in pixels of a time unit in the Gantt chart.
+const UNIT_WIDTH: usize = 12;
+
+/// The height in pixels of a row in the Gantt chart.
+const ROW_HEIGHT: usize = 24;
+
+/// The width in pixels of the labels column in the Gantt chart.
+const LABEL_WIDTH: usize = 60;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin:0.5em 0 1.5em}\
+th,td{border:1px solid #ccc;padding:2px 8px;text-align:right}\
+th{background:#eee}td.text{text-align:left}\
+.RUNNING{background:#d4f7d4}.WAITING{background:#fff3c4}.STOPPED{background:#f7d4d4}.ZOMBIE{color:#888}\
+details{margin:0.3em 0}summary{cursor:pointer;font-weight:bold}\
+svg text{font-size:11px;font-family:monospace}\
+.sides>tbody>tr>td{vertical-align:top;text-align:left}\
+.divergence>td{outline:3px solid #d33}td.changed{background:#f9b4b4;font-weight:bold}";
+
+/// Escape the characters that have a special meaning in HTML and XML.
+pub fn escape(value: &str) -> String {
+    let mut s = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            '\'' => s.push_str("&#39;"),
+            c => s.push(c),
+        }
+    }
+    s
+}
+
+/// Returns a color for a process, so that every process
+/// is displayed with the same color in the whole report.
+pub(crate) fn color(pid: usize) -> String {
+    format!("hsl({}, 65%, 60%)", (pid * 67) % 360)
+}
+
+/// Render the Gantt chart of a simulation as an inline SVG image.
+///
+/// * `marker` - a time unit marked with a line that links to the
+///   `divergence` anchor of the page.
+pub(crate) fn gantt(logs: &[Log], marker: Option<usize>) -> String {
+    let segments = timeline::segments(logs);
+    let makespan = segments.last().map_or(0, |segment| segment.end);
+    let mut pids = logs
+        .iter()
+        .flat_map(|log| log.processes.keys())
+        .map(|pid| pid.get())
+        .collect::<Vec<_>>();
+    pids.sort();
+    pids.dedup();
+
+    let width = LABEL_WIDTH + makespan * UNIT_WIDTH + 1;
+    let height = (pids.len() + 2) * ROW_HEIGHT;
+    let row = |pid: Option<usize>| {
+        pid.and_then(|pid| pids.iter().position(|other| *other == pid))
+            .unwrap_or(pids.len())
+            * ROW_HEIGHT
+    };
+
+    let mut s = String::new();
+    writeln!(
+        s,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\">"
+    )
+    .unwrap();
+    for pid in &pids {
+        writeln!(
+            s,
+            "<text x=\"4\" y=\"{}\">PID {pid}</text>",
+            row(Some(*pid)) + ROW_HEIGHT * 2 / 3
+        )
+        .unwrap();
+    }
+    writeln!(
+        s,
+        "<text x=\"4\" y=\"{}\">Idle</text>",
+        row(None) + ROW_HEIGHT * 2 / 3
+    )
+    .unwrap();
+
+    for segment in &segments {
+        let x = LABEL_WIDTH + segment.start * UNIT_WIDTH;
+        let w = segment.duration() * UNIT_WIDTH;
+        let (y, fill, title) = match segment.activity {
+            Activity::Run { pid, stop_reason } => (
+                row(Some(pid.get())),
+                color(pid.get()),
+                format!("Run {pid}: {stop_reason}"),
+            ),
+            Activity::Sleep => (row(None), String::from("#bbb"), String::from("Sleep")),
+        };
+        writeln!(
+            s,
+            "<rect x=\"{x}\" y=\"{}\" width=\"{w}\" height=\"{}\" fill=\"{fill}\" stroke=\"#333\"><title>{} [{}, {})</title></rect>",
+            y + 2,
+            ROW_HEIGHT - 4,
+            escape(&title),
+            segment.start,
+            segment.end
+        )
+        .unwrap();
+    }
+
+    let axis = (pids.len() + 1) * ROW_HEIGHT;
+    let step = (makespan / 20).max(1);
+    for time in (0..=makespan).step_by(step) {
+        writeln!(
+            s,
+            "<text x=\"{}\" y=\"{}\">{time}</text>",
+            LABEL_WIDTH + time * UNIT_WIDTH,
+            axis + ROW_HEIGHT / 2
+        )
+        .unwrap();
+    }
+    if let Some(time) = marker {
+        writeln!(
+            s,
+            "<a href=\"#divergence\"><line x1=\"{x}\" y1=\"0\" x2=\"{x}\" y2=\"{axis}\" stroke=\"#d33\" stroke-width=\"3\"><title>First divergence at {time}</title></line></a>",
+            x = LABEL_WIDTH + time * UNIT_WIDTH
+        )
+        .unwrap();
+    }
+    s.push_str("</svg>\n");
+    s
+}
+
+/// Render the summary statistics as an HTML table.
+pub(crate) fn summary(metrics: &Metrics) -> String {
+    let optional = |value: Option<f64>| value.map_or(String::from("-"), |value| format!("{value:.2}"));
+    let mut s = String::from("<table>\n");
+    for (name, value) in [
+        ("Iterations", metrics.iterations.to_string()),
+        ("Makespan", metrics.makespan.to_string()),
+        ("Busy", metrics.busy.to_string()),
+        ("Idle", metrics.idle.to_string()),
+        (
+            "Utilization",
+            format!("{:.1}%", metrics.utilization() * 100.0),
+        ),
+        ("Context switches", metrics.context_switches.to_string()),
+        ("Average turnaround", optional(metrics.average_turnaround())),
+        ("Average waiting", optional(metrics.average_waiting())),
+        ("Average response", optional(metrics.average_response())),
+        (
+            "Scheduler counters",
+            metrics.scheduler.map_or(String::from("-"), |stats| {
+                format!(
+                    "{} context switches, {} preemptions, {} idle, {} wakeups",
+                    stats.context_switches, stats.preemptions, stats.idle, stats.wakeups
+                )
+            }),
+        ),
+        (
+            "Outcome",
+            metrics
+                .outcome
+                .map_or(String::from("-"), |outcome| outcome.to_string()),
+        ),
+    ] {
+        writeln!(
+            s,
+            "<tr><th>{name}</th><td class=\"text\">{}</td></tr>",
+            escape(&value)
+        )
+        .unwrap();
+    }
+    s.push_str("</table>\n<table>\n<tr><th>PID</th><th>PRI</th><th>ARRIVAL</th><th>FIRST RUN</th><th>COMPLETION</th><th>RUNS</th><th>RUNNING</th><th>READY</th><th>WAITING</th><th>TURNAROUND</th></tr>\n");
+    for process in &metrics.processes {
+        let optional = |value: Option<usize>| value.map_or(String::from("-"), |value| value.to_string());
+        writeln!(
+            s,
+            "<tr><td style=\"background:{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            color(process.pid.get()),
+            process.pid,
+            process.priority,
+            process.arrival,
+            optional(process.first_run),
+            optional(process.completion),
+            process.runs,
+            process.running,
+            process.ready,
+            process.waiting,
+            optional(process.turnaround())
+        )
+        .unwrap();
+    }
+    s.push_str("</table>\n");
+    s
+}
+
+/// Render the process table of every iteration.
+pub(crate) fn iterations(logs: &[Log]) -> String {
+    let mut s = String::new();
+    for (iteration, log) in logs.iter().enumerate() {
+        writeln!(
+            s,
+            "<details><summary>Iteration {}: {}</summary>",
+            iteration + 1,
+            escape(&log.decision.to_string())
+        )
+        .unwrap();
+        s.push_str(&table(log, None));
+        s.push_str("</details>\n");
+    }
+    s
+}
+
+// the process table of an iteration and the reason the process stopped,
+// with the cells that differ from the ones of `other` highlighted
+fn table(log: &Log, other: Option<&Log>) -> String {
+    let mut s = String::from("\n<table><tr>");
+    for title in HEADER {
+        write!(s, "<th>{title}</th>").unwrap();
+    }
+    s.push_str("</tr>\n");
+    for process in sorted(log) {
+        let class = match process.state {
+            ProcessState::Running => "RUNNING",
+            ProcessState::Waiting { .. } => "WAITING",
+            ProcessState::Stopped => "STOPPED",
+            ProcessState::Zombie => "ZOMBIE",
+            _ => "",
+        };
+        let others = other.and_then(|other| other.processes.get(&process.pid)).map(cells);
+        write!(s, "<tr class=\"{class}\">").unwrap();
+        for (column, cell) in cells(process).iter().enumerate() {
+            let changed = others.as_ref().is_some_and(|others| others[column] != *cell);
+            let class = match (changed, column == 1 || column == 6) {
+                (true, _) => " class=\"changed\"",
+                (false, true) => " class=\"text\"",
+                (false, false) => "",
+            };
+            write!(s, "<td{class}>{}</td>", escape(cell)).unwrap();
+        }
+        s.push_str("</tr>\n");
+    }
+    s.push_str("</table>\n");
+    if let Some((reason, result)) = log.stop_reason {
+        writeln!(s, "<p>{}</p>", escape(&format!("{reason} -> {result:?}"))).unwrap();
+    }
+    s
+}
+
+/// Generate a self-contained HTML report of a simulation.
+///
+/// The report contains summary statistics, a Gantt chart of the
+/// simulation rendered as an inline SVG image and the process table
+/// of every iteration. It does not reference any external resources,
+/// so it can be shared as a single file.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+/// * `metrics` - the statistics computed from the same logs.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::metrics::Metrics;
+/// use processor::{report, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     process.fork(|process| process.exec(), 0);
+///     process.sleep(3);
+/// });
+///
+/// let html = report::html(&logs, &Metrics::new(&logs));
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// ```
+pub fn html(logs: &[Log], metrics: &Metrics) -> String {
+    let mut s = String::new();
+    writeln!(
+        s,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Scheduler report</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    )
+    .unwrap();
+    s.push_str("<h1>Scheduler report</h1>\n<h2>Summary</h2>\n");
+    s.push_str(&summary(metrics));
+    s.push_str("<h2>Timeline</h2>\n");
+    s.push_str(&gantt(logs, None));
+    s.push_str("<h2>Iterations</h2>\n");
+    s.push_str(&iterations(logs));
+    s.push_str("</body>\n</html>\n");
+    s
+}
+
+/// Generate a self-contained HTML page that compares the logs of a
+/// simulation with reference logs.
+///
+/// The page shows the Gantt charts of both simulations, one above the
+/// other, with a line at the time of the first iteration where they
+/// differ, and the process tables of both simulations side by side.
+/// The first divergent iteration is highlighted and open, and the lines
+/// of the charts link to it. The cells of a process that differ from the
+/// other simulation are highlighted in every iteration.
+///
+/// * `expected` - the reference logs.
+/// * `actual` - the logs that are verified.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{report, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let run = |timeslice| {
+///     Processor::run(scheduler::round_robin(NonZeroUsize::new(timeslice).unwrap(), 1), |process| {
+///         process.fork(|process| process.exec(), 0);
+///         process.sleep(3);
+///     })
+/// };
+///
+/// let html = report::html_diff(&run(2), &run(3));
+/// assert!(html.contains("id=\"divergence\""));
+/// assert!(!report::html_diff(&run(2), &run(2)).contains("id=\"divergence\""));
+/// ```
+pub fn html_diff(expected: &[Log], actual: &[Log]) -> String {
+    let result = diff(expected, actual);
+    // the time when the divergent iteration starts, in both simulations
+    let marker = |logs: &[Log]| {
+        result.iteration.map(|iteration| {
+            timeline::segments(logs)
+                .iter()
+                .take_while(|segment| segment.iteration < iteration)
+                .last()
+                .map_or(0, |segment| segment.end)
+        })
+    };
+
+    let mut s = String::new();
+    writeln!(
+        s,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Scheduler diff</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    )
+    .unwrap();
+    s.push_str("<h1>Scheduler diff</h1>\n");
+    match result.iteration {
+        Some(iteration) => writeln!(
+            s,
+            "<p><a href=\"#divergence\">The logs diverge at iteration {}.</a></p>\n<pre>{}</pre>",
+            iteration + 1,
+            escape(&result.to_string())
+        )
+        .unwrap(),
+        None => s.push_str("<p>The logs are identical.</p>\n"),
+    }
+    s.push_str("<h2>Expected timeline</h2>\n");
+    s.push_str(&gantt(expected, marker(expected)));
+    s.push_str("<h2>Actual timeline</h2>\n");
+    s.push_str(&gantt(actual, marker(actual)));
+
+    s.push_str("<h2>Iterations</h2>\n<table class=\"sides\">\n<tr><th>Iteration</th><th>Expected</th><th>Actual</th></tr>\n");
+    for iteration in 0..expected.len().max(actual.len()) {
+        let (left, right) = (expected.get(iteration), actual.get(iteration));
+        let divergent = result.iteration == Some(iteration);
+        let side = |log: Option<&Log>, other: Option<&Log>| match log {
+            Some(log) => format!(
+                "<details{}><summary>{}</summary>{}</details>",
+                if divergent { " open" } else { "" },
+                escape(&log.decision.to_string()),
+                table(log, other)
+            ),
+            None => String::from("<p>No iteration</p>"),
+        };
+        writeln!(
+            s,
+            "<tr{}><th>{}</th><td>{}</td><td>{}</td></tr>",
+            if divergent { " id=\"divergence\" class=\"divergence\"" } else { "" },
+            iteration + 1,
+            side(left, right),
+            side(right, left)
+        )
+        .unwrap();
+    }
+    s.push_str("</table>\n</body>\n</html>\n");
+    s
+}
@@ -0,0 +1,610 @@
+//! Describe the workload of a simulation in a file instead of a
+//! closure, so workloads can be written without Rust.
+//!
+//! A scenario is a TOML document with the instructions of the process
+//! with PID 1. Every instruction is a table with a single key:
+//!
+//! ```toml
+//! instructions = [
+//!     { exec = 4 },
+//!     { fork = { priority = 5, instructions = [
+//!         { exec = 2 },
+//!         { wait = 1 },
+//!     ] } },
+//!     { sleep = 10 },
+//!     { signal = 1 },
+//!     { repeat = { times = 3, instructions = [{ sleep = 1 }, { exec = 2 }] } },
+//! ]
+//! ```
+//!
+//! Events are numbers for user events, or strings like `"io:3"`. The
+//! same instructions can be written in Rust with a [`WorkloadBuilder`].
+
+use std::fmt::{self, Display};
+use std::fs;
+use std::mem;
+use std::path::Path;
+use std::str::FromStr;
+
+use scheduler::{Event, Scheduler};
+use serde::{Deserialize, Deserializer};
+
+use crate::{engine, Log, Process, Processor};
+
+/// A workload described by data instead of a closure.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::scenario::{self, Scenario};
+/// use std::num::NonZeroUsize;
+///
+/// let scenario: Scenario = r#"
+///     instructions = [
+///         { fork = { instructions = [{ wait = 1 }] } },
+///         { exec = 3 },
+///         { signal = 1 },
+///         { sleep = 2 },
+///     ]
+/// "#.parse().unwrap();
+///
+/// let logs = scenario::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), &scenario);
+/// assert_eq!(logs.last().unwrap().decision.to_string(), "Done, no more processes");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// The instructions of the process with PID 1.
+    pub instructions: Vec<Instruction>,
+}
+
+/// An instruction executed by a process of a [`Scenario`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Instruction {
+    /// Execute for the given number of time units.
+    Exec(usize),
+
+    /// Sleep for the given number of time units.
+    Sleep(usize),
+
+    /// Wait for an event.
+    Wait(#[serde(deserialize_with = "event")] Event),
+
+    /// Signal an event.
+    Signal(#[serde(deserialize_with = "event")] Event),
+
+    /// Create a process.
+    Fork(Child),
+
+    /// Execute instructions several times.
+    Repeat(Repeat),
+}
+
+/// A process created by [`Instruction::Fork`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Child {
+    /// The priority of the process, 0 if it is not given.
+    #[serde(default)]
+    pub priority: i8,
+
+    /// The instructions of the process.
+    pub instructions: Vec<Instruction>,
+}
+
+/// The instructions repeated by [`Instruction::Repeat`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Repeat {
+    /// How many times the instructions are executed.
+    pub times: usize,
+
+    /// The instructions to repeat.
+    pub instructions: Vec<Instruction>,
+}
+
+/// An error returned when a scenario cannot be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioError {
+    /// The description of the error.
+    pub message: String,
+}
+
+impl Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl FromStr for Scenario {
+    type Err = ScenarioError;
+
+    fn from_str(s: &str) -> Result<Scenario, ScenarioError> {
+        toml::from_str(s).map_err(|error| ScenarioError {
+            message: error.to_string(),
+        })
+    }
+}
+
+impl Scenario {
+    /// Reads a scenario from a TOML file.
+    ///
+    /// * `path` - the path of the file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Scenario, ScenarioError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|error| ScenarioError {
+            message: format!("Unable to read {}: {error}", path.display()),
+        })?;
+        text.parse().map_err(|error: ScenarioError| ScenarioError {
+            message: format!("{}: {}", path.display(), error.message),
+        })
+    }
+
+    /// The number of instructions, with the instructions of every fork
+    /// and repeat.
+    pub fn size(&self) -> usize {
+        size(&self.instructions)
+    }
+
+    /// Executes the instructions of the process with PID 1, like the
+    /// closure given to [`Processor::run`].
+    ///
+    /// * `process` - the process with PID 1.
+    pub fn execute<S: Scheduler + 'static>(&self, process: &Process<S>) {
+        execute(&self.instructions, process);
+    }
+
+    /// Returns a [`WorkloadBuilder`] for the instructions of the process
+    /// with PID 1.
+    pub fn builder() -> WorkloadBuilder {
+        WorkloadBuilder::new()
+    }
+}
+
+/// Adds the instructions of a process one after the other, to write a
+/// [`Scenario`] in Rust.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::scenario::{Scenario, WorkloadBuilder};
+/// use processor::Processor;
+/// use std::num::NonZeroUsize;
+///
+/// let workload = WorkloadBuilder::new()
+///     .exec(4)
+///     .fork(WorkloadBuilder::new().exec(2).wait(1), 0)
+///     .sleep(10)
+///     .signal(1)
+///     .sleep(5);
+///
+/// let scenario = workload.clone().build();
+/// assert_eq!(scenario.to_string().parse::<Scenario>().unwrap(), scenario);
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), workload.closure());
+/// assert_eq!(logs.last().unwrap().decision.to_string(), "Done, no more processes");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkloadBuilder {
+    instructions: Vec<Instruction>,
+}
+
+impl WorkloadBuilder {
+    /// A process without instructions.
+    pub fn new() -> WorkloadBuilder {
+        WorkloadBuilder::default()
+    }
+
+    /// Executes for `units` time units.
+    pub fn exec(self, units: usize) -> Self {
+        self.push(Instruction::Exec(units))
+    }
+
+    /// Sleeps for `units` time units.
+    pub fn sleep(self, units: usize) -> Self {
+        self.push(Instruction::Sleep(units))
+    }
+
+    /// Waits for an event.
+    ///
+    /// * `event` - the event to wait for, numbers are user events.
+    pub fn wait(self, event: impl Into<Event>) -> Self {
+        self.push(Instruction::Wait(event.into()))
+    }
+
+    /// Signals an event.
+    ///
+    /// * `event` - the event to signal, numbers are user events.
+    pub fn signal(self, event: impl Into<Event>) -> Self {
+        self.push(Instruction::Signal(event.into()))
+    }
+
+    /// Creates a process.
+    ///
+    /// * `child` - the instructions of the process.
+    /// * `priority` - the priority of the process.
+    pub fn fork(self, child: WorkloadBuilder, priority: i8) -> Self {
+        self.push(Instruction::Fork(Child {
+            priority,
+            instructions: child.instructions,
+        }))
+    }
+
+    /// Executes instructions several times.
+    ///
+    /// * `times` - how many times the instructions are executed.
+    /// * `body` - the instructions to repeat.
+    pub fn repeat(self, times: usize, body: WorkloadBuilder) -> Self {
+        self.push(Instruction::Repeat(Repeat {
+            times,
+            instructions: body.instructions,
+        }))
+    }
+
+    /// The scenario whose process with PID 1 has the instructions.
+    pub fn build(self) -> Scenario {
+        Scenario {
+            instructions: self.instructions,
+        }
+    }
+
+    /// The closure of the process with PID 1, for [`Processor::run`].
+    pub fn closure<S: Scheduler + 'static>(self) -> impl FnOnce(&Process<S>) + Send + 'static {
+        let scenario = self.build();
+        move |process| scenario.execute(process)
+    }
+
+    fn push(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+}
+
+fn size(instructions: &[Instruction]) -> usize {
+    instructions
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::Fork(child) => 1 + size(&child.instructions),
+            Instruction::Repeat(repeat) => 1 + size(&repeat.instructions),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Runs a simulation of `scenario`, like [`Processor::run`].
+///
+/// * `scheduler` - the scheduler to use for the simulation.
+/// * `scenario` - the workload of the simulation.
+pub fn run<S: Scheduler + 'static>(scheduler: S, scenario: &Scenario) -> Vec<Log> {
+    Processor::run(scheduler, |process| scenario.execute(process))
+}
+
+/// Runs a simulation of `scenario` without threads.
+///
+/// The logs are the same as the ones returned by [`run`], but the
+/// processes run one after another on the calling thread, so this also
+/// works where threads are not available, like WebAssembly. There is no
+/// narration.
+///
+/// * `scheduler` - the scheduler to use for the simulation.
+/// * `scenario` - the workload of the simulation.
+///
+/// ## Panics
+///
+/// Panics if the scheduler rejects the priority of a process, like
+/// [`Process::fork`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::scenario::{self, Scenario};
+/// use std::num::NonZeroUsize;
+///
+/// let scenario: Scenario = "instructions = [{ fork = { instructions = [{ exec = 5 }] } }, { sleep = 10 }]".parse().unwrap();
+/// let scheduler = || scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+///
+/// assert_eq!(scenario::simulate(scheduler(), &scenario), scenario::run(scheduler(), &scenario));
+/// ```
+pub fn simulate<S: Scheduler>(scheduler: S, scenario: &Scenario) -> Vec<Log> {
+    engine::run(scheduler, scenario)
+}
+
+/// Generates a random scenario, the same one for the same arguments.
+///
+/// Every process has a few random instructions and forks its children
+/// at random places. Waits and signals use events from 1 to `events`,
+/// only the process with PID 1 never waits. It ends by sleeping longer
+/// than all the other processes run and signaling every event, once for
+/// every wait of a process and of its ancestors, so that the simulation
+/// ends with [`Done`](scheduler::SchedulingDecision::Done) and PID 1
+/// exits last.
+///
+/// * `seed` - the seed of the random numbers.
+/// * `processes` - the number of processes, including PID 1, at least 1.
+/// * `events` - the number of events, 0 for no waits and signals.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::scenario::{self, Scenario};
+///
+/// let scenario = scenario::generate(42, 10, 3);
+/// assert_eq!(scenario, scenario::generate(42, 10, 3));
+/// assert_eq!(scenario.to_string().parse::<Scenario>().unwrap(), scenario);
+/// ```
+pub fn generate(seed: u64, processes: usize, events: usize) -> Scenario {
+    // there is always the process with PID 1
+    let processes = processes.max(1);
+    let mut random = Random(seed);
+    // the parent of every process after PID 1, which is created before it
+    let parents = (2..=processes).map(|pid| random.below(pid - 1) + 1).collect::<Vec<_>>();
+    let mut bodies = (0..processes).map(|pid| body(&mut random, events, pid > 0)).collect::<Vec<_>>();
+    // a process is forked after the waits of its ancestors, so every
+    // round of signals lets it pass one of the waits of the chain
+    let mut waits = vec![0];
+    for (index, parent) in parents.iter().enumerate() {
+        let own = bodies[index + 1].iter().filter(|instruction| matches!(instruction, Instruction::Wait(_))).count();
+        waits.push(waits[parent - 1] + own);
+    }
+    // the processes need at most one time unit for every instruction and
+    // for every time unit of an instruction, one to be forked and one to exit
+    let units = bodies
+        .iter()
+        .flatten()
+        .map(|instruction| match instruction {
+            Instruction::Exec(units) | Instruction::Sleep(units) => units + 1,
+            _ => 1,
+        })
+        .sum::<usize>()
+        + 2 * processes;
+
+    // the children are added from the last one, so that their own
+    // children are already added
+    for (index, parent) in parents.iter().enumerate().rev() {
+        let child = Child {
+            priority: random.below(6) as i8,
+            instructions: mem::take(&mut bodies[index + 1]),
+        };
+        let position = random.below(bodies[parent - 1].len() + 1);
+        bodies[parent - 1].insert(position, Instruction::Fork(child));
+    }
+    let waits = waits.into_iter().max().unwrap_or(0);
+    let mut instructions = mem::take(&mut bodies[0]);
+    if waits > 0 {
+        let mut round = vec![Instruction::Sleep(units)];
+        round.extend((1..=events).map(|event| Instruction::Signal(Event::user(event))));
+        instructions.push(Instruction::Repeat(Repeat {
+            times: waits,
+            instructions: round,
+        }));
+    }
+    instructions.push(Instruction::Sleep(units));
+    Scenario { instructions }
+}
+
+// the random instructions of a process, without forks
+fn body(random: &mut Random, events: usize, wait: bool) -> Vec<Instruction> {
+    (0..random.below(4) + 1)
+        .map(|_| match random.below(10) {
+            0 if events > 0 && wait => Instruction::Wait(Event::user(random.below(events) + 1)),
+            1 | 2 if events > 0 => Instruction::Signal(Event::user(random.below(events) + 1)),
+            3..=5 => Instruction::Sleep(random.below(8) + 1),
+            _ => Instruction::Exec(random.below(8) + 1),
+        })
+        .collect()
+}
+
+/// Returns a smaller scenario that still fails, to find the cause of a
+/// failure found with a large scenario, like one from [`generate`].
+///
+/// Repeatedly tries to remove an instruction, to replace a fork or a
+/// repeat by its instructions, to lower the priority of a fork and to
+/// lower the numbers of the instructions, and keeps every change after
+/// which `fails` still returns `true`, until no change can be kept.
+/// Returns `scenario` itself if it does not fail.
+///
+/// * `scenario` - the scenario that fails.
+/// * `fails` - returns `true` if a scenario fails.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::scenario::{self, Instruction, Scenario};
+///
+/// // the scenarios that sleep for at least 3 time units fail
+/// let fails = |scenario: &Scenario| {
+///     scenario.instructions.iter().any(|instruction| matches!(instruction, Instruction::Sleep(units) if *units >= 3))
+/// };
+/// let scenario = scenario::generate(7, 20, 3);
+/// assert!(fails(&scenario));
+///
+/// let shrunk = scenario::shrink(&scenario, fails);
+/// assert_eq!(shrunk.instructions, [Instruction::Sleep(3)]);
+/// ```
+pub fn shrink<F: FnMut(&Scenario) -> bool>(scenario: &Scenario, mut fails: F) -> Scenario {
+    let mut smallest = scenario.clone();
+    if !fails(&smallest) {
+        return smallest;
+    }
+    // the first change that keeps the failure is kept, and the changes
+    // of the smaller scenario are tried again from the start
+    while let Some(smaller) = candidates(&smallest.instructions)
+        .into_iter()
+        .map(|instructions| Scenario { instructions })
+        .find(|candidate| fails(candidate))
+    {
+        smallest = smaller;
+    }
+    smallest
+}
+
+// the instructions with one change each, the changes that remove the
+// most instructions first
+fn candidates(instructions: &[Instruction]) -> Vec<Vec<Instruction>> {
+    let replace = |index: usize, replacement: Vec<Instruction>| {
+        let mut candidate = instructions[..index].to_vec();
+        candidate.extend(replacement);
+        candidate.extend_from_slice(&instructions[index + 1..]);
+        candidate
+    };
+    let mut candidates = Vec::new();
+    for index in 0..instructions.len() {
+        candidates.push(replace(index, Vec::new()));
+    }
+    for (index, instruction) in instructions.iter().enumerate() {
+        match instruction {
+            Instruction::Fork(child) => {
+                candidates.push(replace(index, child.instructions.clone()));
+                if child.priority != 0 {
+                    candidates.push(replace(index, vec![Instruction::Fork(Child { priority: 0, ..child.clone() })]));
+                }
+                for instructions in candidates_of(&child.instructions) {
+                    candidates.push(replace(index, vec![Instruction::Fork(Child { instructions, ..child.clone() })]));
+                }
+            }
+            Instruction::Repeat(repeat) => {
+                candidates.push(replace(index, repeat.instructions.clone()));
+                for times in smaller(repeat.times) {
+                    candidates.push(replace(index, vec![Instruction::Repeat(Repeat { times, ..repeat.clone() })]));
+                }
+                for instructions in candidates_of(&repeat.instructions) {
+                    candidates.push(replace(index, vec![Instruction::Repeat(Repeat { instructions, ..repeat.clone() })]));
+                }
+            }
+            Instruction::Exec(units) => candidates.extend(smaller(*units).map(|units| replace(index, vec![Instruction::Exec(units)]))),
+            Instruction::Sleep(units) => candidates.extend(smaller(*units).map(|units| replace(index, vec![Instruction::Sleep(units)]))),
+            Instruction::Wait(_) | Instruction::Signal(_) => {}
+        }
+    }
+    candidates
+}
+
+// the candidates of the instructions of a fork or a repeat, which keep
+// at least one instruction
+fn candidates_of(instructions: &[Instruction]) -> Vec<Vec<Instruction>> {
+    candidates(instructions).into_iter().filter(|candidate| !candidate.is_empty()).collect()
+}
+
+// smaller numbers than `number` that are at least 1, the smallest first
+fn smaller(number: usize) -> impl Iterator<Item = usize> {
+    let mut numbers = vec![1, number / 2, number.saturating_sub(1)];
+    numbers.retain(|smaller| (1..number).contains(smaller));
+    numbers.dedup();
+    numbers.into_iter()
+}
+
+/// The SplitMix64 generator, so that a seed gives the same scenario
+/// with every version of the dependencies.
+struct Random(u64);
+
+impl Random {
+    // a number from 0 to `bound - 1`
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z % bound as u64) as usize
+    }
+}
+
+/// Writes the scenario as a TOML document that can be read again, with
+/// an instruction on every line.
+impl Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("instructions = ")?;
+        write_instructions(f, &self.instructions, 0)?;
+        writeln!(f)
+    }
+}
+
+// writes an array of instructions, indented by `indent` levels
+fn write_instructions(f: &mut fmt::Formatter<'_>, instructions: &[Instruction], indent: usize) -> fmt::Result {
+    if instructions.is_empty() {
+        return f.write_str("[]");
+    }
+    f.write_str("[\n")?;
+    let padding = "    ".repeat(indent + 1);
+    for instruction in instructions {
+        f.write_str(&padding)?;
+        match instruction {
+            Instruction::Exec(units) => write!(f, "{{ exec = {units} }}")?,
+            Instruction::Sleep(units) => write!(f, "{{ sleep = {units} }}")?,
+            Instruction::Wait(event) => write!(f, "{{ wait = {} }}", EventValue(*event))?,
+            Instruction::Signal(event) => write!(f, "{{ signal = {} }}", EventValue(*event))?,
+            Instruction::Fork(child) => {
+                f.write_str("{ fork = { ")?;
+                if child.priority != 0 {
+                    write!(f, "priority = {}, ", child.priority)?;
+                }
+                f.write_str("instructions = ")?;
+                write_instructions(f, &child.instructions, indent + 1)?;
+                f.write_str(" } }")?;
+            }
+            Instruction::Repeat(repeat) => {
+                write!(f, "{{ repeat = {{ times = {}, instructions = ", repeat.times)?;
+                write_instructions(f, &repeat.instructions, indent + 1)?;
+                f.write_str(" } }")?;
+            }
+        }
+        f.write_str(",\n")?;
+    }
+    write!(f, "{}]", "    ".repeat(indent))
+}
+
+/// An event as a TOML value: a number for user events, a string otherwise.
+struct EventValue(Event);
+
+impl Display for EventValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let event = self.0.to_string();
+        match event.contains(':') {
+            true => write!(f, "\"{event}\""),
+            false => f.write_str(&event),
+        }
+    }
+}
+
+fn execute<S: Scheduler + 'static>(instructions: &[Instruction], process: &Process<S>) {
+    for instruction in instructions {
+        match instruction {
+            Instruction::Exec(units) => {
+                process.exec_n(*units);
+            }
+            Instruction::Sleep(units) => process.sleep(*units),
+            Instruction::Wait(event) => process.wait(*event),
+            Instruction::Signal(event) => process.signal(*event),
+            Instruction::Fork(child) => {
+                // the child runs on its own thread, it gets its own instructions
+                let instructions = child.instructions.clone();
+                process.fork(move |process| execute(&instructions, process), child.priority);
+            }
+            Instruction::Repeat(repeat) => {
+                for _ in 0..repeat.times {
+                    execute(&repeat.instructions, process);
+                }
+            }
+        }
+    }
+}
+
+// an event is a number for user events, or its name
+fn event<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Event, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(usize),
+        Name(String),
+    }
+
+    match Value::deserialize(deserializer)? {
+        Value::Number(number) => Ok(Event::user(number)),
+        Value::Name(name) => name
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("`{name}` is not a valid event"))),
+    }
+}
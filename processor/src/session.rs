@@ -0,0 +1,152 @@
+//! A simulation driven one step at a time by the caller instead of
+//! by the closures of processes.
+
+use scheduler::{Pid, Priority, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult};
+
+use crate::Log;
+
+/// A simulation where the caller performs the actions of the running
+/// process, one at a time, on the calling thread.
+///
+/// The session asks the scheduler the same questions as the
+/// [`Processor`](crate::Processor) in the same order, so the logs are
+/// the same as the ones of a closure that performs the same actions.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Session;
+/// use scheduler::{Priority, Syscall, SyscallResult};
+/// use std::num::NonZeroUsize;
+///
+/// let mut session = Session::new(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1));
+/// assert_eq!(session.running().unwrap(), 1);
+///
+/// let SyscallResult::Pid(child) = session.syscall(Syscall::Fork(Priority::new(0))) else {
+///     panic!("the fork has failed");
+/// };
+/// assert_eq!(child, 2);
+///
+/// // PID 1 uses the rest of its timeslice, so PID 2 runs and exits first
+/// session.exec();
+/// session.exec();
+/// assert_eq!(session.running(), Some(child));
+/// session.syscall(Syscall::Exit);
+/// session.syscall(Syscall::Exit);
+/// assert_eq!(session.running(), None);
+/// assert_eq!(session.logs().last().unwrap().decision.to_string(), "Done, no more processes");
+/// ```
+pub struct Session<S: Scheduler> {
+    scheduler: S,
+    /// The process that is running, [`None`] once the simulation has ended.
+    running: Option<Pid>,
+    remaining: usize,
+    logs: Vec<Log>,
+}
+
+impl<S: Scheduler> Session<S> {
+    /// Starts a simulation by creating the process with PID 1, like
+    /// [`Processor::run`](crate::Processor::run).
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the scheduler does not return PID 1 for the first process.
+    pub fn new(scheduler: S) -> Session<S> {
+        let mut session = Session {
+            scheduler,
+            running: None,
+            remaining: 1,
+            logs: Vec::new(),
+        };
+        let SyscallResult::Pid(pid) = session.stop(StopReason::syscall(Syscall::Fork(Priority::new(0)))) else {
+            panic!("Fork did not return a pid");
+        };
+        if pid != 1 {
+            panic!("Scheduler did not return PID 1 for the first process");
+        }
+        session
+    }
+
+    /// The process that is running, [`None`] once the simulation has ended.
+    pub fn running(&self) -> Option<Pid> {
+        self.running
+    }
+
+    /// The time units left from the timeslice of the running process.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// The running process executes one unit of time, like
+    /// [`Process::exec`](crate::Process::exec). Does nothing once the
+    /// simulation has ended.
+    pub fn exec(&mut self) {
+        if self.running.is_none() {
+            return;
+        }
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.stop(StopReason::expired());
+        } else if self.scheduler.preempt(self.remaining) {
+            self.stop(StopReason::preempted());
+        }
+    }
+
+    /// The running process sends a system call.
+    ///
+    /// Returns [`SyscallResult::NoRunningProcess`] once the simulation
+    /// has ended.
+    ///
+    /// * `syscall` - the system call.
+    pub fn syscall(&mut self, syscall: Syscall) -> SyscallResult {
+        if self.running.is_none() {
+            return SyscallResult::NoRunningProcess;
+        }
+        self.stop(StopReason::syscall(syscall))
+    }
+
+    /// The logs of the decisions taken so far.
+    pub fn logs(&self) -> &[Log] {
+        &self.logs
+    }
+
+    /// Ends the session and returns its logs.
+    pub fn into_logs(self) -> Vec<Log> {
+        self.logs
+    }
+
+    fn stop(&mut self, mut reason: StopReason) -> SyscallResult {
+        // a preemption does not use a time unit
+        if !matches!(reason, StopReason::Preempted { .. }) {
+            self.remaining = self.remaining.wrapping_sub(1);
+        }
+        reason.set_remaining(self.remaining);
+        let result = self.scheduler.stop(reason);
+        if let Some(log) = self.logs.last_mut() {
+            log.stop_reason = Some((reason, result));
+        }
+        self.schedule();
+        result
+    }
+
+    /// Asks the scheduler for decisions until a process is scheduled
+    /// or the simulation ends.
+    fn schedule(&mut self) {
+        self.running = None;
+        loop {
+            let next = self.scheduler.next();
+            self.logs.push(Log::snapshot(&mut self.scheduler, next, true));
+            match next {
+                SchedulingDecision::Run { pid, timeslice } => {
+                    self.remaining = timeslice.get();
+                    self.running = Some(pid);
+                    return;
+                }
+                SchedulingDecision::Sleep(time) => self.scheduler.on_tick(time),
+                SchedulingDecision::Deadlock | SchedulingDecision::Panic | SchedulingDecision::Done => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Write the [`Processor`](crate::Processor)'s logs while the
+//! simulation is running.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{json, Log, LogFormatter, PlainFormatter};
+
+/// A destination for the logs of a simulation.
+///
+/// The [`Processor`](crate::Processor) calls [`LogSink::write`] as soon
+/// as an iteration is complete (the process that was running has
+/// stopped), so logs can be stored without keeping all of them in memory.
+pub trait LogSink: Send {
+    /// Write an iteration.
+    ///
+    /// * `iteration` - the iteration number, starting from 0.
+    /// * `log` - the iteration's log.
+    fn write(&mut self, iteration: usize, log: &Log) -> io::Result<()>;
+
+    /// Called after the last iteration was written.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The format used by a [`WriterSink`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SinkFormat {
+    /// The format produced by [`format_logs`](crate::format_logs).
+    Text,
+
+    /// One JSON object for every iteration, on separate lines.
+    Json,
+}
+
+/// A [`LogSink`] that writes logs to any [`Write`] implementation.
+pub struct WriterSink<W: Write + Send> {
+    writer: W,
+    format: SinkFormat,
+}
+
+impl<W: Write + Send> WriterSink<W> {
+    /// Create a new sink.
+    ///
+    /// * `writer` - where the logs are written.
+    /// * `format` - how the logs are written.
+    pub fn new(writer: W, format: SinkFormat) -> WriterSink<W> {
+        WriterSink { writer, format }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Send> LogSink for WriterSink<W> {
+    fn write(&mut self, iteration: usize, log: &Log) -> io::Result<()> {
+        match self.format {
+            SinkFormat::Text => self
+                .writer
+                .write_all(PlainFormatter::default().format_iteration(log, iteration).as_bytes()),
+            SinkFormat::Json => writeln!(self.writer, "{}", json::log(iteration, log)),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A [`LogSink`] that writes logs to a file.
+pub type FileSink = WriterSink<BufWriter<File>>;
+
+impl FileSink {
+    /// Create (or truncate) the file at `path` and write logs to it.
+    ///
+    /// * `path` - the path of the file.
+    /// * `format` - how the logs are written.
+    pub fn create<P: AsRef<Path>>(path: P, format: SinkFormat) -> io::Result<FileSink> {
+        Ok(WriterSink::new(BufWriter::new(File::create(path)?), format))
+    }
+}
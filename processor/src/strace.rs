@@ -0,0 +1,243 @@
+//! Convert a capture of the system calls of a real program into a
+//! [`Scenario`], so its behavior can be replayed with any scheduler.
+//!
+//! A capture is the file written by `strace -f -ttt -T -o <file>`, with
+//! a system call on every line:
+//!
+//! ```text
+//! 100  1697040000.000000 clone(child_stack=NULL, flags=SIGCHLD) = 101 <0.000120>
+//! 100  1697040000.000150 nanosleep({tv_sec=0, tv_nsec=5000000}, NULL) = 0 <0.005000>
+//! 101  1697040000.000200 futex(0x5600, FUTEX_WAIT_PRIVATE, 0, NULL <unfinished ...>
+//! 100  1697040000.005400 futex(0x5600, FUTEX_WAKE_PRIVATE, 1) = 1 <0.000010>
+//! 101  1697040000.005410 <... futex resumed>) = 0 <0.005210>
+//! 101  1697040000.008000 exit_group(0)     = ?
+//! 101  1697040000.008100 +++ exited with 0 +++
+//! 100  1697040000.008200 --- SIGCHLD {si_signo=SIGCHLD, si_code=CLD_EXITED, si_pid=101} ---
+//! 100  1697040000.010000 exit_group(0)     = ?
+//! 100  1697040000.010100 +++ exited with 0 +++
+//! ```
+//!
+//! Every line has the PID of the process, the time when the call starts
+//! in seconds and the call, optionally followed by its result and by the
+//! time it has taken between `<` and `>`. A call that is interrupted by
+//! the calls of other processes is split by strace into an
+//! `<unfinished ...>` line and a `<... name resumed>` line of the same
+//! process, which are joined into one call that starts at the first
+//! line; a call that never resumes is ignored. The exits (`+++ ... +++`),
+//! the signals (`--- ... ---`), empty lines and lines starting with `#`
+//! are ignored. The process of the first line becomes the process with
+//! PID 1.
+//!
+//! The time between the end of a call and the start of the next call of
+//! the same process is a CPU burst, an `exec`. The calls become:
+//!
+//! * `clone`, `clone3`, `fork` and `vfork`, whose result is the PID of the
+//!   child, a `fork` with priority 0 and the calls of the child;
+//! * `futex` with `FUTEX_WAIT` or `FUTEX_WAKE`, a `wait` or a `signal` of a
+//!   user event, the same for the same address;
+//! * `exit` and `exit_group`, the end of the process;
+//! * any other call that has taken time, a `sleep` for that time.
+//!
+//! With [`EventSemantics::Transient`](scheduler::EventSemantics::Transient),
+//! the default, a wake that happens before the matching wait in the
+//! simulation is lost. With
+//! [`EventSemantics::Sticky`](scheduler::EventSemantics::Sticky) it is
+//! remembered, and the wait returns immediately.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use scheduler::Event;
+
+use crate::scenario::{Child, Instruction, Scenario};
+use crate::ParseError;
+
+/// A call of a process in a capture.
+#[derive(Debug, Clone, PartialEq)]
+struct Call {
+    /// The line of the call, starting from 1.
+    line: usize,
+    /// When the call starts, in seconds.
+    start: f64,
+    /// When the call ends, in seconds.
+    end: f64,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Fork(usize),
+    Wait(Event),
+    Signal(Event),
+    Exit,
+    /// Any other call, a sleep if it has taken time.
+    Block,
+}
+
+/// Converts a capture into a scenario.
+///
+/// * `capture` - the lines of the capture.
+/// * `unit` - the length of a time unit of the simulation.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::scenario::Instruction;
+/// use processor::strace;
+/// use std::time::Duration;
+///
+/// let capture = "\
+///     100 0.000 clone() = 101\n\
+///     101 0.002 exit_group(0)\n\
+///     100 0.001 nanosleep() = 0 <0.004>\n\
+///     100 0.007 exit_group(0)\n";
+///
+/// let scenario = strace::import(capture, Duration::from_millis(1)).unwrap();
+/// assert_eq!(scenario.instructions[1], Instruction::Exec(1));
+/// assert_eq!(scenario.instructions[2], Instruction::Sleep(4));
+/// assert!(strace::import("100 0.000 clone() = 102\n100 0.001 clone() = 102\n", Duration::from_millis(1)).is_err());
+/// ```
+pub fn import(capture: &str, unit: Duration) -> Result<Scenario, ParseError> {
+    let mut processes: HashMap<usize, Vec<Call>> = HashMap::new();
+    let mut first = None;
+    let mut events = HashMap::new();
+    // the calls that are not resumed yet, with their line and start
+    let mut unfinished: HashMap<usize, (usize, f64, &str)> = HashMap::new();
+    for (index, line) in capture.lines().enumerate() {
+        let error = |message: String| ParseError { line: index + 1, message };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (pid, start, rest) = columns(line).map_err(error)?;
+        if rest.starts_with("+++") || rest.starts_with("---") {
+            continue;
+        }
+        first.get_or_insert(pid);
+        let (number, start, rest) = if let Some(head) = rest.strip_suffix("<unfinished ...>") {
+            if unfinished.insert(pid, (index + 1, start, head)).is_some() {
+                return Err(error(format!("process {pid} starts a call before it resumes the previous one")));
+            }
+            continue;
+        } else if let Some(resumed) = rest.strip_prefix("<...") {
+            let Some((_, tail)) = resumed.split_once("resumed>") else {
+                return Err(error(format!("expected `<... name resumed>`, found `{rest}`")));
+            };
+            let Some((number, start, head)) = unfinished.remove(&pid) else {
+                return Err(error(format!("process {pid} resumes a call that is not unfinished")));
+            };
+            (number, start, format!("{head}{tail}"))
+        } else {
+            (index + 1, start, rest.to_string())
+        };
+        let call = call(&rest, number, start, &mut events).map_err(error)?;
+        let calls = processes.entry(pid).or_default();
+        if calls.last().is_some_and(|last| last.kind == Kind::Exit) {
+            return Err(error(format!("process {pid} has already exited")));
+        }
+        if calls.last().is_some_and(|last| call.start < last.start) {
+            return Err(error(format!("the calls of process {pid} are not in the order of their time")));
+        }
+        calls.push(call);
+    }
+    let Some(first) = first else {
+        return Ok(Scenario { instructions: Vec::new() });
+    };
+
+    let start = processes[&first][0].start;
+    let mut forked = vec![first];
+    let instructions = program(first, start, &processes, &mut forked, unit.as_secs_f64())?;
+    if let Some((pid, calls)) = processes.iter().find(|(pid, _)| !forked.contains(pid)) {
+        return Err(ParseError {
+            line: calls[0].line,
+            message: format!("process {pid} is not created by any clone"),
+        });
+    }
+    Ok(Scenario { instructions })
+}
+
+// parses the PID, the time and the rest of a line
+fn columns(line: &str) -> Result<(usize, f64, &str), String> {
+    let columns = line.split_once(char::is_whitespace).and_then(|(pid, rest)| {
+        let (time, rest) = rest.trim_start().split_once(char::is_whitespace)?;
+        Some((pid, time, rest))
+    });
+    let Some((pid, time, rest)) = columns else {
+        return Err(format!("expected `pid time call`, found `{line}`"));
+    };
+    let pid = pid.parse::<usize>().map_err(|_| format!("`{pid}` is not a valid PID"))?;
+    let start = time.parse::<f64>().ok().filter(|time| *time >= 0.0).ok_or_else(|| format!("`{time}` is not a valid time"))?;
+    Ok((pid, start, rest.trim()))
+}
+
+// parses a call that starts at `start`, the events are numbered in the
+// order of the first appearance of their address
+fn call(rest: &str, number: usize, start: f64, events: &mut HashMap<String, usize>) -> Result<Call, String> {
+    let (rest, elapsed) = match rest.strip_suffix('>').and_then(|rest| rest.rsplit_once('<')) {
+        Some((rest, elapsed)) => {
+            let elapsed = elapsed.parse::<f64>().ok().filter(|elapsed| *elapsed >= 0.0).ok_or_else(|| format!("`{elapsed}` is not a valid duration"))?;
+            (rest.trim(), elapsed)
+        }
+        None => (rest, 0.0),
+    };
+    let (rest, result) = match rest.rsplit_once(" = ") {
+        Some((rest, result)) => (rest.trim(), Some(result.trim())),
+        None => (rest, None),
+    };
+    let (name, arguments) = match rest.split_once('(') {
+        Some((name, arguments)) => (name.trim(), arguments.trim_end_matches(')')),
+        None => (rest, ""),
+    };
+
+    let mut event = |arguments: &str| {
+        let address = arguments.split(',').next().unwrap_or_default().trim().to_string();
+        let next = events.len() + 1;
+        Event::user(*events.entry(address).or_insert(next))
+    };
+    let kind = match name {
+        "clone" | "clone3" | "fork" | "vfork" => {
+            let result = result.ok_or_else(|| format!("`{name}` needs the PID of the child as its result"))?;
+            Kind::Fork(result.parse().map_err(|_| format!("`{result}` is not a valid PID"))?)
+        }
+        "futex" if arguments.contains("FUTEX_WAIT") => Kind::Wait(event(arguments)),
+        "futex" if arguments.contains("FUTEX_WAKE") => Kind::Signal(event(arguments)),
+        "exit" | "exit_group" => Kind::Exit,
+        _ => Kind::Block,
+    };
+    Ok(Call { line: number, start, end: start + elapsed, kind })
+}
+
+// the instructions of the process `pid`, that starts running at `start`
+fn program(pid: usize, start: f64, processes: &HashMap<usize, Vec<Call>>, forked: &mut Vec<usize>, unit: f64) -> Result<Vec<Instruction>, ParseError> {
+    // the times are rounded once, so the rounding errors do not add up
+    let units = |time: f64| (time / unit).round() as usize;
+    let mut instructions = Vec::new();
+    let mut time = start;
+    for call in processes.get(&pid).map_or(&[][..], Vec::as_slice) {
+        let burst = units(call.start).saturating_sub(units(time));
+        if burst > 0 {
+            instructions.push(Instruction::Exec(burst));
+        }
+        let elapsed = units(call.end) - units(call.start);
+        match call.kind {
+            Kind::Fork(child) => {
+                if forked.contains(&child) {
+                    return Err(ParseError {
+                        line: call.line,
+                        message: format!("process {child} is created more than once"),
+                    });
+                }
+                forked.push(child);
+                let program = program(child, call.end, processes, forked, unit)?;
+                instructions.push(Instruction::Fork(Child { priority: 0, instructions: program }));
+            }
+            Kind::Wait(event) => instructions.push(Instruction::Wait(event)),
+            Kind::Signal(event) => instructions.push(Instruction::Signal(event)),
+            Kind::Exit => break,
+            Kind::Block if elapsed > 0 => instructions.push(Instruction::Sleep(elapsed)),
+            Kind::Block => {}
+        }
+        time = call.end;
+    }
+    Ok(instructions)
+}
@@ -0,0 +1,347 @@
+//! The workloads of the tests of the runner, so that other scheduler
+//! implementations can run the same suite.
+//!
+//! Every workload is the closure of the process with PID 1 and is named
+//! after the folder and the name of its reference logs in `outputs/`,
+//! like `workers/sleeper`. The modules group the workloads by folder.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use processor::test_workloads;
+//! use processor::Processor;
+//! use scheduler::SchedulingDecision;
+//! use std::io;
+//! use std::num::NonZeroUsize;
+//!
+//! let logs = Processor::builder()
+//!     .narrate(io::sink())
+//!     .run(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), test_workloads::deadlock::wait);
+//! assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Deadlock);
+//!
+//! assert!(test_workloads::find::<Box<dyn scheduler::Scheduler>>("workers/sleeper").is_some());
+//! ```
+
+use scheduler::Scheduler;
+
+use crate::{process_tree, Process};
+
+/// The instructions of the process with PID 1.
+pub type Workload<S> = fn(&Process<S>);
+
+/// Every workload with its name, in the order of the tests.
+pub fn all<S: Scheduler + 'static>() -> Vec<(&'static str, Workload<S>)> {
+    vec![
+        ("simple/single_process", simple::single_process),
+        ("simple/fork_2", simple::fork_2),
+        ("simple/fork_3", simple::fork_3),
+        ("simple/sleep", simple::sleep),
+        ("simple/work_sleep", simple::work_sleep),
+        ("simple/fork_wait_signal", simple::fork_wait_signal),
+        ("simple/fork_wait_sleep_signal", simple::fork_wait_sleep_signal),
+        ("deadlock/wait", deadlock::wait),
+        ("deadlock/signal_before_wait", deadlock::signal_before_wait),
+        ("deadlock/wait_2", deadlock::wait_2),
+        ("deadlock/signal_before_wait_2", deadlock::signal_before_wait_2),
+        ("deadlock/wait_3", deadlock::wait_3),
+        ("panic/exec", panic::exec),
+        ("panic/sleep", panic::sleep),
+        ("panic/wait", panic::wait),
+        ("wait_and_signal/send_receive", wait_and_signal::send_receive),
+        ("wait_and_signal/workers", wait_and_signal::workers),
+        ("wait_and_signal/senders", wait_and_signal::senders),
+        ("workers/single_worker", workers::single_worker),
+        ("workers/worker_io", workers::worker_io),
+        ("workers/worker_3", workers::worker_3),
+        ("workers/worker_spawning", workers::worker_spawning),
+        ("workers/sleeper", workers::sleeper),
+    ]
+}
+
+/// Returns the workload with the name `name`, like `workers/sleeper`,
+/// if there is one.
+pub fn find<S: Scheduler + 'static>(name: &str) -> Option<Workload<S>> {
+    all().into_iter().find(|(workload, _)| *workload == name).map(|(_, workload)| workload)
+}
+
+pub mod simple {
+    use super::{process_tree, Process, Scheduler};
+
+    /// PID 1 executes for 5 time units.
+    pub fn single_process<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(exec 5)(process)
+    }
+
+    /// PID 1 forks a process that executes for 5 time units and executes
+    /// for 10.
+    pub fn fork_2<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { exec 5 };
+            exec 10
+        )(process)
+    }
+
+    /// A chain of three processes, PID 1 executes for 10 time units and the
+    /// other two for 5.
+    pub fn fork_3<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork {
+                fork { exec 5 };
+                exec 5
+            };
+            exec 10
+        )(process)
+    }
+
+    /// PID 1 sleeps for 10 time units.
+    pub fn sleep<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(sleep 10)(process)
+    }
+
+    /// PID 1 executes for 3 time units and sleeps for 10, three times.
+    pub fn work_sleep<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            exec 3;
+            sleep 10;
+            exec 3;
+            sleep 10;
+            exec 3;
+            sleep 10
+        )(process)
+    }
+
+    /// PID 1 wakes a process that waits for an event, after sleeping for
+    /// 10 time units.
+    pub fn fork_wait_signal<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { wait 1 };
+            sleep 10;
+            signal 1;
+            sleep 10
+        )(process)
+    }
+
+    /// PID 1 wakes a process that waits for an event, after sleeping for
+    /// 5 time units.
+    pub fn fork_wait_sleep_signal<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { wait 1 };
+            sleep 5;
+            signal 1;
+            sleep 10
+        )(process)
+    }
+}
+
+pub mod deadlock {
+    use super::{process_tree, Process, Scheduler};
+
+    /// PID 1 waits for an event that is never signaled.
+    pub fn wait<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            exec 5;
+            wait 1
+        )(process)
+    }
+
+    /// PID 1 signals an event before waiting for it.
+    pub fn signal_before_wait<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            exec 5;
+            signal 1;
+            wait 1
+        )(process)
+    }
+
+    /// Two processes wait for events that are never signaled.
+    pub fn wait_2<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork {
+                exec 5;
+                wait 2
+            };
+            sleep 10;
+            wait 1;
+            sleep 10
+        )(process)
+    }
+
+    /// PID 1 signals an event before the processes wait for it.
+    pub fn signal_before_wait_2<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork {
+                exec 5;
+                wait 2
+            };
+            signal 2;
+            wait 2;
+            sleep 10
+        )(process)
+    }
+
+    /// Three processes wait for events, only one of the events is signaled.
+    pub fn wait_3<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork {
+                exec 5;
+                wait 1
+            };
+            fork {
+                exec 5;
+                wait 1
+            };
+            fork {
+                exec 5;
+                wait 2
+            };
+            sleep 10;
+            signal 1;
+            wait 0;
+            sleep 10
+        )(process)
+    }
+}
+
+pub mod panic {
+    use super::{process_tree, Process, Scheduler};
+
+    /// PID 1 exits while a process executes.
+    pub fn exec<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { exec 5 };
+            exec 1
+        )(process)
+    }
+
+    /// PID 1 exits while a process sleeps.
+    pub fn sleep<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { sleep 5 };
+            exec 1
+        )(process)
+    }
+
+    /// PID 1 exits while a process waits for an event.
+    pub fn wait<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { wait 1 };
+            exec 1
+        )(process)
+    }
+}
+
+pub mod wait_and_signal {
+    use super::{process_tree, Process, Scheduler};
+
+    /// A process waits for an event that PID 1 signals after executing.
+    pub fn send_receive<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork {
+                wait 1;
+                exec 5
+            };
+            exec 5;
+            signal 1;
+            sleep 10
+        )(process)
+    }
+
+    /// Three processes wait for two events that PID 1 signals.
+    pub fn workers<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork { wait 1 };
+            fork { wait 1 };
+            fork { wait 2 };
+            exec 10;
+            signal 1;
+            signal 2;
+            sleep 10
+        )(process)
+    }
+
+    /// Every signal wakes a process that signals the next event.
+    pub fn senders<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork {
+                wait 1;
+                signal 2
+            };
+            fork {
+                wait 2;
+                signal 3
+            };
+            fork { wait 3 };
+            fork { wait 3 };
+            exec 10;
+            signal 1;
+            sleep 10
+        )(process)
+    }
+}
+
+pub mod workers {
+    use super::{process_tree, Process, Scheduler};
+
+    /// PID 1 executes while a process with priority 5 executes.
+    pub fn single_worker<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork prio 5 { exec 20 };
+            exec 30
+        )(process)
+    }
+
+    /// A process executes and then alternates short sleeps and executions,
+    /// while PID 1 executes.
+    pub fn worker_io<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork prio 3 {
+                exec 10;
+                repeat 5 {
+                    sleep 1;
+                    exec 2
+                }
+            };
+            exec 50
+        )(process)
+    }
+
+    /// Two processes with different priorities, one of them sleeps often,
+    /// while PID 1 executes.
+    pub fn worker_3<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork prio 3 { exec 10 };
+            fork prio 5 {
+                repeat 20 {
+                    sleep 1;
+                    exec 2
+                }
+            };
+            exec 50
+        )(process)
+    }
+
+    /// A process forks another process after executing, while PID 1
+    /// executes.
+    pub fn worker_spawning<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork prio 5 {
+                exec 20;
+                fork prio 5 { exec 20 }
+            };
+            exec 50
+        )(process)
+    }
+
+    /// A process forks two other processes while PID 1 sleeps.
+    pub fn sleeper<S: Scheduler + 'static>(process: &Process<S>) {
+        process_tree!(
+            fork prio 5 {
+                fork prio 5 { exec 20 };
+                exec 20;
+                fork prio 5 { exec 20 }
+            };
+            sleep 110;
+            exec 50
+        )(process)
+    }
+}
@@ -0,0 +1,124 @@
+//! Reconstruct the virtual time line of a simulation.
+//!
+//! The [`Processor`](crate::Processor) only records the scheduling
+//! decisions and the reasons processes stopped. This module replays
+//! them to find out when each process ran and when the processor slept.
+
+use scheduler::{Pid, SchedulingDecision, StopReason};
+
+use crate::Log;
+
+/// What the processor did during a [`Segment`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Activity {
+    /// The process with PID `pid` was running.
+    Run {
+        /// The PID of the running process.
+        pid: Pid,
+
+        /// The reason the process has stopped.
+        stop_reason: StopReason,
+    },
+
+    /// The processor was idle, waiting for sleeping processes.
+    Sleep,
+}
+
+/// An interval of virtual time `[start, end)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Segment {
+    /// The iteration (starting from 0) that produced the segment.
+    pub iteration: usize,
+
+    /// The time unit when the segment starts.
+    pub start: usize,
+
+    /// The time unit when the segment ends (exclusive).
+    pub end: usize,
+
+    /// What the processor did.
+    pub activity: Activity,
+}
+
+impl Segment {
+    /// Returns the number of time units the segment lasts.
+    pub fn duration(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Returns the number of time units a process has used when it
+/// stopped for `reason` after being scheduled for `timeslice` units.
+///
+/// A system call uses one time unit, which is already accounted for
+/// in the `remaining` value reported by the processor.
+pub(crate) fn used(timeslice: usize, reason: &StopReason) -> usize {
+    match reason {
+        StopReason::Syscall { remaining, .. } | StopReason::Preempted { remaining } => {
+            timeslice.saturating_sub(*remaining)
+        }
+        StopReason::Expired => timeslice,
+    }
+}
+
+/// Returns the duration and the activity of an iteration, if
+/// the iteration advances the virtual time.
+fn segment(log: &Log) -> Option<(usize, Activity)> {
+    match (log.decision, log.stop_reason) {
+        (SchedulingDecision::Run { pid, timeslice }, Some((stop_reason, _))) => Some((
+            used(timeslice.get(), &stop_reason),
+            Activity::Run { pid, stop_reason },
+        )),
+        (SchedulingDecision::Sleep(amount), _) => Some((amount.get(), Activity::Sleep)),
+        _ => None,
+    }
+}
+
+/// Build the list of segments from the [`Processor`](crate::Processor)'s logs.
+///
+/// Segments are returned in chronological order. `Run` decisions that
+/// were never followed by a stop (the simulation ended) are ignored.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn segments(logs: &[Log]) -> Vec<Segment> {
+    let mut segments = vec![];
+    let mut time = 0;
+    for (iteration, log) in logs.iter().enumerate() {
+        let Some((duration, activity)) = segment(log) else {
+            continue;
+        };
+        segments.push(Segment {
+            iteration,
+            start: time,
+            end: time + duration,
+            activity,
+        });
+        time += duration;
+    }
+    segments
+}
+
+/// Returns the interval of virtual time `(start, end)` covered by
+/// every iteration.
+///
+/// Iterations that do not advance the time (like the final decision)
+/// have `start == end`.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn iteration_times(logs: &[Log]) -> Vec<(usize, usize)> {
+    let mut times = Vec::with_capacity(logs.len());
+    let mut time = 0;
+    for log in logs {
+        let duration = segment(log).map_or(0, |(duration, _)| duration);
+        times.push((time, time + duration));
+        time += duration;
+    }
+    times
+}
+
+/// Returns the total virtual time covered by the logs.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+pub fn makespan(logs: &[Log]) -> usize {
+    segments(logs).last().map_or(0, |segment| segment.end)
+}
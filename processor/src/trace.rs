@@ -0,0 +1,21 @@
+//! Diagnostics reported while a simulation runs.
+
+/// Reports a diagnostic of the simulation.
+///
+/// With the `tracing` feature, the diagnostic is a [`tracing`] event
+/// with the given level and fields. Otherwise, the message is part of
+/// the narration of the processor, printed to the standard output
+/// unless [`ProcessorBuilder::narrate`](crate::ProcessorBuilder::narrate)
+/// was used.
+///
+/// ```ignore
+/// diagnostic!(self.processor, trace, { pid = pid.get(), "exec" }, "{}: EXEC", pid);
+/// ```
+macro_rules! diagnostic {
+    ($processor:expr, $level:ident, { $($event:tt)* }, $($message:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($event)*);
+        #[cfg(not(feature = "tracing"))]
+        $processor.narrate(format_args!($($message)*));
+    };
+}
@@ -0,0 +1,83 @@
+//! Write the tree of the processes of a workload without nesting the
+//! closures of the forks by hand.
+
+/// Returns the closure of a process, for [`Processor::run`](crate::Processor::run)
+/// or [`Process::fork`](crate::Process::fork), from its instructions.
+///
+/// The instructions are separated by `;`:
+///
+/// * `exec n`, `sleep n` - execute or sleep for `n` time units;
+/// * `wait e`, `signal e` - wait for or signal an event, numbers are
+///   user events;
+/// * `fork { ... }` - create a process with the instructions between
+///   the braces and priority 0, or with `fork prio p { ... }` priority
+///   `p`, a literal or a variable;
+/// * `repeat n { ... }` - execute the instructions between the braces
+///   `n` times.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{process_tree, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Processor::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     process_tree! {
+///         exec 4;
+///         fork prio 0 { exec 2; wait 1 };
+///         sleep 10;
+///         signal 1;
+///         sleep 5
+///     },
+/// );
+///
+/// assert_eq!(logs.last().unwrap().decision.to_string(), "Done, no more processes");
+/// ```
+#[macro_export]
+macro_rules! process_tree {
+    // every instruction is a block that ends with the next ones
+    (@body $process:ident;) => {
+        ()
+    };
+    (@body $process:ident; exec $units:expr $(; $($rest:tt)*)?) => {{
+        $process.exec_n($units);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; sleep $units:expr $(; $($rest:tt)*)?) => {{
+        $process.sleep($units);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; wait $event:expr $(; $($rest:tt)*)?) => {{
+        $process.wait($event);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; signal $event:expr $(; $($rest:tt)*)?) => {{
+        $process.signal($event);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; fork { $($child:tt)* } $(; $($rest:tt)*)?) => {{
+        $process.fork($crate::process_tree!($($child)*), 0);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; fork prio $priority:literal { $($child:tt)* } $(; $($rest:tt)*)?) => {{
+        $process.fork($crate::process_tree!($($child)*), $priority);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; fork prio $priority:ident { $($child:tt)* } $(; $($rest:tt)*)?) => {{
+        $process.fork($crate::process_tree!($($child)*), $priority);
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; repeat $times:literal { $($body:tt)* } $(; $($rest:tt)*)?) => {{
+        for _ in 0..$times {
+            $crate::process_tree!(@body $process; $($body)*);
+        }
+        $crate::process_tree!(@body $process; $($($rest)*)?)
+    }};
+    (@body $process:ident; $($instructions:tt)*) => {
+        compile_error!(concat!("unknown instruction `", stringify!($($instructions)*), "`"))
+    };
+    ($($instructions:tt)*) => {
+        move |process: &$crate::Process<_>| $crate::process_tree!(@body process; $($instructions)*)
+    };
+}
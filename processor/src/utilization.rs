@@ -0,0 +1,154 @@
+//! Split the virtual time of a simulation into intervals of the same
+//! length and find how busy the processor was during each of them.
+//!
+//! Comparing the intervals of several simulations shows where the
+//! processor is idle, for instance how the timeslice and the minimum
+//! remaining timeslice of a scheduler change the gaps between processes.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use processor::utilization;
+//! use processor::Processor;
+//! use std::num::NonZeroUsize;
+//!
+//! let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+//!     process.exec();
+//!     process.sleep(5);
+//! });
+//!
+//! let intervals = utilization::intervals(&logs, NonZeroUsize::new(4).unwrap());
+//! assert_eq!(intervals.len(), 2);
+//! assert_eq!((intervals[0].busy, intervals[0].idle), (2, 2));
+//! assert!(utilization::csv(&intervals).starts_with("start,end,busy,idle,utilization,pids\n"));
+//! ```
+
+use std::fmt::Write;
+use std::num::NonZeroUsize;
+
+use scheduler::Pid;
+
+use crate::timeline::{self, Activity};
+use crate::Log;
+
+/// The width of the bar of an interval in [`text`].
+const BAR_WIDTH: usize = 20;
+
+/// An interval of virtual time `[start, end)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval {
+    /// The time unit when the interval starts.
+    pub start: usize,
+
+    /// The time unit when the interval ends (exclusive).
+    pub end: usize,
+
+    /// The time units when a process was running.
+    pub busy: usize,
+
+    /// The time units when the processor was sleeping.
+    pub idle: usize,
+
+    /// The processes that have run during the interval, sorted.
+    pub pids: Vec<Pid>,
+}
+
+impl Interval {
+    /// Returns the fraction of the interval when a process was running.
+    pub fn utilization(&self) -> f64 {
+        match self.end - self.start {
+            0 => 0.0,
+            length => self.busy as f64 / length as f64,
+        }
+    }
+}
+
+/// Splits the time line of a simulation into intervals of `length` time
+/// units. The last interval ends with the simulation, so it can be shorter.
+///
+/// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+/// * `length` - the length of the intervals.
+pub fn intervals(logs: &[Log], length: NonZeroUsize) -> Vec<Interval> {
+    let length = length.get();
+    let makespan = timeline::makespan(logs);
+    let mut intervals = (0..makespan.div_ceil(length))
+        .map(|index| Interval {
+            start: index * length,
+            end: ((index + 1) * length).min(makespan),
+            busy: 0,
+            idle: 0,
+            pids: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    for segment in timeline::segments(logs) {
+        // the parts of the segment in every interval that it overlaps
+        let mut time = segment.start;
+        while time < segment.end {
+            let interval = &mut intervals[time / length];
+            let units = segment.end.min(interval.end) - time;
+            match segment.activity {
+                Activity::Run { pid, .. } => {
+                    interval.busy += units;
+                    if !interval.pids.contains(&pid) {
+                        interval.pids.push(pid);
+                    }
+                }
+                Activity::Sleep => interval.idle += units,
+            }
+            time += units;
+        }
+    }
+    for interval in &mut intervals {
+        interval.pids.sort();
+    }
+    intervals
+}
+
+/// Renders the intervals as a table, with a bar for the utilization of
+/// every interval.
+///
+/// * `intervals` - the intervals returned by [`intervals`].
+pub fn text(intervals: &[Interval]) -> String {
+    let mut text = String::new();
+    let width = intervals.last().map_or(1, |interval| interval.end.to_string().len());
+    for interval in intervals {
+        let utilization = interval.utilization();
+        let filled = (utilization * BAR_WIDTH as f64).round() as usize;
+        let _ = writeln!(
+            text,
+            "{:>width$}..{:<width$}  {:>4.0}%  {}{}  {}",
+            interval.start,
+            interval.end,
+            utilization * 100.0,
+            "#".repeat(filled),
+            ".".repeat(BAR_WIDTH - filled),
+            pids(interval)
+        );
+    }
+    text
+}
+
+/// Renders the intervals as CSV, with a header line and a line for
+/// every interval. The PIDs of an interval are separated by spaces.
+///
+/// * `intervals` - the intervals returned by [`intervals`].
+pub fn csv(intervals: &[Interval]) -> String {
+    let mut csv = String::from("start,end,busy,idle,utilization,pids\n");
+    for interval in intervals {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{:.3},{}",
+            interval.start,
+            interval.end,
+            interval.busy,
+            interval.idle,
+            interval.utilization(),
+            pids(interval)
+        );
+    }
+    csv
+}
+
+fn pids(interval: &Interval) -> String {
+    interval.pids.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,594 @@
+//! Check the decisions of a scheduler against its own process list
+//! and the system calls of the processes, the processes and timings it
+//! reports, the time left to the processes when they stop and the PIDs
+//! it gives to new processes.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display};
+use std::mem;
+
+use scheduler::{Pid, PidPolicy, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult, Timings};
+
+use crate::{timeline, Log};
+
+/// An invariant broken by a decision of the scheduler, found when the
+/// [`Processor`](crate::Processor) validates the decisions (see
+/// [`ProcessorBuilder::validate`](crate::ProcessorBuilder::validate)).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Violation {
+    /// The scheduler runs a process that it does not list.
+    UnknownProcess {
+        /// The PID of the process that runs.
+        pid: Pid,
+    },
+
+    /// The scheduler runs a process that is not ready or running in
+    /// its list.
+    NotReady {
+        /// The PID of the process that runs.
+        pid: Pid,
+
+        /// The state of the process in the list of the scheduler.
+        state: ProcessState,
+    },
+
+    /// The processor sleeps for a different time than the time until
+    /// the first sleeping process wakes up.
+    SleepAmount {
+        /// The time units of the decision.
+        amount: usize,
+
+        /// The time units until the first sleeping process wakes up, or
+        /// [`None`] if no process is sleeping.
+        expected: Option<usize>,
+    },
+
+    /// A process is no longer listed by the scheduler, but it has not
+    /// exited.
+    Vanished {
+        /// The PID of the process.
+        pid: Pid,
+    },
+
+    /// A process is listed by the scheduler, but no fork has created it.
+    Appeared {
+        /// The PID of the process.
+        pid: Pid,
+    },
+
+    /// The total time of a process is shorter than the time it has
+    /// spent executing and sending system calls.
+    TimingsExceedTotal {
+        /// The PID of the process.
+        pid: Pid,
+
+        /// The timings reported by the scheduler.
+        timings: Timings,
+    },
+
+    /// The total time of a process is shorter than at the previous
+    /// decision.
+    TotalDecreased {
+        /// The PID of the process.
+        pid: Pid,
+
+        /// The total time at the previous decision.
+        before: usize,
+
+        /// The total time at this decision.
+        after: usize,
+    },
+
+    /// The execution and system call times of the processes have grown
+    /// by a different amount than the time the processor ran them since
+    /// the previous decision.
+    ServiceMismatch {
+        /// The time units the processes have run.
+        ran: usize,
+
+        /// How much the execution and system call times have grown.
+        reported: isize,
+    },
+
+    /// A process stops with as many time units left as its timeslice,
+    /// or more, although every stop uses at least one.
+    RemainingExceedsTimeslice {
+        /// The PID of the process.
+        pid: Pid,
+
+        /// The time units left, reported with the stop.
+        remaining: usize,
+
+        /// The timeslice of the decision that has run the process.
+        timeslice: usize,
+    },
+
+    /// The time units left to a process when it stops do not match the
+    /// time units it has executed since it was scheduled.
+    RemainingMismatch {
+        /// The PID of the process.
+        pid: Pid,
+
+        /// The time units left, reported with the stop, 0 if the
+        /// timeslice has expired.
+        remaining: usize,
+
+        /// The time units left by the executions of the process.
+        expected: usize,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownProcess { pid } => {
+                write!(f, "the scheduler runs process {pid}, which it does not list")
+            }
+            Violation::NotReady { pid, state } => write!(
+                f,
+                "the scheduler runs process {pid}, whose state is {state} instead of READY or RUNNING"
+            ),
+            Violation::SleepAmount { amount, expected: Some(expected) } => write!(
+                f,
+                "the processor sleeps for {amount} units, but the first sleeping process wakes up after {expected} units"
+            ),
+            Violation::SleepAmount { amount, expected: None } => {
+                write!(f, "the processor sleeps for {amount} units, but no process is sleeping")
+            }
+            Violation::Vanished { pid } => {
+                write!(f, "the scheduler no longer lists process {pid}, which has not exited")
+            }
+            Violation::Appeared { pid } => {
+                write!(f, "the scheduler lists process {pid}, which no fork has created")
+            }
+            Violation::TimingsExceedTotal { pid, timings } => write!(
+                f,
+                "the total time of process {pid} is shorter than its syscall and execution times ({timings})"
+            ),
+            Violation::TotalDecreased { pid, before, after } => {
+                write!(f, "the total time of process {pid} decreases from {before} to {after}")
+            }
+            Violation::ServiceMismatch { ran, reported } => write!(
+                f,
+                "the processes have run for {ran} units, but their syscall and execution times grow by {reported}"
+            ),
+            Violation::RemainingExceedsTimeslice { pid, remaining, timeslice } => write!(
+                f,
+                "process {pid} stops with {remaining} units left of a timeslice of {timeslice}, but every stop uses a unit"
+            ),
+            Violation::RemainingMismatch { pid, remaining, expected } => write!(
+                f,
+                "process {pid} stops with {remaining} units left, but its executions leave {expected}"
+            ),
+        }
+    }
+}
+
+/// Checks that the processes listed by the scheduler only change with
+/// the system calls that create and end them.
+#[derive(Debug, Default)]
+struct ProcessCheck {
+    /// The processes listed at the previous decision.
+    last: Option<BTreeSet<Pid>>,
+    /// The processes created since the previous decision.
+    created: BTreeSet<Pid>,
+    /// The processes that have exited, that can stay listed for a while.
+    exited: BTreeSet<Pid>,
+}
+
+impl ProcessCheck {
+    // follows the system call that has stopped the process `pid`
+    fn stopped(&mut self, pid: Pid, syscall: Syscall, result: SyscallResult) {
+        match (syscall, result) {
+            (Syscall::Exit, _) => {
+                self.exited.insert(pid);
+            }
+            (Syscall::Fork(_), SyscallResult::Pid(child)) => {
+                // the PID of a process that has exited can be given again
+                self.exited.remove(&child);
+                self.created.insert(child);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the processes that have vanished and then the ones that
+    /// have appeared since the previous decision, in the order of their
+    /// PIDs.
+    ///
+    /// * `pids` - the processes listed at the decision.
+    fn check(&mut self, pids: BTreeSet<Pid>) -> Vec<Violation> {
+        let created = mem::take(&mut self.created);
+        let Some(last) = self.last.replace(pids) else {
+            return Vec::new();
+        };
+        let pids = self.last.as_ref().unwrap();
+        let mut violations = Vec::new();
+        for pid in last.difference(pids) {
+            if !self.exited.remove(pid) {
+                violations.push(Violation::Vanished { pid: *pid });
+            }
+        }
+        for pid in pids.difference(&last) {
+            if !created.contains(pid) {
+                violations.push(Violation::Appeared { pid: *pid });
+            }
+        }
+        violations
+    }
+}
+
+/// Checks the processes recorded in the logs of a simulation, and
+/// returns every process that vanishes or appears, with the number of
+/// the iteration starting from 0.
+///
+/// A process can only stop being listed by the scheduler after it has
+/// exited, and only be listed after the fork that has created it. This
+/// is checked while the simulation runs by
+/// [`ProcessorBuilder::validate`](crate::ProcessorBuilder::validate).
+///
+/// * `logs` - the logs of a simulation, recorded with the processes.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{check_processes, Processor, Violation};
+/// use scheduler::Pid;
+/// use std::num::NonZeroUsize;
+///
+/// let simulate = || {
+///     Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///         process.fork(|process| process.exec(), 0);
+///         process.sleep(3);
+///     })
+/// };
+/// assert!(check_processes(&simulate()).is_empty());
+///
+/// // process 2 runs in the third iteration and exits after it
+/// let mut lost = simulate();
+/// lost[2].processes.remove(&Pid::new(2));
+/// assert_eq!(check_processes(&lost), vec![(2, Violation::Vanished { pid: Pid::new(2) })]);
+///
+/// // process 2 is created after the first iteration
+/// let mut late = simulate();
+/// late[1].processes.remove(&Pid::new(2));
+/// assert_eq!(check_processes(&late), vec![(2, Violation::Appeared { pid: Pid::new(2) })]);
+/// ```
+pub fn check_processes(logs: &[Log]) -> Vec<(usize, Violation)> {
+    let mut check = ProcessCheck::default();
+    let mut violations = Vec::new();
+    for (iteration, log) in logs.iter().enumerate() {
+        let pids = log.processes.keys().copied().collect();
+        violations.extend(check.check(pids).into_iter().map(|violation| (iteration, violation)));
+        if let (SchedulingDecision::Run { pid, .. }, Some((StopReason::Syscall { syscall, .. }, result))) = (log.decision, log.stop_reason) {
+            check.stopped(pid, syscall, result);
+        }
+    }
+    violations
+}
+
+/// Checks the timings reported by the scheduler at every decision
+/// against the timings of the previous decision.
+#[derive(Debug, Default)]
+struct TimingCheck {
+    /// The timings of the processes at the previous decision.
+    last: HashMap<Pid, Timings>,
+}
+
+impl TimingCheck {
+    /// Returns the first invariant broken by the timings, in the order of
+    /// the PIDs.
+    ///
+    /// * `timings` - the timings of the processes at the decision.
+    /// * `ran` - the time units the processor has run processes since the
+    ///   previous decision.
+    fn check(&mut self, timings: HashMap<Pid, Timings>, ran: usize) -> Option<Violation> {
+        let last = mem::replace(&mut self.last, timings);
+        let mut pids = self.last.keys().collect::<Vec<_>>();
+        pids.sort();
+        for pid in pids {
+            let timings = self.last[pid];
+            if timings.total < timings.syscall + timings.execution {
+                return Some(Violation::TimingsExceedTotal { pid: *pid, timings });
+            }
+            if let Some(before) = last.get(pid).filter(|before| before.total > timings.total) {
+                return Some(Violation::TotalDecreased { pid: *pid, before: before.total, after: timings.total });
+            }
+        }
+
+        // the times of a process that has exited are not known, and
+        // logs without processes cannot be checked
+        if last.is_empty() || last.keys().any(|pid| !self.last.contains_key(pid)) {
+            return None;
+        }
+        let service = |timings: &HashMap<Pid, Timings>| timings.values().map(|timings| timings.syscall + timings.execution).sum::<usize>() as isize;
+        let reported = service(&self.last) - service(&last);
+        (reported != ran as isize).then_some(Violation::ServiceMismatch { ran, reported })
+    }
+}
+
+/// Checks the timings recorded in the logs of a simulation, and returns
+/// the first broken invariant of every iteration that has one, with
+/// the number of the iteration starting from 0.
+///
+/// At every iteration, the total time of every process has to be at
+/// least the time it has spent executing and sending system calls, and
+/// cannot be shorter than at the previous iteration. The execution and
+/// system call times of the processes have to grow by the time the
+/// processor has run them. This can be checked while the simulation
+/// runs with [`ProcessorBuilder::check_timings`](crate::ProcessorBuilder::check_timings).
+///
+/// * `logs` - the logs of a simulation, recorded with the processes.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{check_timings, Processor};
+/// use std::num::NonZeroUsize;
+///
+/// let mut logs = Processor::run(scheduler::cfs(NonZeroUsize::new(3).unwrap(), 1), |process| {
+///     process.fork(|process| process.exec(), 0);
+///     process.exec();
+/// });
+/// assert!(check_timings(&logs).is_empty());
+///
+/// for info in logs[2].processes.values_mut() {
+///     info.timings.total = 0;
+/// }
+/// assert!(!check_timings(&logs).is_empty());
+/// ```
+pub fn check_timings(logs: &[Log]) -> Vec<(usize, Violation)> {
+    let mut check = TimingCheck::default();
+    let mut ran = 0;
+    let mut violations = Vec::new();
+    for (iteration, log) in logs.iter().enumerate() {
+        let timings = log.processes.iter().map(|(pid, info)| (*pid, info.timings)).collect();
+        if let Some(violation) = check.check(timings, ran) {
+            violations.push((iteration, violation));
+        }
+        ran = match (log.decision, log.stop_reason) {
+            (SchedulingDecision::Run { timeslice, .. }, Some((reason, _))) => timeline::used(timeslice.get(), &reason),
+            _ => 0,
+        };
+    }
+    violations
+}
+
+/// Checks the time units left to the processes when they stop, recorded
+/// in the logs of a simulation, and returns every stop that reports as
+/// many time units left as the timeslice of its decision, or more, with
+/// the number of the iteration starting from 0.
+///
+/// Every stop uses at least a time unit, for the system call or for
+/// the execution before a preemption. While the simulation runs,
+/// [`ProcessorBuilder::validate`](crate::ProcessorBuilder::validate)
+/// also checks the time left against the executions of the process.
+///
+/// * `logs` - the logs of a simulation.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{check_remaining, Processor, Violation};
+/// use scheduler::{Pid, StopReason};
+/// use std::num::NonZeroUsize;
+///
+/// let mut logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), |process| {
+///     process.exec();
+///     process.sleep(2);
+/// });
+/// assert!(check_remaining(&logs).is_empty());
+///
+/// if let Some((reason, _)) = &mut logs[0].stop_reason {
+///     reason.set_remaining(4);
+/// }
+/// assert_eq!(
+///     check_remaining(&logs),
+///     vec![(0, Violation::RemainingExceedsTimeslice { pid: Pid::new(1), remaining: 4, timeslice: 3 })]
+/// );
+/// ```
+pub fn check_remaining(logs: &[Log]) -> Vec<(usize, Violation)> {
+    let mut violations = Vec::new();
+    for (iteration, log) in logs.iter().enumerate() {
+        let (SchedulingDecision::Run { pid, timeslice }, Some((StopReason::Syscall { remaining, .. } | StopReason::Preempted { remaining }, _))) =
+            (log.decision, log.stop_reason)
+        else {
+            continue;
+        };
+        if remaining >= timeslice.get() {
+            violations.push((iteration, Violation::RemainingExceedsTimeslice { pid, remaining, timeslice: timeslice.get() }));
+        }
+    }
+    violations
+}
+
+/// Follows the virtual time of a simulation to check the decisions of
+/// its scheduler.
+#[derive(Debug)]
+pub(crate) struct Validator {
+    /// Whether the decisions are checked.
+    decisions: bool,
+    /// The check of the processes, with the decisions.
+    processes: ProcessCheck,
+    /// The check of the timings, if they are checked.
+    timings: Option<TimingCheck>,
+    /// The virtual time, in time units.
+    now: usize,
+    /// The time units processes have run since the previous decision.
+    ran: usize,
+    /// The process that runs and its timeslice.
+    running: Option<(Pid, usize)>,
+    /// The time units the running process has executed.
+    execs: usize,
+    /// The invariant broken by the last stop, reported with the next
+    /// decision.
+    stop: Option<Violation>,
+    /// The time when every sleeping process wakes up.
+    sleeping: HashMap<Pid, usize>,
+}
+
+impl Validator {
+    /// * `decisions` - whether the decisions are checked.
+    /// * `timings` - whether the timings reported by the scheduler are
+    ///   checked.
+    pub(crate) fn new(decisions: bool, timings: bool) -> Validator {
+        Validator {
+            decisions,
+            processes: ProcessCheck::default(),
+            timings: timings.then(TimingCheck::default),
+            now: 0,
+            ran: 0,
+            running: None,
+            execs: 0,
+            stop: None,
+            sleeping: HashMap::new(),
+        }
+    }
+
+    /// Returns the invariant broken by `decision`, by the processes or by
+    /// their timings, if there is one.
+    ///
+    /// A process that runs has to be listed by the scheduler as ready or
+    /// running, the state it has after the decision. The processor has
+    /// to sleep until the first sleeping process wakes up. The processes
+    /// can only be removed from the list after they exit and added after
+    /// they are created. The time left to the process that has stopped
+    /// before the decision is checked first.
+    pub(crate) fn decision<S: Scheduler + ?Sized>(&mut self, scheduler: &mut S, decision: SchedulingDecision) -> Option<Violation> {
+        let timings = self.timings.as_mut().and_then(|check| {
+            let mut timings = HashMap::new();
+            scheduler.for_each_process(&mut |process| {
+                timings.insert(process.pid(), process.timings());
+            });
+            check.check(timings, mem::take(&mut self.ran))
+        });
+        let mut violation = self.check(scheduler, decision);
+        violation = self.stop.take().or(violation);
+        if self.decisions {
+            let mut pids = BTreeSet::new();
+            scheduler.for_each_process(&mut |process| {
+                pids.insert(process.pid());
+            });
+            violation = violation.or(self.processes.check(pids).into_iter().next());
+        }
+        self.decisions.then_some(violation).flatten().or(timings)
+    }
+
+    // the invariant broken by `decision`, following the virtual time
+    fn check<S: Scheduler + ?Sized>(&mut self, scheduler: &mut S, decision: SchedulingDecision) -> Option<Violation> {
+        self.sleeping.retain(|_, wake| *wake > self.now);
+        match decision {
+            SchedulingDecision::Run { pid, timeslice } => {
+                self.running = Some((pid, timeslice.get()));
+                let mut state = None;
+                scheduler.for_each_process(&mut |process| {
+                    if process.pid() == pid {
+                        state = Some(process.state());
+                    }
+                });
+                match state {
+                    None => Some(Violation::UnknownProcess { pid }),
+                    Some(ProcessState::Ready | ProcessState::Running) => None,
+                    Some(state) => Some(Violation::NotReady { pid, state }),
+                }
+            }
+            SchedulingDecision::Sleep(amount) => {
+                // the processes that sleep before the simulation starts
+                // wake up at a time that is not known
+                let mut known = true;
+                scheduler.for_each_process(&mut |process| {
+                    if process.state() == (ProcessState::Waiting { event: None }) {
+                        known &= self.sleeping.contains_key(&process.pid());
+                    }
+                });
+                let expected = self.sleeping.values().map(|wake| wake - self.now).min();
+                self.now += amount.get();
+                match known && expected != Some(amount.get()) {
+                    true => Some(Violation::SleepAmount { amount: amount.get(), expected }),
+                    false => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Counts a time unit executed by the running process.
+    pub(crate) fn exec(&mut self) {
+        self.execs += 1;
+    }
+
+    /// Follows the time used by the process that stops, and the
+    /// processes it creates or ends.
+    ///
+    /// * `reason` - why the process stops, with its remaining time.
+    /// * `result` - the result returned by the scheduler.
+    pub(crate) fn stopped(&mut self, reason: StopReason, result: SyscallResult) {
+        let execs = mem::take(&mut self.execs);
+        let Some((pid, timeslice)) = self.running.take() else {
+            return;
+        };
+        // a system call uses a time unit after the executions
+        let (remaining, expected) = match reason {
+            StopReason::Syscall { remaining, .. } => (remaining, timeslice.saturating_sub(execs + 1)),
+            StopReason::Preempted { remaining } => (remaining, timeslice.saturating_sub(execs)),
+            StopReason::Expired => (0, timeslice.saturating_sub(execs)),
+        };
+        self.stop = if remaining >= timeslice {
+            Some(Violation::RemainingExceedsTimeslice { pid, remaining, timeslice })
+        } else {
+            (remaining != expected).then_some(Violation::RemainingMismatch { pid, remaining, expected })
+        };
+        let used = timeline::used(timeslice, &reason);
+        self.now += used;
+        self.ran += used;
+        if let StopReason::Syscall { syscall, .. } = reason {
+            self.processes.stopped(pid, syscall, result);
+        }
+        if let StopReason::Syscall { syscall: Syscall::Sleep(amount), .. } = reason {
+            self.sleeping.insert(pid, self.now + amount);
+        }
+    }
+}
+
+/// The PIDs of the processes that have not exited, to check the PIDs
+/// that the scheduler gives to new processes.
+#[derive(Debug)]
+pub(crate) struct Pids {
+    policy: PidPolicy,
+    live: BTreeSet<Pid>,
+    last: Option<Pid>,
+}
+
+impl Pids {
+    /// * `policy` - the way the scheduler is expected to choose the PIDs.
+    pub(crate) fn new(policy: PidPolicy) -> Pids {
+        Pids {
+            policy,
+            live: BTreeSet::new(),
+            last: None,
+        }
+    }
+
+    /// Adds the process created by a fork, and returns why its PID is
+    /// not valid if it is not.
+    pub(crate) fn fork(&mut self, pid: Pid) -> Result<(), String> {
+        if self.live.contains(&pid) {
+            return Err(format!("The scheduler returned PID {pid} for a new process, but process {pid} has not exited"));
+        }
+        if let (PidPolicy::Increasing, Some(last)) = (self.policy, self.last) {
+            if pid <= last {
+                return Err(format!(
+                    "The scheduler returned PID {pid} for a new process after PID {last}, PIDs have to increase unless they are recycled"
+                ));
+            }
+        }
+        self.live.insert(pid);
+        self.last = self.last.max(Some(pid));
+        Ok(())
+    }
+
+    /// Removes a process that has exited.
+    pub(crate) fn exit(&mut self, pid: Pid) {
+        self.live.remove(&pid);
+    }
+}
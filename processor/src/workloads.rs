@@ -0,0 +1,207 @@
+//! Named workloads that tests, benchmarks and the runner share.
+//!
+//! Every workload is the closure of the process with PID 1, for any
+//! scheduler. PID 1 forks the other processes and sleeps until they
+//! have ended, longer than all of their executions, system calls and
+//! sleeps together, since the schedulers panic when PID 1 ends first.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use processor::workloads::{self, Workload};
+//! use processor::Processor;
+//! use scheduler::SchedulingDecision;
+//! use std::io;
+//! use std::num::NonZeroUsize;
+//!
+//! let workload = workloads::find("pipeline").unwrap();
+//! assert_eq!(workload, Workload::Pipeline);
+//!
+//! let logs = Processor::builder()
+//!     .narrate(io::sink())
+//!     .run(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), move |process| workload.run(process));
+//! assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+//! ```
+
+use std::fmt::{self, Display};
+
+use scheduler::Scheduler;
+
+use crate::Process;
+
+/// A workload of [`standard`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Workload {
+    /// Four processes that only execute.
+    CpuBound,
+    /// Two processes that execute for a long time and two processes that
+    /// execute briefly between sleeps.
+    IoBoundMix,
+    /// A tree of processes, where every process forks two children
+    /// until the tree is four levels deep.
+    ForkBombBounded,
+    /// A chain of stages that wait for the previous stage, execute and
+    /// wake the next stage.
+    Pipeline,
+    /// A producer that wakes a consumer for every item.
+    ProducerConsumer,
+    /// Three processes that execute briefly and sleep for their period.
+    PeriodicTasks,
+}
+
+const STANDARD: [Workload; 6] = [
+    Workload::CpuBound,
+    Workload::IoBoundMix,
+    Workload::ForkBombBounded,
+    Workload::Pipeline,
+    Workload::ProducerConsumer,
+    Workload::PeriodicTasks,
+];
+
+/// Every workload, in the order of [`Workload`].
+pub fn standard() -> &'static [Workload] {
+    &STANDARD
+}
+
+/// Returns the workload with the name `name`, if there is one.
+///
+/// * `name` - the name of the workload, see [`Workload::name`].
+pub fn find(name: &str) -> Option<Workload> {
+    STANDARD.into_iter().find(|workload| workload.name() == name)
+}
+
+impl Workload {
+    /// The name of the workload, like `cpu_bound`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Workload::CpuBound => "cpu_bound",
+            Workload::IoBoundMix => "io_bound_mix",
+            Workload::ForkBombBounded => "fork_bomb_bounded",
+            Workload::Pipeline => "pipeline",
+            Workload::ProducerConsumer => "producer_consumer",
+            Workload::PeriodicTasks => "periodic_tasks",
+        }
+    }
+
+    /// Runs the workload as the process `process`, usually the process
+    /// with PID 1.
+    pub fn run<S: Scheduler + 'static>(self, process: &Process<S>) {
+        match self {
+            Workload::CpuBound => cpu_bound(process),
+            Workload::IoBoundMix => io_bound_mix(process),
+            Workload::ForkBombBounded => {
+                fork_tree(process, 3);
+                process.sleep(100);
+            }
+            Workload::Pipeline => pipeline(process),
+            Workload::ProducerConsumer => producer_consumer(process),
+            Workload::PeriodicTasks => periodic_tasks(process),
+        }
+    }
+}
+
+impl Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn cpu_bound<S: Scheduler + 'static>(process: &Process<S>) {
+    for priority in 0..4 {
+        process.fork(|process| process.exec_n(20), priority);
+    }
+    process.sleep(100);
+}
+
+fn io_bound_mix<S: Scheduler + 'static>(process: &Process<S>) {
+    for _ in 0..2 {
+        process.fork(|process| process.exec_n(15), 0);
+    }
+    for _ in 0..2 {
+        process.fork(
+            |process| {
+                for _ in 0..5 {
+                    process.exec();
+                    process.sleep(4);
+                }
+            },
+            0,
+        );
+    }
+    process.sleep(100);
+}
+
+// forks two children that fork until `depth` is 0, and executes
+fn fork_tree<S: Scheduler + 'static>(process: &Process<S>, depth: usize) {
+    if depth > 0 {
+        for _ in 0..2 {
+            process.fork(move |process| fork_tree(process, depth - 1), 0);
+        }
+    }
+    process.exec_n(2);
+}
+
+// event `stage` wakes the stage `stage`
+fn pipeline<S: Scheduler + 'static>(process: &Process<S>) {
+    const STAGES: usize = 4;
+    for stage in 1..=STAGES {
+        process.fork(
+            move |process| {
+                process.wait(stage);
+                process.exec_n(3);
+                if stage < STAGES {
+                    process.signal(stage + 1);
+                }
+            },
+            0,
+        );
+    }
+    // every stage waits before the first one is woken
+    process.sleep(10);
+    process.signal(1usize);
+    process.sleep(30);
+}
+
+// the producer signals event 1 for every item, and sleeps long enough
+// for the consumer to wait for the next one, as signals are lost with
+// transient events and remembered only once with sticky events
+fn producer_consumer<S: Scheduler + 'static>(process: &Process<S>) {
+    const ITEMS: usize = 4;
+    // the consumer
+    process.fork(
+        |process| {
+            for _ in 0..ITEMS {
+                process.wait(1usize);
+                process.exec_n(2);
+            }
+        },
+        0,
+    );
+    // the producer
+    process.fork(
+        |process| {
+            for _ in 0..ITEMS {
+                process.sleep(5);
+                process.exec();
+                process.signal(1usize);
+            }
+        },
+        0,
+    );
+    process.sleep(60);
+}
+
+fn periodic_tasks<S: Scheduler + 'static>(process: &Process<S>) {
+    for (period, units) in [(5, 1), (8, 2), (12, 3)] {
+        process.fork(
+            move |process| {
+                for _ in 0..4 {
+                    process.exec_n(units);
+                    process.sleep(period);
+                }
+            },
+            0,
+        );
+    }
+    process.sleep(150);
+}
@@ -0,0 +1,166 @@
+//! The configuration file of `runner batch`, which runs several
+//! scenarios without repeating their arguments:
+//!
+//! ```toml
+//! scheduler = "round-robin"
+//! format = "text"
+//! # the folder where the logs of every scenario are written
+//! output = "runs"
+//!
+//! [parameters]
+//! timeslice = 3
+//! min-remaining = 1
+//! cpu-slices = 10
+//!
+//! [[scenario]]
+//! name = "workers/sleeper"
+//!
+//! [[scenario]]
+//! workload = "pipeline"
+//! scheduler = "cfs"
+//! parameters = { cpu-slices = 12 }
+//! format = "summary"
+//! output = "pipeline-cfs.txt"
+//! ```
+//!
+//! Every `[[scenario]]` has one of `name`, `workload`, `file` and `trace`,
+//! like the options of `runner run`, and can replace any value of the
+//! top of the file. The `SCHEDULER`, `TIMESLICE`, `REMAINING` and
+//! `CPU_SLICES` environment variables replace the values of the file.
+//! Paths are relative to the folder of the file.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use serde::Deserialize;
+
+use crate::{Cli, Command, Format, RunArgs};
+
+/// The contents of a configuration file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Config {
+    scheduler: Option<String>,
+    format: Option<String>,
+    interval: Option<usize>,
+    /// The folder of the logs, the standard output if it is not given.
+    output: Option<PathBuf>,
+    #[serde(default)]
+    parameters: ParametersFile,
+    #[serde(rename = "scenario", default)]
+    scenarios: Vec<Entry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct ParametersFile {
+    timeslice: Option<usize>,
+    min_remaining: Option<usize>,
+    cpu_slices: Option<usize>,
+}
+
+/// A scenario to run.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    name: Option<String>,
+    workload: Option<String>,
+    file: Option<PathBuf>,
+    trace: Option<PathBuf>,
+    scheduler: Option<String>,
+    format: Option<String>,
+    interval: Option<usize>,
+    #[serde(default)]
+    parameters: ParametersFile,
+    /// The file of the logs in the output folder, named after the
+    /// scenario if it is not given.
+    output: Option<PathBuf>,
+}
+
+impl Config {
+    /// Reads a configuration file.
+    pub(crate) fn load(path: &Path) -> Result<Config, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("unable to read {}: {error}", path.display()))?;
+        toml::from_str(&text).map_err(|error| format!("{}: {error}", path.display()))
+    }
+
+    /// The arguments of `runner run` for every scenario.
+    ///
+    /// * `base` - the folder of the configuration file.
+    pub(crate) fn runs(&self, base: &Path) -> Result<Vec<RunArgs>, String> {
+        let mut runs: Vec<RunArgs> = Vec::new();
+        for (index, entry) in self.scenarios.iter().enumerate() {
+            let error = |message: String| format!("scenario {}: {message}", index + 1);
+            let mut arguments = vec!["runner".to_string(), "run".to_string()];
+            let mut option = |name: &str, variable: Option<&str>, value: Option<String>| {
+                // the environment variables replace the values of the file
+                if let (Some(value), None) = (value, variable.and_then(env::var_os)) {
+                    arguments.extend([format!("--{name}"), value]);
+                }
+            };
+            let (parameters, defaults) = (&entry.parameters, &self.parameters);
+            option("scheduler", Some("SCHEDULER"), entry.scheduler.clone().or(self.scheduler.clone()));
+            option("timeslice", Some("TIMESLICE"), parameters.timeslice.or(defaults.timeslice).map(|value| value.to_string()));
+            option("min-remaining", Some("REMAINING"), parameters.min_remaining.or(defaults.min_remaining).map(|value| value.to_string()));
+            option("cpu-slices", Some("CPU_SLICES"), parameters.cpu_slices.or(defaults.cpu_slices).map(|value| value.to_string()));
+            option("format", None, entry.format.clone().or(self.format.clone()));
+            option("interval", None, entry.interval.or(self.interval).map(|value| value.to_string()));
+            option("scenario", None, entry.name.clone());
+            option("workload", None, entry.workload.clone());
+            option("file", None, entry.file.as_ref().map(|path| base.join(path).display().to_string()));
+            option("trace", None, entry.trace.as_ref().map(|path| base.join(path).display().to_string()));
+
+            let Cli { command: Command::Run(mut args) } = Cli::try_parse_from(arguments).map_err(|parse| {
+                // only the first line, without the usage
+                let message = parse.to_string();
+                error(message.lines().next().unwrap_or_default().trim_start_matches("error: ").to_string())
+            })? else {
+                unreachable!("the arguments are the ones of `runner run`")
+            };
+            if let Some(folder) = &self.output {
+                let file = match &entry.output {
+                    Some(file) => file.clone(),
+                    None => PathBuf::from(format!("{}.{}", label(entry), extension(args.format))),
+                };
+                let path = base.join(folder).join(file);
+                if runs.iter().any(|run| run.output.as_ref() == Some(&path)) {
+                    return Err(error(format!("{} is written by another scenario, give it an `output`", path.display())));
+                }
+                args.output = Some(path);
+            }
+            runs.push(args);
+        }
+        Ok(runs)
+    }
+
+    /// The folder of the logs, if there is one.
+    ///
+    /// * `base` - the folder of the configuration file.
+    pub(crate) fn output(&self, base: &Path) -> Option<PathBuf> {
+        self.output.as_ref().map(|folder| base.join(folder))
+    }
+}
+
+// the name of the scenario, for the name of its file
+fn label(entry: &Entry) -> String {
+    let stem = |path: &PathBuf| path.file_stem().map_or_else(|| "scenario".to_string(), |stem| stem.to_string_lossy().into_owned());
+    match (&entry.name, &entry.workload, &entry.file, &entry.trace) {
+        (Some(name), _, _, _) => name.replace('/', "-"),
+        (None, Some(workload), _, _) => workload.clone(),
+        (None, None, Some(path), _) | (None, None, None, Some(path)) => stem(path),
+        (None, None, None, None) => "scenario".to_string(),
+    }
+}
+
+fn extension(format: Format) -> &'static str {
+    match format {
+        Format::Text | Format::Syscalls => "log",
+        Format::Pretty | Format::Summary | Format::Utilization => "txt",
+        Format::Markdown => "md",
+        Format::Json | Format::ChromeTrace => "json",
+        Format::Html => "html",
+        Format::UtilizationCsv => "csv",
+    }
+}
@@ -0,0 +1,146 @@
+//! The reference logs under `outputs/` that the tests compare with, one
+//! for every scenario and every configuration that the tests run.
+
+use std::fs;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use processor::test_workloads::{self, Workload};
+use processor::header::{self, Header};
+use processor::{format_logs, Log, Processor};
+use scheduler::{Params, Policy, Scheduler};
+
+
+/// A configuration of the tests, the folder of its reference logs and
+/// the parameters that it reads from the environment.
+pub(crate) struct Configuration {
+    pub(crate) folder: &'static str,
+    pub(crate) policy: Policy,
+    pub(crate) timeslice: usize,
+    pub(crate) remaining: usize,
+    pub(crate) cpu_slices: usize,
+}
+
+const fn configuration(folder: &'static str, policy: Policy, timeslice: usize, remaining: usize, cpu_slices: usize) -> Configuration {
+    Configuration {
+        folder,
+        policy,
+        timeslice,
+        remaining,
+        cpu_slices,
+    }
+}
+
+/// The configurations that the tests are run with. The tests without a
+/// scheduler feature use the round robin policy.
+pub(crate) const CONFIGURATIONS: &[Configuration] = &[
+    configuration("no-scheduler", Policy::RoundRobin, 3, 1, 10),
+    configuration("round-robin", Policy::RoundRobin, 3, 1, 10),
+    configuration("round-robin", Policy::RoundRobin, 5, 2, 10),
+    configuration("round-robin", Policy::RoundRobin, 3, 3, 10),
+    configuration("priority-queue", Policy::PriorityQueue, 3, 1, 10),
+    configuration("priority-queue", Policy::PriorityQueue, 5, 2, 10),
+    configuration("priority-queue", Policy::PriorityQueue, 3, 3, 10),
+    configuration("cfs", Policy::Cfs, 3, 1, 10),
+    configuration("cfs", Policy::Cfs, 3, 2, 12),
+    configuration("cfs", Policy::Cfs, 3, 3, 18),
+];
+
+/// A reference log that the tests read.
+pub(crate) struct Golden {
+    /// The path of the file, relative to the outputs folder.
+    pub(crate) path: PathBuf,
+    /// The name of the scenario, like `workers/sleeper`.
+    pub(crate) name: &'static str,
+    pub(crate) configuration: &'static Configuration,
+    pub(crate) workload: Workload<Box<dyn Scheduler>>,
+}
+
+impl Golden {
+    /// Runs the scenario and returns the logs that the file should contain.
+    pub(crate) fn render(&self) -> String {
+        format_logs(&self.logs())
+    }
+
+    /// The metadata written before the logs in the file.
+    pub(crate) fn header(&self) -> Header {
+        let Configuration { policy, timeslice, remaining, cpu_slices, .. } = *self.configuration;
+        Header::new()
+            .with("scheduler", policy.name())
+            .with("timeslice", timeslice)
+            .with("remaining", remaining)
+            .with("cpu-slices", cpu_slices)
+            .with("version", header::VERSION)
+            .with("scenario", self.name)
+    }
+
+    /// Runs the scenario with the configuration.
+    pub(crate) fn logs(&self) -> Vec<Log> {
+        let Configuration { policy, timeslice, remaining, cpu_slices, .. } = *self.configuration;
+        let params = Params {
+            timeslice: NonZeroUsize::new(timeslice).unwrap(),
+            minimum_remaining_timeslice: remaining,
+            cpu_time: NonZeroUsize::new(cpu_slices).unwrap(),
+        };
+        // the name of a policy is always known
+        let scheduler = scheduler::by_name(policy.name(), &params).unwrap();
+        Processor::builder().narrate(io::sink()).run(scheduler, self.workload)
+    }
+}
+
+/// The path of the reference log of a scenario, relative to the outputs
+/// folder.
+///
+/// * `folder` - the folder of the configuration, like `round-robin`.
+/// * `name` - the name of the scenario, like `workers/sleeper`.
+pub(crate) fn path(folder: &str, name: &str, timeslice: usize, remaining: usize, cpu_slices: usize) -> PathBuf {
+    PathBuf::from(format!("{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log"))
+}
+
+/// Every reference log whose path contains `filter`.
+pub(crate) fn goldens(filter: Option<&str>) -> Vec<Golden> {
+    let mut goldens = Vec::new();
+    for configuration in CONFIGURATIONS {
+        for (name, workload) in test_workloads::all() {
+            let Configuration { folder, timeslice, remaining, cpu_slices, .. } = *configuration;
+            let path = path(folder, name, timeslice, remaining, cpu_slices);
+            if filter.is_none_or(|filter| path.to_string_lossy().contains(filter)) {
+                goldens.push(Golden {
+                    path,
+                    name,
+                    configuration,
+                    workload,
+                });
+            }
+        }
+    }
+    goldens
+}
+
+/// The logs in `outputs` that no test reads anymore, relative to
+/// `outputs`, whose path contains `filter`.
+pub(crate) fn stale(outputs: &Path, filter: Option<&str>) -> io::Result<Vec<PathBuf>> {
+    let expected = goldens(None).into_iter().map(|golden| golden.path).collect::<Vec<_>>();
+    let mut files = Vec::new();
+    if outputs.is_dir() {
+        logs(outputs, Path::new(""), &mut files)?;
+    }
+    files.retain(|path| !expected.contains(path) && filter.is_none_or(|filter| path.to_string_lossy().contains(filter)));
+    files.sort();
+    Ok(files)
+}
+
+// adds the log files in `outputs/folder` to `files`, recursively
+fn logs(outputs: &Path, folder: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(outputs.join(folder))? {
+        let entry = entry?;
+        let path = folder.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            logs(outputs, &path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "log") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
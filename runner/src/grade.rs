@@ -0,0 +1,263 @@
+//! The grading of a scheduler with a rubric, for `runner grade`.
+//!
+//! A rubric is a TOML file with a `[[test]]` table for every test:
+//!
+//! ```toml
+//! [[test]]
+//! name = "round robin"
+//! points = 30
+//! # every reference log whose path contains the filter has to match
+//! check = "reference"
+//! filter = "round-robin/"
+//!
+//! [[test]]
+//! name = "priority queue invariants"
+//! points = 20
+//! # the conformance checks, with the configurations whose folder
+//! # contains the filter
+//! check = "conformance"
+//! filter = "priority-queue"
+//! # a share of the points for every case that passes, instead of all
+//! # the points only when every case passes
+//! partial = true
+//! ```
+//!
+//! The points cannot be negative. A test whose filter matches no case
+//! earns no points, since its filter is most likely wrong.
+
+use std::fmt::Write;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use processor::{export, header};
+use scheduler::{conformance, Params};
+use serde::Deserialize;
+
+use crate::golden::{self, Configuration, CONFIGURATIONS};
+
+/// The tests of an assignment and their points.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Rubric {
+    #[serde(rename = "test")]
+    tests: Vec<Test>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Test {
+    name: String,
+    points: f64,
+    check: Check,
+    /// Only the cases that contain it, every case if it is empty.
+    #[serde(default)]
+    filter: String,
+    #[serde(default)]
+    partial: bool,
+}
+
+/// How the cases of a test are checked.
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Check {
+    /// The simulations of the reference logs match the files.
+    Reference,
+    /// The scheduler passes the checks of [`conformance`].
+    Conformance,
+}
+
+impl Rubric {
+    /// Reads a rubric from a TOML file.
+    pub(crate) fn load(path: &Path) -> Result<Rubric, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("unable to read {}: {error}", path.display()))?;
+        let rubric: Rubric = toml::from_str(&text).map_err(|error| format!("{}: {error}", path.display()))?;
+        if let Some(test) = rubric.tests.iter().find(|test| !(test.points >= 0.0 && test.points.is_finite())) {
+            return Err(format!("{}: test `{}` has {} points, they have to be a positive number or 0", path.display(), test.name, test.points));
+        }
+        Ok(rubric)
+    }
+}
+
+/// The results of the tests of a rubric.
+pub(crate) struct Report {
+    tests: Vec<TestReport>,
+}
+
+struct TestReport {
+    name: String,
+    points: f64,
+    earned: f64,
+    cases: usize,
+    passed: usize,
+    /// A line for every case that has failed, or why there is no case.
+    failures: Vec<String>,
+}
+
+/// Runs the tests of the rubric and gives them points.
+///
+/// * `rubric` - the tests and their points.
+/// * `outputs` - the folder of the reference logs.
+pub(crate) fn grade(rubric: &Rubric, outputs: &Path) -> Result<Report, String> {
+    if rubric.tests.iter().any(|test| matches!(test.check, Check::Reference)) && !outputs.is_dir() {
+        return Err(format!("the folder of the reference logs {} does not exist", outputs.display()));
+    }
+    // the schedulers that panic are reported as failures
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let tests = rubric
+        .tests
+        .iter()
+        .map(|test| {
+            let (cases, mut failures) = match test.check {
+                Check::Reference => reference(outputs, &test.filter),
+                Check::Conformance => conformance(&test.filter),
+            };
+            let passed = cases - failures.len();
+            // a test without cases is most likely a wrong filter
+            if cases == 0 {
+                failures.push(format!("no cases match the filter `{}`", test.filter));
+            }
+            let earned = match (passed == cases, test.partial) {
+                _ if cases == 0 => 0.0,
+                (true, _) => test.points,
+                (false, true) => test.points * passed as f64 / cases as f64,
+                (false, false) => 0.0,
+            };
+            TestReport {
+                name: test.name.clone(),
+                points: test.points,
+                earned,
+                cases,
+                passed,
+                failures,
+            }
+        })
+        .collect();
+    panic::set_hook(hook);
+    Ok(Report { tests })
+}
+
+// compares the simulations with the reference logs, and returns the
+// number of cases and the failures
+fn reference(outputs: &Path, filter: &str) -> (usize, Vec<String>) {
+    let goldens = golden::goldens(Some(filter));
+    let mut failures = Vec::new();
+    for golden in &goldens {
+        let Ok(reference) = fs::read_to_string(outputs.join(&golden.path)) else {
+            failures.push(format!("missing {}", golden.path.display()));
+            continue;
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| golden.render())) {
+            Ok(logs) if header::split(&reference).1 == logs => {}
+            Ok(_) => failures.push(format!("mismatch {}", golden.path.display())),
+            Err(error) => failures.push(format!("panic {}: {}", golden.path.display(), message(&*error))),
+        }
+    }
+    (goldens.len(), failures)
+}
+
+// runs the conformance scenarios with every configuration whose folder
+// contains `filter`, and returns the number of cases and the failures
+fn conformance(filter: &str) -> (usize, Vec<String>) {
+    let mut configurations: Vec<&Configuration> = Vec::new();
+    for configuration in CONFIGURATIONS.iter().filter(|configuration| configuration.folder.contains(filter)) {
+        // the configuration without a scheduler feature is the same as one of round robin
+        let same = |other: &&Configuration| {
+            (other.policy, other.timeslice, other.remaining, other.cpu_slices)
+                == (configuration.policy, configuration.timeslice, configuration.remaining, configuration.cpu_slices)
+        };
+        if !configurations.iter().any(same) {
+            configurations.push(configuration);
+        }
+    }
+
+    let scenarios = conformance::scenarios();
+    let mut failures = Vec::new();
+    for configuration in &configurations {
+        let Configuration { policy, timeslice, remaining, cpu_slices, .. } = **configuration;
+        let params = Params {
+            timeslice: NonZeroUsize::new(timeslice).unwrap(),
+            minimum_remaining_timeslice: remaining,
+            cpu_time: NonZeroUsize::new(cpu_slices).unwrap(),
+        };
+        for scenario in &scenarios {
+            // the name of a policy is always known
+            if let Err(failure) = conformance::run(scheduler::by_name(policy.name(), &params).unwrap(), scenario) {
+                failures.push(format!("{} {timeslice}_{remaining}_{cpu_slices}: {failure}", policy.name()));
+            }
+        }
+    }
+    (configurations.len() * scenarios.len(), failures)
+}
+
+fn message(error: &(dyn std::any::Any + Send)) -> &str {
+    match (error.downcast_ref::<&str>(), error.downcast_ref::<String>()) {
+        (Some(message), _) => message,
+        (None, Some(message)) => message,
+        (None, None) => "unknown panic",
+    }
+}
+
+impl Report {
+    /// The points earned by every test together.
+    pub(crate) fn score(&self) -> f64 {
+        self.tests.iter().map(|test| test.earned).sum()
+    }
+
+    /// The points of every test together.
+    pub(crate) fn total(&self) -> f64 {
+        self.tests.iter().map(|test| test.points).sum()
+    }
+
+    /// A line for every test, followed by its failures and the score.
+    pub(crate) fn text(&self) -> String {
+        let mut text = String::new();
+        let width = self.tests.iter().map(|test| test.name.len()).max().unwrap_or(0);
+        for test in &self.tests {
+            let _ = writeln!(
+                text,
+                "{:<width$}  {}/{}  {}/{} cases passed",
+                test.name,
+                points(test.earned),
+                points(test.points),
+                test.passed,
+                test.cases
+            );
+            for failure in &test.failures {
+                let _ = writeln!(text, "    {failure}");
+            }
+        }
+        let _ = writeln!(text, "score {}/{}", points(self.score()), points(self.total()));
+        text
+    }
+
+    /// The score and an object for every test, as JSON.
+    pub(crate) fn json(&self) -> String {
+        let tests = self.tests.iter().map(|test| {
+            let failures = test.failures.iter().map(|failure| export::json_string(failure)).collect::<Vec<_>>();
+            format!(
+                "{{\"name\":{},\"points\":{},\"earned\":{},\"cases\":{},\"passed\":{},\"failures\":[{}]}}",
+                export::json_string(&test.name),
+                points(test.points),
+                points(test.earned),
+                test.cases,
+                test.passed,
+                failures.join(",")
+            )
+        });
+        format!(
+            "{{\"score\":{},\"total\":{},\"tests\":[{}]}}\n",
+            points(self.score()),
+            points(self.total()),
+            tests.collect::<Vec<_>>().join(",")
+        )
+    }
+}
+
+// the points with at most two decimals
+fn points(points: f64) -> String {
+    let rounded = (points * 100.0).round() / 100.0;
+    rounded.to_string()
+}
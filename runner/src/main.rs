@@ -1,29 +1,1154 @@
-use scheduler::round_robin;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
 use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
 
-use processor::format_logs;
-use processor::Processor;
-
-fn main() {
-    let logs = Processor::run(round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
-        process.exec();
-        process.exec();
-        process.exec();
-        process.exec();
-        process.fork(
-            |process| {
-                process.exec();
-                process.exec();
-                process.wait(1);
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use scheduler::{Params, Policy, Scheduler, SchedulingDecision};
+
+use config::Config;
+use processor::coverage::Coverage;
+use processor::metrics::Metrics;
+use processor::replay::SyscallTrace;
+use processor::scenario::{self, Scenario};
+use processor::strace;
+use processor::{header, test_workloads, workloads};
+use processor::{export, format_logs, format_logs_markdown, format_logs_pretty, format_logs_with_summary, report, utilization};
+use processor::{diff, parse_logs, Log, Process, Processor, SinkFormat, WriterSink};
+
+mod config;
+mod golden;
+mod grade;
+mod repl;
+#[cfg(feature = "tui")]
+mod tui;
+mod verification;
+
+use verification::{Case, Outcome};
+
+/// Simulates the schedulers of the `scheduler` crate.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a scenario and write its logs.
+    Run(RunArgs),
+
+    /// Run the scenarios of a configuration file, with the scheduler,
+    /// the parameters and the format of every scenario.
+    Batch(BatchArgs),
+
+    /// Run a scenario with several policies and compare their metrics.
+    Compare(CompareArgs),
+
+    /// Run a scenario with every combination of several parameters and
+    /// write the metrics of every combination.
+    Sweep(SweepArgs),
+
+    /// Run a scenario and step through its iterations in the terminal.
+    #[cfg(feature = "tui")]
+    Tui(TuiArgs),
+
+    /// List the scenarios that can be run.
+    List,
+
+    /// Generate a random scenario file, the same one for the same seed.
+    Gen(GenArgs),
+
+    /// Convert a capture of the system calls of a program, the file
+    /// written by `strace -f -ttt -T -o <file>`, into a scenario file.
+    Import(ImportArgs),
+
+    /// Find a smaller scenario that fails like a scenario file, by
+    /// removing and simplifying its instructions.
+    Shrink(ShrinkArgs),
+
+    /// Run the random scenarios of many seeds and report the seeds
+    /// whose simulation fails.
+    Stress(StressArgs),
+
+    /// Type the system calls of the running process by hand and see the
+    /// decisions of the scheduler after every one.
+    Repl(ReplArgs),
+
+    /// Run the scenarios with the configurations of the tests and
+    /// report which behaviors of the scheduler they exercise.
+    Coverage(CoverageArgs),
+
+    /// Write the reference logs that the tests compare with.
+    ///
+    /// Replaces running the tests with the `WRITE_OUTPUT` environment
+    /// variable for every configuration.
+    Bless(BlessArgs),
+
+    /// Compare the reference logs with the simulations, and find the
+    /// logs that are missing or that no test reads.
+    Verify(VerifyArgs),
+
+    /// Run the tests of a rubric and write the points that the
+    /// scheduler has earned.
+    Grade(GradeArgs),
+}
+
+#[derive(Args)]
+struct BlessArgs {
+    /// The folder of the reference logs.
+    #[arg(long, default_value = "outputs")]
+    outputs: PathBuf,
+
+    /// Only the logs whose path contains this text, like `cfs/simple`.
+    filter: Option<String>,
+
+    /// Remove the logs that no test reads.
+    #[arg(long)]
+    prune: bool,
+
+    /// Print the changes without writing them.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Also write the date in the headers of the files that are
+    /// created or updated.
+    #[arg(long)]
+    date: bool,
+}
+
+#[derive(Args)]
+struct GenArgs {
+    /// The seed of the random numbers.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// The number of processes, including PID 1.
+    #[arg(long, default_value_t = 10)]
+    processes: usize,
+
+    /// The number of events that the processes wait for and signal.
+    #[arg(long, default_value_t = 3)]
+    events: usize,
+
+    /// The file where the scenario is written, the standard output if
+    /// it is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Run the scenario and write its logs to the standard output.
+    #[arg(long)]
+    run: bool,
+
+    /// The scheduling policy of `--run`.
+    #[arg(long, env = "SCHEDULER", default_value = "round-robin", value_parser = policy_parser())]
+    scheduler: Policy,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    /// How the logs of `--run` are written.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Args)]
+struct ReplArgs {
+    /// The scheduling policy.
+    #[arg(long, env = "SCHEDULER", default_value = "round-robin", value_parser = policy_parser())]
+    scheduler: Policy,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    /// The file where the logs of the session are written when it ends.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// The file with the capture.
+    capture: PathBuf,
+
+    /// The length of a time unit, in microseconds.
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+    unit: u64,
+
+    /// The file where the scenario is written, the standard output if
+    /// it is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ShrinkArgs {
+    /// The scenario file that fails.
+    scenario: PathBuf,
+
+    /// The scheduling policy.
+    #[arg(long, env = "SCHEDULER", default_value = "round-robin", value_parser = policy_parser())]
+    scheduler: Policy,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    /// The failure to keep, the failure of the scenario if it is not given.
+    #[arg(long, value_enum)]
+    failure: Option<Failure>,
+
+    /// The file where the smaller scenario is written, the standard
+    /// output if it is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct StressArgs {
+    /// The seeds, like `0..1000`, `0..=999` or `42`.
+    #[arg(long, default_value = "0..100", value_parser = seeds)]
+    seeds: Range<u64>,
+
+    /// The kind of scenarios, for every seed.
+    #[arg(long, value_enum, default_value_t = StressScenario::Random)]
+    scenario: StressScenario,
+
+    /// The number of processes of a scenario, including PID 1.
+    #[arg(long, default_value_t = 10)]
+    processes: usize,
+
+    /// The number of events that the processes wait for and signal.
+    #[arg(long, default_value_t = 3)]
+    events: usize,
+
+    /// The scheduling policy.
+    #[arg(long, env = "SCHEDULER", default_value = "round-robin", value_parser = policy_parser())]
+    scheduler: Policy,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    /// The number of threads, the number of processors if it is not given.
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+}
+
+/// The scenarios of `runner stress`.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum StressScenario {
+    /// The scenarios of `runner gen`, which end when every process has
+    /// exited, so any other end is a failure.
+    Random,
+}
+
+/// How a scenario fails, for `runner shrink` and `runner stress`.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Failure {
+    /// The scheduler panics during the simulation.
+    Crash,
+
+    /// The simulation ends with a deadlock.
+    Deadlock,
+
+    /// The simulation ends with a panic of the scheduler, because the
+    /// process with PID 1 has exited before the others.
+    Panic,
+}
+
+impl Failure {
+    // how the scenario fails, if it does
+    fn of(scheduler: Box<dyn Scheduler>, scenario: &Scenario) -> Option<Failure> {
+        // without threads, so the simulation can be run for every candidate
+        match panic::catch_unwind(AssertUnwindSafe(|| scenario::simulate(scheduler, scenario))) {
+            Err(_) => Some(Failure::Crash),
+            Ok(logs) => match logs.last().map(|log| log.decision) {
+                Some(SchedulingDecision::Deadlock) => Some(Failure::Deadlock),
+                Some(SchedulingDecision::Panic) => Some(Failure::Panic),
+                _ => None,
             },
-            0,
-        );
-        process.sleep(10);
-        process.signal(1);
-        process.exec();
+        }
+    }
+}
+
+#[derive(Args)]
+struct CoverageArgs {
+    /// Only the configurations of this policy, every configuration if
+    /// it is not given.
+    #[arg(long, value_parser = policy_parser())]
+    scheduler: Option<Policy>,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// The folder of the reference logs.
+    #[arg(long, default_value = "outputs")]
+    outputs: PathBuf,
+
+    /// Only the logs whose path contains this text, like `cfs/simple`.
+    filter: Option<String>,
+
+    /// Print how the simulations differ from the logs.
+    #[arg(long)]
+    diff: bool,
+
+    /// Write a page that shows how the simulation differs from the log
+    /// for every mismatching log in this folder, with the path of the log
+    /// and the `.html` extension.
+    #[arg(long)]
+    html: Option<PathBuf>,
+
+    /// How the results are written.
+    #[arg(long, value_enum, default_value_t = VerifyFormat::Text)]
+    format: VerifyFormat,
+
+    /// The file where the results are written, the standard output if
+    /// it is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct GradeArgs {
+    /// The TOML file with the tests and their points.
+    rubric: PathBuf,
+
+    /// The folder of the reference logs.
+    #[arg(long, default_value = "outputs")]
+    outputs: PathBuf,
+
+    /// How the report is written.
+    #[arg(long, value_enum, default_value_t = GradeFormat::Text)]
+    format: GradeFormat,
+
+    /// The file where the report is written, the standard output if it
+    /// is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// The scheduling policy.
+    #[arg(long, env = "SCHEDULER", default_value = "round-robin", value_parser = policy_parser())]
+    scheduler: Policy,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    #[command(flatten)]
+    workload: WorkloadArgs,
+
+    /// How the logs are written.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// The time units of an interval of the utilization formats.
+    #[arg(long, default_value = "10")]
+    interval: NonZeroUsize,
+
+    /// The file where the logs are written, the standard output if it
+    /// is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Print the narration of the simulation to the standard error.
+    #[arg(long)]
+    narrate: bool,
+
+    /// Check that the scheduler only runs the processes that it lists
+    /// as ready or running and only sleeps until the first sleeping
+    /// process wakes up, and fail if it does not.
+    #[arg(long)]
+    validate: bool,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// The configuration file, see the documentation of the `config`
+    /// module of the runner.
+    #[arg(default_value = "runner.toml")]
+    config: PathBuf,
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    /// The scheduling policies, separated by commas.
+    #[arg(long, value_delimiter = ',', default_value = "round-robin,priority-queue,cfs", value_parser = policy_parser())]
+    schedulers: Vec<Policy>,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    #[command(flatten)]
+    workload: WorkloadArgs,
+}
+
+#[derive(Args)]
+struct SweepArgs {
+    /// The scheduling policies, separated by commas.
+    #[arg(long, value_delimiter = ',', default_value = "round-robin,priority-queue,cfs", value_parser = policy_parser())]
+    schedulers: Vec<Policy>,
+
+    /// The time quanta, separated by commas.
+    #[arg(long, value_delimiter = ',', default_value = "3")]
+    timeslices: Vec<NonZeroUsize>,
+
+    /// The minimum remaining timeslices, separated by commas.
+    #[arg(long, value_delimiter = ',', default_value = "1")]
+    min_remaining: Vec<usize>,
+
+    /// The time units of the cpu for an iteration, separated by commas.
+    #[arg(long, value_delimiter = ',', default_value = "10")]
+    cpu_slices: Vec<NonZeroUsize>,
+
+    #[command(flatten)]
+    workload: WorkloadArgs,
+
+    /// How the matrix is written.
+    #[arg(long, value_enum, default_value_t = SweepFormat::Csv)]
+    format: SweepFormat,
+
+    /// Add a column that tells if the reference log of every
+    /// combination exists in this folder, only for `--scenario`.
+    #[arg(long, value_name = "OUTPUTS")]
+    goldens: Option<PathBuf>,
+
+    /// The file where the matrix is written, the standard output if it
+    /// is not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// The formats of the matrix written by `runner sweep`.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum SweepFormat {
+    /// A header line and a line for every combination.
+    Csv,
+
+    /// An array with an object for every combination.
+    Json,
+}
+
+/// The formats of the results written by `runner verify`.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum VerifyFormat {
+    /// A line for every log that does not match and the totals.
+    Text,
+
+    /// A JUnit XML report, with a test case for every log.
+    Junit,
+
+    /// An object with the totals and an object for every log.
+    Json,
+}
+
+/// The formats of the report written by `runner grade`.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum GradeFormat {
+    /// A line for every test, its failures and the score.
+    Text,
+
+    /// An object with the score and the results of every test.
+    Json,
+}
+
+#[cfg(feature = "tui")]
+#[derive(Args)]
+struct TuiArgs {
+    /// The scheduling policy.
+    #[arg(long, env = "SCHEDULER", default_value = "round-robin", value_parser = policy_parser())]
+    scheduler: Policy,
+
+    #[command(flatten)]
+    parameters: Parameters,
+
+    #[command(flatten)]
+    workload: WorkloadArgs,
+
+    /// The milliseconds between two iterations while playing.
+    #[arg(long, default_value_t = 500)]
+    delay: u64,
+}
+
+/// The parameters of the schedulers.
+#[derive(Args)]
+struct Parameters {
+    /// The time quanta of the round robin and priority queue policies.
+    #[arg(long, env = "TIMESLICE", default_value = "3")]
+    timeslice: NonZeroUsize,
+
+    /// The minimum remaining timeslice that allows a process to be
+    /// scheduled again after a system call.
+    #[arg(long, env = "REMAINING", default_value_t = 1)]
+    min_remaining: usize,
+
+    /// The time units that the cpu has for an iteration, used by the
+    /// cfs policy.
+    #[arg(long, env = "CPU_SLICES", default_value = "10")]
+    cpu_slices: NonZeroUsize,
+}
+
+impl Parameters {
+    fn params(&self) -> Params {
+        Params {
+            timeslice: self.timeslice,
+            minimum_remaining_timeslice: self.min_remaining,
+            cpu_time: self.cpu_slices,
+        }
+    }
+}
+
+/// The workload to simulate, given in one of four ways.
+#[derive(Args)]
+struct WorkloadArgs {
+    /// The scenario to run, like `workers/sleeper` (see `runner list`).
+    #[arg(long, required_unless_present_any = ["file", "trace", "workload"])]
+    scenario: Option<String>,
+
+    /// A workload of `processor::workloads`, like `pipeline`.
+    #[arg(long, conflicts_with = "scenario", value_parser = workload_parser())]
+    workload: Option<workloads::Workload>,
+
+    /// A TOML file that describes the scenario to run, see the
+    /// documentation of `processor::scenario`.
+    #[arg(long, conflicts_with_all = ["scenario", "workload"])]
+    file: Option<PathBuf>,
+
+    /// A file with the system calls of the scenario to run, see the
+    /// documentation of `processor::replay`.
+    #[arg(long, conflicts_with_all = ["scenario", "workload", "file"])]
+    trace: Option<PathBuf>,
+}
+
+/// A workload that can be simulated several times.
+enum Workload {
+    Builtin(test_workloads::Workload<Box<dyn Scheduler>>),
+    Standard(workloads::Workload),
+    File(Scenario),
+    Trace(SyscallTrace),
+}
+
+impl WorkloadArgs {
+    fn load(&self) -> Result<Workload, String> {
+        if let Some(workload) = self.workload {
+            return Ok(Workload::Standard(workload));
+        }
+        match (&self.scenario, &self.file, &self.trace) {
+            (Some(name), _, _) => test_workloads::find(name)
+                .map(Workload::Builtin)
+                .ok_or_else(|| format!("unknown scenario {name}, see `runner list`")),
+            (None, Some(path), _) => Scenario::load(path).map(Workload::File).map_err(|error| error.to_string()),
+            (None, None, Some(path)) => fs::read_to_string(path)
+                .map_err(|error| format!("unable to read {}: {error}", path.display()))?
+                .parse()
+                .map(Workload::Trace)
+                .map_err(|error| format!("{}: {error}", path.display())),
+            // clap requires one of them
+            (None, None, None) => unreachable!(),
+        }
+    }
+}
+
+impl Workload {
+    fn execute(&self, process: &Process<Box<dyn Scheduler>>) {
+        match self {
+            Workload::Builtin(workload) => workload(process),
+            Workload::Standard(workload) => workload.run(process),
+            Workload::File(scenario) => scenario.execute(process),
+            Workload::Trace(trace) => trace.execute(process),
+        }
+    }
+}
+
+/// The formats of the logs written by `runner run`.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The format of the reference logs.
+    Text,
+
+    /// Aligned tables.
+    Pretty,
+
+    /// Markdown tables.
+    Markdown,
+
+    /// The reference format followed by statistics about the simulation.
+    Summary,
+
+    /// One JSON object for every iteration, on separate lines.
+    Json,
+
+    /// A report with a Gantt chart.
+    Html,
+
+    /// The Chrome trace event format.
+    ChromeTrace,
+
+    /// The system calls of the processes, which `--trace` replays.
+    Syscalls,
+
+    /// The utilization of the processor during every `--interval`.
+    Utilization,
+
+    /// The utilization of the processor during every `--interval`, as CSV.
+    UtilizationCsv,
+}
+
+fn policy_names() -> [PossibleValue; 3] {
+    Policy::ALL.map(|policy| match policy {
+        Policy::RoundRobin => PossibleValue::new(policy.name()).alias("rr"),
+        Policy::PriorityQueue => PossibleValue::new(policy.name()).alias("pq"),
+        Policy::Cfs => PossibleValue::new(policy.name()),
+    })
+}
+
+fn policy_parser() -> impl TypedValueParser<Value = Policy> {
+    PossibleValuesParser::new(policy_names()).map(|name: String| {
+        // the parser accepts the names of the policies and their aliases
+        let position = policy_names().iter().position(|value| value.matches(&name, false)).unwrap();
+        Policy::ALL[position]
+    })
+}
+
+fn workload_parser() -> impl TypedValueParser<Value = workloads::Workload> {
+    let names = workloads::standard().iter().map(|workload| workload.name());
+    // the parser only accepts the names of the workloads
+    PossibleValuesParser::new(names).map(|name: String| workloads::find(&name).unwrap())
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Run(args) => run(args),
+        Command::Batch(args) => batch(args),
+        Command::Compare(args) => compare(args),
+        Command::Sweep(args) => sweep(args),
+        #[cfg(feature = "tui")]
+        Command::Tui(args) => tui(args),
+        Command::List => {
+            let mut stdout = io::stdout().lock();
+            test_workloads::all::<Box<dyn Scheduler>>()
+                .iter()
+                .try_for_each(|(name, _)| writeln!(stdout, "{name}"))
+                .map_err(|error| format!("unable to list the scenarios: {error}"))
+        }
+        Command::Gen(args) => generate(args),
+        Command::Import(args) => import(args),
+        Command::Shrink(args) => shrink(args),
+        Command::Stress(args) => stress(args),
+        Command::Repl(args) => repl(args),
+        Command::Coverage(args) => coverage(args),
+        Command::Bless(args) => bless(args),
+        Command::Verify(args) => verify(args),
+        Command::Grade(args) => grade(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("runner: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: RunArgs) -> Result<(), String> {
+    let workload = args.workload.load()?;
+    // the name of a policy is always known
+    let scheduler = scheduler::by_name(args.scheduler.name(), &args.parameters.params()).unwrap();
+
+    let mut output: Box<dyn Write + Send> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).map_err(|error| format!("unable to create {}: {error}", path.display()))?,
+        )),
+        None => Box::new(io::stdout()),
+    };
+    let narration: Box<dyn Write + Send> = match args.narrate {
+        true => Box::new(io::stderr()),
+        false => Box::new(io::sink()),
+    };
+
+    let builder = Processor::builder().narrate(narration).validate(args.validate);
+    let workload = |process: &Process<Box<dyn Scheduler>>| workload.execute(process);
+    let logs = if args.format == Format::Json {
+        // the iterations are written as soon as they are complete, only
+        // the violations are looked for in the logs that are kept
+        builder
+            .sink(WriterSink::new(output, SinkFormat::Json))
+            .retain_logs(args.validate)
+            .run(scheduler, workload)
+    } else {
+        let logs = builder.run(scheduler, workload);
+        output
+            .write_all(render(args.format, args.interval, &logs).as_bytes())
+            .and_then(|()| output.flush())
+            .map_err(|error| format!("unable to write the logs: {error}"))?;
+        logs
+    };
+    let violations = logs
+        .iter()
+        .enumerate()
+        .filter_map(|(iteration, log)| log.violation.map(|violation| format!("iteration {}: {violation}", iteration + 1)))
+        .collect::<Vec<_>>();
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(format!("invalid decisions\n{}", violations.join("\n"))),
+    }
+}
+
+fn batch(args: BatchArgs) -> Result<(), String> {
+    let config = Config::load(&args.config)?;
+    let base = args.config.parent().unwrap_or(Path::new(""));
+    let runs = config.runs(base).map_err(|error| format!("{}: {error}", args.config.display()))?;
+    if let Some(folder) = config.output(base) {
+        fs::create_dir_all(&folder).map_err(|error| format!("unable to create {}: {error}", folder.display()))?;
+    }
+    for args in runs {
+        let output = args.output.clone();
+        run(args)?;
+        if let Some(path) = output {
+            println!("wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn compare(args: CompareArgs) -> Result<(), String> {
+    let workload = args.workload.load()?;
+    let mut rows = vec![["SCHEDULER", "OUTCOME", "MAKESPAN", "TURNAROUND", "WAITING", "RESPONSE", "SWITCHES", "UTILIZATION"].map(String::from)];
+    for policy in &args.schedulers {
+        // the name of a policy is always known
+        let scheduler = scheduler::by_name(policy.name(), &args.parameters.params()).unwrap();
+        let logs = Processor::builder().narrate(io::sink()).run(scheduler, |process| workload.execute(process));
+        let metrics = Metrics::new(&logs);
+        let average = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{value:.2}"));
+        rows.push([
+            policy.name().to_string(),
+            outcome(&metrics),
+            metrics.makespan.to_string(),
+            average(metrics.average_turnaround()),
+            average(metrics.average_waiting()),
+            average(metrics.average_response()),
+            metrics.context_switches.to_string(),
+            format!("{:.1}%", metrics.utilization() * 100.0),
+        ]);
+    }
+
+    let widths = (0..rows[0].len()).map(|column| rows.iter().map(|row| row[column].len()).max().unwrap()).collect::<Vec<_>>();
+    let mut stdout = io::stdout().lock();
+    rows.iter()
+        .try_for_each(|row| {
+            let cells = row.iter().zip(&widths).map(|(cell, width)| format!("{cell:<width$}")).collect::<Vec<_>>();
+            writeln!(stdout, "{}", cells.join("  ").trim_end())
+        })
+        .map_err(|error| format!("unable to write the comparison: {error}"))
+}
+
+// the last decision of a simulation, in lowercase
+fn outcome(metrics: &Metrics) -> String {
+    match metrics.outcome {
+        Some(SchedulingDecision::Done) => "done".to_string(),
+        Some(SchedulingDecision::Deadlock) => "deadlock".to_string(),
+        Some(SchedulingDecision::Panic) => "panic".to_string(),
+        outcome => outcome.map_or("-".to_string(), |outcome| outcome.to_string()),
+    }
+}
+
+fn sweep(args: SweepArgs) -> Result<(), String> {
+    if args.goldens.is_some() && args.workload.scenario.is_none() {
+        return Err("only the scenarios of `runner list` have reference logs".to_string());
+    }
+    let workload = args.workload.load()?;
+    let mut columns = vec!["scheduler", "timeslice", "min_remaining", "cpu_slices", "outcome", "makespan", "turnaround", "waiting", "response", "switches", "utilization"];
+    if args.goldens.is_some() {
+        columns.push("golden");
+    }
+
+    // the values of every combination, `None` for the missing averages
+    let mut rows = Vec::new();
+    for policy in &args.schedulers {
+        for &timeslice in &args.timeslices {
+            for &min_remaining in &args.min_remaining {
+                for &cpu_slices in &args.cpu_slices {
+                    let params = Params {
+                        timeslice,
+                        minimum_remaining_timeslice: min_remaining,
+                        cpu_time: cpu_slices,
+                    };
+                    // the name of a policy is always known
+                    let scheduler = scheduler::by_name(policy.name(), &params).unwrap();
+                    let logs = Processor::builder().narrate(io::sink()).run(scheduler, |process| workload.execute(process));
+                    let metrics = Metrics::new(&logs);
+                    let average = |value: Option<f64>| value.map(|value| format!("{value:.2}"));
+                    let mut row = vec![
+                        Some(policy.name().to_string()),
+                        Some(timeslice.to_string()),
+                        Some(min_remaining.to_string()),
+                        Some(cpu_slices.to_string()),
+                        Some(outcome(&metrics)),
+                        Some(metrics.makespan.to_string()),
+                        average(metrics.average_turnaround()),
+                        average(metrics.average_waiting()),
+                        average(metrics.average_response()),
+                        Some(metrics.context_switches.to_string()),
+                        Some(format!("{:.3}", metrics.utilization())),
+                    ];
+                    if let (Some(outputs), Some(name)) = (&args.goldens, &args.workload.scenario) {
+                        let path = golden::path(policy.name(), name, timeslice.get(), min_remaining, cpu_slices.get());
+                        let present = outputs.join(path).is_file();
+                        row.push(Some(if present { "present" } else { "missing" }.to_string()));
+                    }
+                    rows.push(row);
+                }
+            }
+        }
+    }
+
+    let text = match args.format {
+        SweepFormat::Csv => {
+            let lines = rows.iter().map(|row| row.iter().map(|value| value.as_deref().unwrap_or("")).collect::<Vec<_>>().join(","));
+            std::iter::once(columns.join(",")).chain(lines).map(|line| line + "\n").collect::<String>()
+        }
+        SweepFormat::Json => {
+            // the names and the outcomes are quoted, the other values are numbers
+            let quoted = ["scheduler", "outcome", "golden"];
+            let objects = rows.iter().map(|row| {
+                let fields = columns.iter().zip(row).map(|(column, value)| match value {
+                    Some(value) if quoted.contains(column) => format!("\"{column}\":\"{value}\""),
+                    Some(value) => format!("\"{column}\":{value}"),
+                    None => format!("\"{column}\":null"),
+                });
+                format!("{{{}}}", fields.collect::<Vec<_>>().join(","))
+            });
+            format!("[{}]\n", objects.collect::<Vec<_>>().join(","))
+        }
+    };
+    match &args.output {
+        Some(path) => fs::write(path, text).map_err(|error| format!("unable to write {}: {error}", path.display())),
+        None => io::stdout().write_all(text.as_bytes()).map_err(|error| format!("unable to write the matrix: {error}")),
+    }
+}
+
+#[cfg(feature = "tui")]
+fn tui(args: TuiArgs) -> Result<(), String> {
+    let workload = args.workload.load()?;
+    // the name of a policy is always known
+    let scheduler = scheduler::by_name(args.scheduler.name(), &args.parameters.params()).unwrap();
+    let logs = Processor::builder().narrate(io::sink()).run(scheduler, |process| workload.execute(process));
+
+    let name = match (&args.workload.scenario, &args.workload.workload, &args.workload.file, &args.workload.trace) {
+        (Some(name), _, _, _) => name.clone(),
+        (None, Some(workload), _, _) => workload.to_string(),
+        (None, None, Some(path), _) | (None, None, None, Some(path)) => path.display().to_string(),
+        (None, None, None, None) => unreachable!(),
+    };
+    let title = format!("{} - {name}", args.scheduler.name());
+    tui::show(title, &logs, std::time::Duration::from_millis(args.delay)).map_err(|error| format!("unable to use the terminal: {error}"))
+}
+
+fn import(args: ImportArgs) -> Result<(), String> {
+    let capture = fs::read_to_string(&args.capture).map_err(|error| format!("unable to read {}: {error}", args.capture.display()))?;
+    let scenario = strace::import(&capture, Duration::from_micros(args.unit)).map_err(|error| format!("{}: {error}", args.capture.display()))?;
+    match &args.output {
+        Some(path) => fs::write(path, scenario.to_string()).map_err(|error| format!("unable to write {}: {error}", path.display())),
+        None => write!(io::stdout().lock(), "{scenario}").map_err(|error| format!("unable to write the scenario: {error}")),
+    }
+}
+
+fn shrink(args: ShrinkArgs) -> Result<(), String> {
+    let original = Scenario::load(&args.scenario).map_err(|error| error.to_string())?;
+    let params = args.parameters.params();
+    // the name of a policy is always known
+    let failure = |scenario: &Scenario| Failure::of(scheduler::by_name(args.scheduler.name(), &params).unwrap(), scenario);
+
+    // the panics of the candidates are expected
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = match failure(&original) {
+        None => Err(format!("{} does not fail", args.scenario.display())),
+        Some(found) if args.failure.is_some_and(|failure| failure != found) => {
+            Err(format!("{} fails with a {}, not a {}", args.scenario.display(), name(found), name(args.failure.unwrap())))
+        }
+        Some(found) => Ok((found, scenario::shrink(&original, |candidate| failure(candidate) == Some(found)))),
+    };
+    panic::set_hook(hook);
+    let (found, shrunk) = result?;
+
+    let text = format!("# shrunk from {} to {} instructions, fails with a {}\n{shrunk}", original.size(), shrunk.size(), name(found));
+    match &args.output {
+        Some(path) => fs::write(path, text).map_err(|error| format!("unable to write {}: {error}", path.display())),
+        None => io::stdout().write_all(text.as_bytes()).map_err(|error| format!("unable to write the scenario: {error}")),
+    }
+}
+
+fn stress(args: StressArgs) -> Result<(), String> {
+    let StressArgs { seeds, scenario: StressScenario::Random, processes, events, .. } = args;
+    let params = args.parameters.params();
+    let threads = args.threads.or_else(|| thread::available_parallelism().ok()).map_or(1, NonZeroUsize::get);
+    // the threads take the next seed until there are no more
+    let next = AtomicU64::new(seeds.start);
+    let failures = Mutex::new(Vec::new());
+
+    // the panics of the schedulers are counted as failures
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let seed = next.fetch_add(1, Ordering::Relaxed);
+                if seed >= seeds.end {
+                    break;
+                }
+                let scenario = scenario::generate(seed, processes, events);
+                // the name of a policy is always known
+                let scheduler = scheduler::by_name(args.scheduler.name(), &params).unwrap();
+                if let Some(failure) = Failure::of(scheduler, &scenario) {
+                    failures.lock().unwrap().push((seed, failure));
+                }
+            });
+        }
     });
+    panic::set_hook(hook);
+
+    let mut failures = failures.into_inner().unwrap();
+    failures.sort_by_key(|(seed, _)| *seed);
+    let total = seeds.end.saturating_sub(seeds.start);
+    println!("{total} seeds, {} passed, {} failed", total - failures.len() as u64, failures.len());
+    for kind in Failure::value_variants() {
+        let seeds = failures.iter().filter(|(_, failure)| failure == kind).map(|(seed, _)| seed.to_string()).collect::<Vec<_>>();
+        if !seeds.is_empty() {
+            println!("{}: {}", name(*kind), seeds.join(", "));
+        }
+    }
+    let Some((seed, _)) = failures.first() else {
+        return Ok(());
+    };
+    println!(
+        "reproduce with `runner gen --seed {seed} --processes {processes} --events {events} -o failing.toml` and \
+         `runner shrink failing.toml --scheduler {} --timeslice {} --min-remaining {} --cpu-slices {}`",
+        args.scheduler.name(),
+        params.timeslice,
+        params.minimum_remaining_timeslice,
+        params.cpu_time
+    );
+    Err(format!("{} seeds have failed", failures.len()))
+}
+
+// the seeds of `a..b`, `a..=b` or `a`
+fn seeds(value: &str) -> Result<Range<u64>, String> {
+    let number = |number: &str| number.trim().parse::<u64>().map_err(|_| format!("`{number}` is not a seed"));
+    match value.split_once("..") {
+        Some((start, end)) => match end.strip_prefix('=') {
+            Some(last) => Ok(number(start)?..number(last)?.saturating_add(1)),
+            None => Ok(number(start)?..number(end)?),
+        },
+        None => number(value).map(|seed| seed..seed.saturating_add(1)),
+    }
+}
+
+// the name of a value of an enum of the arguments, like `deadlock`
+fn name(value: impl ValueEnum) -> String {
+    // the values are never skipped
+    value.to_possible_value().unwrap().get_name().to_string()
+}
+
+fn repl(args: ReplArgs) -> Result<(), String> {
+    let scheduler = scheduler::by_name(args.scheduler.name(), &args.parameters.params()).unwrap();
+    let logs = repl::run(scheduler, io::stdin().lock(), io::stdout().lock()).map_err(|error| format!("unable to read the commands: {error}"))?;
+    if let Some(path) = args.output {
+        fs::write(&path, format_logs(&logs)).map_err(|error| format!("unable to write {}: {error}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn generate(args: GenArgs) -> Result<(), String> {
+    let scenario = scenario::generate(args.seed, args.processes, args.events);
+    let mut stdout = io::stdout().lock();
+    match &args.output {
+        Some(path) => fs::write(path, scenario.to_string()).map_err(|error| format!("unable to write {}: {error}", path.display()))?,
+        // the logs are written instead of the scenario
+        None if args.run => {}
+        None => return write!(stdout, "{scenario}").map_err(|error| format!("unable to write the scenario: {error}")),
+    }
+    if args.run {
+        // the name of a policy is always known
+        let scheduler = scheduler::by_name(args.scheduler.name(), &args.parameters.params()).unwrap();
+        // without threads, so large scenarios do not need a thread for every process
+        let logs = scenario::simulate(scheduler, &scenario);
+        let format = match args.format {
+            // there is no sink, the logs are all written at the end
+            Format::Json => export::json(&logs) + "\n",
+            format => render(format, NonZeroUsize::new(10).unwrap(), &logs),
+        };
+        stdout.write_all(format.as_bytes()).map_err(|error| format!("unable to write the logs: {error}"))?;
+    }
+    Ok(())
+}
+
+fn coverage(args: CoverageArgs) -> Result<(), String> {
+    let mut coverage = Coverage::new();
+    for golden in golden::goldens(None) {
+        if args.scheduler.is_none_or(|policy| policy == golden.configuration.policy) {
+            coverage.add(&golden.logs());
+        }
+    }
+    writeln!(io::stdout(), "{coverage}").map_err(|error| format!("unable to write the coverage: {error}"))
+}
+
+fn bless(args: BlessArgs) -> Result<(), String> {
+    let filter = args.filter.as_deref();
+    let (mut created, mut updated, mut unchanged) = (0, 0, 0);
+    for golden in golden::goldens(filter) {
+        let path = args.outputs.join(&golden.path);
+        let header = match args.date {
+            true => golden.header().with(header::DATE, header::today()),
+            false => golden.header(),
+        };
+        let logs = golden.render();
+        let change = match fs::read_to_string(&path) {
+            Ok(reference) => {
+                let (previous, reference) = header::split(&reference);
+                // a file is not written again only for a new date
+                if reference == logs && previous.same_origin(&header) {
+                    unchanged += 1;
+                    continue;
+                }
+                updated += 1;
+                if args.dry_run { "would update" } else { "updated" }
+            }
+            Err(_) => {
+                created += 1;
+                if args.dry_run { "would create" } else { "created" }
+            }
+        };
+        println!("{change} {}", golden.path.display());
+        if !args.dry_run {
+            // the file is always in a folder of the outputs
+            fs::create_dir_all(path.parent().unwrap())
+                .and_then(|()| fs::write(&path, format!("{header}{logs}")))
+                .map_err(|error| format!("unable to write {}: {error}", path.display()))?;
+        }
+    }
+
+    let stale = golden::stale(&args.outputs, filter).map_err(|error| format!("unable to read {}: {error}", args.outputs.display()))?;
+    for path in &stale {
+        match args.prune {
+            true => {
+                println!("{} {}", if args.dry_run { "would remove" } else { "removed" }, path.display());
+                if !args.dry_run {
+                    let path = args.outputs.join(path);
+                    fs::remove_file(&path).map_err(|error| format!("unable to remove {}: {error}", path.display()))?;
+                }
+            }
+            false => println!("stale {}", path.display()),
+        }
+    }
+    let (create, update, remove) = match args.dry_run {
+        true => ("to create", "to update", "to remove"),
+        false => ("created", "updated", "removed"),
+    };
+    println!(
+        "{created} {create}, {updated} {update}, {unchanged} unchanged, {} {}{}",
+        stale.len(),
+        if args.prune { remove } else { "stale (remove them with --prune)" },
+        if args.dry_run { ", dry run, no files written" } else { "" }
+    );
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<(), String> {
+    let filter = args.filter.as_deref();
+    let mut cases = Vec::new();
+    for golden in golden::goldens(filter) {
+        let outcome = match fs::read_to_string(args.outputs.join(&golden.path)) {
+            Err(_) => Outcome::Missing,
+            Ok(reference) => {
+                // the header is only metadata
+                let (_, reference) = header::split(&reference);
+                let logs = golden.render();
+                match reference == logs {
+                    true => Outcome::Matching,
+                    // both are written by `format_logs`, unless the reference is corrupted
+                    false => Outcome::mismatching(match (parse_logs(reference), parse_logs(&logs)) {
+                        (Ok(expected), Ok(actual)) => {
+                            if let Some(folder) = &args.html {
+                                let mut path = folder.join(&golden.path).into_os_string();
+                                path.push(".html");
+                                let path = PathBuf::from(path);
+                                // the folders of the configurations
+                                fs::create_dir_all(path.parent().unwrap())
+                                    .and_then(|_| fs::write(&path, report::html_diff(&expected, &actual)))
+                                    .map_err(|error| format!("unable to write {}: {error}", path.display()))?;
+                            }
+                            Ok(diff(&expected, &actual))
+                        }
+                        (Err(error), _) | (_, Err(error)) => Err(format!("unable to parse the logs: {error}")),
+                    }),
+                }
+            }
+        };
+        cases.push(Case {
+            path: golden.path,
+            folder: golden.configuration.folder.to_string(),
+            outcome,
+        });
+    }
+    let stale = golden::stale(&args.outputs, filter).map_err(|error| format!("unable to read {}: {error}", args.outputs.display()))?;
+    for path in stale {
+        // the path of a log always starts with its folder
+        let folder = path.iter().next().unwrap().to_string_lossy().into_owned();
+        cases.push(Case { path, folder, outcome: Outcome::Stale });
+    }
+
+    let text = match args.format {
+        VerifyFormat::Text => verification::text(&cases, args.diff),
+        VerifyFormat::Junit => verification::junit(&cases),
+        VerifyFormat::Json => verification::json(&cases),
+    };
+    match &args.output {
+        Some(path) => fs::write(path, text).map_err(|error| format!("unable to write {}: {error}", path.display()))?,
+        None => io::stdout().write_all(text.as_bytes()).map_err(|error| format!("unable to write the results: {error}"))?,
+    }
+    match cases.iter().filter(|case| !matches!(case.outcome, Outcome::Matching)).count() {
+        0 => Ok(()),
+        _ => Err("the reference logs are out of date, see `runner bless`".to_string()),
+    }
+}
+
+fn grade(args: GradeArgs) -> Result<(), String> {
+    let rubric = grade::Rubric::load(&args.rubric)?;
+    let report = grade::grade(&rubric, &args.outputs)?;
+    let text = match args.format {
+        GradeFormat::Text => report.text(),
+        GradeFormat::Json => report.json(),
+    };
+    match &args.output {
+        Some(path) => fs::write(path, text).map_err(|error| format!("unable to write {}: {error}", path.display())),
+        None => io::stdout().write_all(text.as_bytes()).map_err(|error| format!("unable to write the report: {error}")),
+    }
+}
 
-    println!("{}", format_logs(&logs));
+fn render(format: Format, interval: NonZeroUsize, logs: &[Log]) -> String {
+    match format {
+        Format::Text => format!("{}\n", format_logs(logs)),
+        Format::Pretty => format_logs_pretty(logs),
+        Format::Markdown => format_logs_markdown(logs),
+        Format::Summary => format_logs_with_summary(logs),
+        Format::Html => report::html(logs, &Metrics::new(logs)),
+        Format::ChromeTrace => export::chrome_trace(logs),
+        Format::Syscalls => SyscallTrace::record(logs).to_string(),
+        Format::Utilization => utilization::text(&utilization::intervals(logs, interval)),
+        Format::UtilizationCsv => utilization::csv(&utilization::intervals(logs, interval)),
+        // written by the sink while the simulation runs
+        Format::Json => String::new(),
+    }
 }
 
 // Do not delete this line
@@ -0,0 +1,125 @@
+//! The interactive mode of `runner repl`, where the user types the
+//! system calls of the running process.
+//!
+//! After every command, the iterations that the command has produced
+//! are printed with the decision of the scheduler and the processes.
+
+use std::io::{self, BufRead, Write};
+
+use processor::{FormatOptions, Log, LogFormatter, PrettyFormatter, Session};
+use scheduler::{Event, Priority, Scheduler, Syscall};
+
+const HELP: &str = "\
+exec [units]        execute for some time units, 1 if not given
+fork [priority]     create a process, with priority 0 if not given
+sleep <units>       sleep for some time units
+wait <event>        wait for an event, like 1 or io:3
+signal <event>      signal an event
+exit                end the running process
+help                show this message
+quit                end the session";
+
+/// A line typed by the user.
+enum Command {
+    Exec(usize),
+    Syscall(Syscall),
+    Help,
+    Quit,
+}
+
+/// Reads commands from `input` until the simulation ends or the user
+/// quits, and returns the logs of the simulation.
+///
+/// * `scheduler` - the scheduler of the simulation.
+/// * `input` - the lines typed by the user.
+/// * `output` - where the prompt and the iterations are written.
+pub(crate) fn run(scheduler: Box<dyn Scheduler>, input: impl BufRead, mut output: impl Write) -> io::Result<Vec<Log>> {
+    let formatter = PrettyFormatter::new(FormatOptions::default());
+    let mut session = Session::new(scheduler);
+    writeln!(output, "Type the system calls of the running process, `help` for the commands.\n")?;
+    let mut shown = show(&formatter, session.logs(), 0, &mut output)?;
+
+    let mut lines = input.lines();
+    while let Some(pid) = session.running() {
+        write!(output, "{pid} ({} left)> ", session.remaining())?;
+        output.flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            writeln!(output)?;
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(&line) {
+            Ok(Command::Exec(units)) => {
+                // the process stops executing once the scheduler stops it
+                for _ in 0..units {
+                    session.exec();
+                    if session.logs().len() > shown {
+                        break;
+                    }
+                }
+            }
+            Ok(Command::Syscall(syscall)) => {
+                session.syscall(syscall);
+            }
+            Ok(Command::Help) => writeln!(output, "{HELP}")?,
+            Ok(Command::Quit) => break,
+            Err(error) => writeln!(output, "{error}, type `help` for the commands")?,
+        }
+        shown = show(&formatter, session.logs(), shown, &mut output)?;
+    }
+    if session.running().is_none() {
+        writeln!(output, "The simulation has ended.")?;
+    }
+    Ok(session.into_logs())
+}
+
+// writes the stop reason of the last iteration that was shown and the
+// iterations after it, and returns the number of iterations shown
+fn show(formatter: &PrettyFormatter, logs: &[Log], shown: usize, output: &mut impl Write) -> io::Result<usize> {
+    if logs.len() == shown {
+        return Ok(shown);
+    }
+    if let Some((reason, result)) = shown.checked_sub(1).and_then(|last| logs[last].stop_reason) {
+        writeln!(output, "{reason} -> {result:?}\n")?;
+    }
+    for (iteration, log) in logs.iter().enumerate().skip(shown) {
+        write!(output, "{}", formatter.format_iteration(log, iteration))?;
+    }
+    Ok(logs.len())
+}
+
+fn parse(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    let argument = words.next();
+    if let Some(extra) = words.next() {
+        return Err(format!("unexpected `{extra}`"));
+    }
+    let number = |default: Option<usize>| match argument {
+        Some(argument) => argument.parse::<usize>().map_err(|_| format!("`{argument}` is not a number")),
+        None => default.ok_or_else(|| format!("`{command}` needs a number")),
+    };
+    let event = || match argument {
+        Some(argument) => argument.parse::<Event>().map_err(|_| format!("`{argument}` is not an event")),
+        None => Err(format!("`{command}` needs an event")),
+    };
+    Ok(match command {
+        "exec" => Command::Exec(number(Some(1))?),
+        "fork" => {
+            let priority = match argument {
+                Some(argument) => argument.parse::<i8>().map_err(|_| format!("`{argument}` is not a priority"))?,
+                None => 0,
+            };
+            Command::Syscall(Syscall::Fork(Priority::new(priority)))
+        }
+        "sleep" => Command::Syscall(Syscall::Sleep(number(None)?)),
+        "wait" => Command::Syscall(Syscall::Wait(event()?)),
+        "signal" => Command::Syscall(Syscall::Signal(event()?)),
+        "exit" => Command::Syscall(Syscall::Exit),
+        "help" => Command::Help,
+        "quit" => Command::Quit,
+        command => return Err(format!("unknown command `{command}`")),
+    })
+}
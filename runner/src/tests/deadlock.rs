@@ -1,133 +1,97 @@
+use std::io;
+
 use function_name::named;
-use processor::Processor;
 
-use super::{run, scheduler};
+use processor::{test_workloads, Processor};
+use scheduler::{EventSemantics, Policy, SchedulerConfig, SchedulingDecision};
+
+use super::{run, simulate, Config, SCHEDULER};
 
 #[test]
 #[named]
 pub fn wait() {
-    let logs = Processor::run(scheduler(), |process| {
-        for _ in 0..5 {
-            process.exec();
-        }
-        process.wait(1);
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::deadlock::wait);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
 
 #[test]
 #[named]
 pub fn signal_before_wait() {
-    let logs = Processor::run(scheduler(), |process| {
-        for _ in 0..5 {
-            process.exec();
-        }
-        process.signal(1);
-        process.wait(1);
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::deadlock::signal_before_wait);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
 
+/// With sticky events, the signal is remembered and the wait returns.
+#[test]
+pub fn signal_before_wait_sticky() {
+    let config = Config::from_env();
+    let policy = Policy::from_name(SCHEDULER).unwrap_or(Policy::RoundRobin);
+    let scheduler = SchedulerConfig::new(policy)
+        .min_remaining(config.remaining)
+        .event_semantics(EventSemantics::Sticky);
+    let scheduler = match policy {
+        Policy::Cfs => scheduler.cpu_time(config.cpu_slices),
+        _ => scheduler.timeslice(config.timeslice),
+    };
+    let logs = Processor::builder()
+        .narrate(io::sink())
+        .run(scheduler.build().unwrap(), test_workloads::deadlock::signal_before_wait);
+
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+}
+
 #[test]
 #[named]
 pub fn wait_2() {
-    let logs = Processor::run(scheduler(), |process| {
-        process.fork(
-            |process| {
-                for _ in 0..5 {
-                    process.exec();
-                }
-                process.wait(2);
-            },
-            0,
-        );
-        process.sleep(10);
-        process.wait(1);
-        process.sleep(10);
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::deadlock::wait_2);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
 
 #[test]
 #[named]
 pub fn signal_before_wait_2() {
-    let logs = Processor::run(scheduler(), |process| {
-        process.fork(
-            |process| {
-                for _ in 0..5 {
-                    process.exec();
-                }
-                process.wait(2);
-            },
-            0,
-        );
-        process.signal(2);
-        process.wait(2);
-        process.sleep(10);
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::deadlock::signal_before_wait_2);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
 
 #[test]
 #[named]
 pub fn wait_3() {
-    let logs = Processor::run(scheduler(), |process| {
-        process.fork(
-            |process| {
-                for _ in 0..5 {
-                    process.exec();
-                }
-                process.wait(1);
-            },
-            0,
-        );
-        process.fork(
-            |process| {
-                for _ in 0..5 {
-                    process.exec();
-                }
-                process.wait(1);
-            },
-            0,
-        );
-        process.fork(
-            |process| {
-                for _ in 0..5 {
-                    process.exec();
-                }
-                process.wait(2);
-            },
-            0,
-        );
-        process.sleep(10);
-        process.signal(1);
-        process.wait(0);
-        process.sleep(10);
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::deadlock::wait_3);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
@@ -4,52 +4,140 @@ use scheduler::cfs;
 use scheduler::priority_queue;
 #[cfg(not(any(feature = "priority-queue", feature = "cfs")))]
 use scheduler::round_robin;
-use scheduler::Scheduler;
+use scheduler::{Policy, Scheduler};
 
 use std::env;
 use std::fs;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
 
 use processor::format_logs;
-use processor::Log;
+use processor::header::{self, Header};
+use processor::{diff, parse_logs, Log, Process, Processor};
 use std::num::NonZeroUsize;
 
 mod deadlock;
 mod panic;
+mod properties;
 mod simple;
+mod strace;
 mod wait_and_signal;
 mod workers;
+mod workloads;
+
+/// The configuration of a test, read from the environment and passed
+/// to the helpers, so tests that run at the same time do not share
+/// any state.
+struct Config {
+    timeslice: usize,
+    remaining: usize,
+    cpu_slices: usize,
+    write_output: bool,
+    // the date changes every file that is written again, so it is
+    // only written with `WRITE_DATE`
+    write_date: bool,
+}
+
+impl Config {
+    fn from_env() -> Config {
+        let (timeslice, remaining, cpu_slices) = arguments();
+        Config {
+            timeslice,
+            remaining,
+            cpu_slices,
+            write_output: env::var("WRITE_OUTPUT").is_ok(),
+            write_date: env::var("WRITE_DATE").is_ok(),
+        }
+    }
+
+    fn path(&self, folder: &str, name: &str) -> String {
+        let Config { timeslice, remaining, cpu_slices, .. } = self;
+        format!("../outputs/{SCHEDULER}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log")
+    }
+
+    fn header(&self, folder: &str, name: &str) -> Header {
+        let fields = Header::new()
+            // the tests without a scheduler feature use the round robin policy
+            .with("scheduler", Policy::from_name(SCHEDULER).unwrap_or(Policy::RoundRobin).name())
+            .with("timeslice", self.timeslice)
+            .with("remaining", self.remaining)
+            .with("cpu-slices", self.cpu_slices)
+            .with("version", header::VERSION)
+            .with("scenario", format!("{folder}/{name}"));
+        match self.write_date {
+            true => fields.with(header::DATE, header::today()),
+            false => fields,
+        }
+    }
+}
+
+/// The narration of a simulation, kept apart from the standard output.
+#[derive(Clone, Default)]
+struct Narration(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Narration {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The logs and the narration of a simulation.
+struct Simulation {
+    logs: Vec<Log>,
+    narration: String,
+}
+
+fn simulate<F>(config: &Config, f: F) -> Simulation
+where
+    F: FnOnce(&Process<Box<dyn Scheduler>>) + Send,
+{
+    let narration = Narration::default();
+    let logs = Processor::builder()
+        .narrate(narration.clone())
+        .run(scheduler(config), f);
+    let narration = String::from_utf8_lossy(&narration.0.lock().unwrap()).into_owned();
+    Simulation { logs, narration }
+}
 
-fn write_logs(folder: &str, name: &str, logs: &str) {
-    let (timeslice, remaining, cpu_slices) = arguments();
+fn write_logs(config: &Config, folder: &str, name: &str, logs: &str) {
     fs::create_dir_all(format!("../outputs/{SCHEDULER}/{folder}")).unwrap();
-    fs::write(
-        format!(
-            "../outputs/{SCHEDULER}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log"
-        ),
-        logs,
-    )
-    .unwrap();
+    fs::write(config.path(folder, name), format!("{}{logs}", config.header(folder, name))).unwrap();
 }
 
-fn read_logs(folder: &str, name: &str) -> String {
-    let (timeslice, remaining, cpu_slices) = arguments();
-    fs::read_to_string(format!(
-        "../outputs/{SCHEDULER}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log"
-    ))
-    .unwrap()
+// the logs of the file, without the header
+fn read_logs(config: &Config, folder: &str, name: &str) -> String {
+    let logs = fs::read_to_string(config.path(folder, name)).unwrap();
+    header::split(&logs).1.to_string()
 }
 
-fn run(folder: &str, name: &str, logs: &[Log]) {
-    let output = format_logs(&logs);
+fn run(config: &Config, folder: &str, name: &str, simulation: &Simulation) {
+    let logs = &simulation.logs;
+    let output = format_logs(logs);
 
-    if env::var("WRITE_OUTPUT").is_ok() {
-        write_logs(folder, name, &output);
+    if config.write_output {
+        write_logs(config, folder, name, &output);
     } else {
-        let reference = read_logs(folder, name);
+        let reference = read_logs(config, folder, name);
+        let narration = format!(
+            "Timeslice {}\nRemaining {}\nCPU slices: {}\n{}",
+            config.timeslice, config.remaining, config.cpu_slices, simulation.narration
+        );
+
+        if let Ok(expected) = parse_logs(&reference) {
+            let result = diff(&expected, logs);
+            assert!(result.is_empty(), "\n{narration}\n{result}");
+        }
 
-        println!("\nleft = Correct Output\nright = Your Output\n");
         use pretty_assertions::assert_eq;
-        assert_eq!(reference, output);
+        assert_eq!(
+            reference,
+            output,
+            "\n{narration}\nleft = Correct Output\nright = Your Output\n"
+        );
     }
 }
 
@@ -72,40 +160,27 @@ fn arguments() -> (usize, usize, usize) {
 #[cfg(feature = "round-robin")]
 static SCHEDULER: &str = "round-robin";
 #[cfg(feature = "round-robin")]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
-
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    round_robin(NonZeroUsize::new(timeslice).unwrap(), remaining)
+fn scheduler(config: &Config) -> Box<dyn Scheduler> {
+    Box::new(round_robin(NonZeroUsize::new(config.timeslice).unwrap(), config.remaining))
 }
 
 #[cfg(feature = "priority-queue")]
 static SCHEDULER: &str = "priority-queue";
 #[cfg(feature = "priority-queue")]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
-
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-
-    priority_queue(NonZeroUsize::new(timeslice).unwrap(), remaining)
+fn scheduler(config: &Config) -> Box<dyn Scheduler> {
+    Box::new(priority_queue(NonZeroUsize::new(config.timeslice).unwrap(), config.remaining))
 }
 
 #[cfg(feature = "cfs")]
 static SCHEDULER: &str = "cfs";
 #[cfg(feature = "cfs")]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
-
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    cfs(NonZeroUsize::new(cpu_slices).unwrap(), remaining)
+fn scheduler(config: &Config) -> Box<dyn Scheduler> {
+    Box::new(cfs(NonZeroUsize::new(config.cpu_slices).unwrap(), config.remaining))
 }
 
 #[cfg(not(any(feature = "round-robin", feature = "priority-queue", feature = "cfs")))]
 static SCHEDULER: &str = "no-scheduler";
 #[cfg(not(any(feature = "round-robin", feature = "priority-queue", feature = "cfs")))]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
-
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    round_robin(NonZeroUsize::new(timeslice).unwrap(), remaining)
+fn scheduler(config: &Config) -> Box<dyn Scheduler> {
+    Box::new(round_robin(NonZeroUsize::new(config.timeslice).unwrap(), config.remaining))
 }
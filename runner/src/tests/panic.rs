@@ -1,66 +1,47 @@
 use function_name::named;
-use processor::Processor;
 
-use super::{run, scheduler};
+use processor::test_workloads;
+
+use super::{run, simulate, Config};
 
 #[test]
 #[named]
 pub fn exec() {
-    let logs = Processor::run(scheduler(), |process| {
-        process.fork(
-            |process| {
-                for _ in 0..5 {
-                    process.exec();
-                }
-            },
-            0,
-        );
-        process.exec();
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::panic::exec);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
 
 #[test]
 #[named]
 pub fn sleep() {
-    let logs = Processor::run(scheduler(), |process| {
-        process.fork(
-            |process| {
-                process.sleep(5);
-            },
-            0,
-        );
-        process.exec();
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::panic::sleep);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
 
 #[test]
 #[named]
 pub fn wait() {
-    let logs = Processor::run(scheduler(), |process| {
-        process.fork(
-            |process| {
-                process.wait(1);
-            },
-            0,
-        );
-        process.exec();
-    });
+    let config = Config::from_env();
+    let simulation = simulate(&config, test_workloads::panic::wait);
 
     run(
+        &config,
         module_path!().split("::").last().unwrap(),
         function_name!(),
-        &logs,
+        &simulation,
     );
 }
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::io;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use scheduler::conformance::{self, Action, Scenario};
+use scheduler::{ProcessState, Scheduler, SchedulingDecision};
+
+use processor::{Process, Processor};
+
+use super::{scheduler, Config};
+
+/// An action that does not create processes.
+fn leaf() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (1..6usize).prop_map(Action::Exec),
+        (1..10usize).prop_map(Action::Sleep),
+        (1..3usize).prop_map(Action::Wait),
+        (1..3usize).prop_map(Action::Signal),
+    ]
+}
+
+/// An action of a generated program, with at most two levels of forks.
+fn action() -> impl Strategy<Value = Action> {
+    leaf().prop_recursive(2, 16, 4, |action| {
+        (0..=5i8, vec(action, 0..4)).prop_map(|(priority, program)| Action::Fork(priority, program))
+    })
+}
+
+/// The program of the process with PID 1.
+fn program() -> impl Strategy<Value = Vec<Action>> {
+    vec(action(), 1..6)
+}
+
+fn perform(program: &[Action], process: &Process<Box<dyn Scheduler>>) {
+    for action in program {
+        match action {
+            Action::Exec(units) => {
+                process.exec_n(*units);
+            }
+            Action::Fork(priority, program) => {
+                let program = program.clone();
+                process.fork(move |process| perform(&program, process), *priority);
+            }
+            Action::Sleep(units) => process.sleep(*units),
+            Action::Wait(event) => process.wait(*event),
+            Action::Signal(event) => process.signal(*event),
+        }
+    }
+}
+
+proptest! {
+    /// The scheduler passes the conformance checks for any workload: no
+    /// process is lost, the timings never decrease, the simulation ends
+    /// and deadlocks are only reported when every process waits.
+    #[test]
+    fn conforms(program in program()) {
+        let config = Config::from_env();
+        let scenario = Scenario { name: "generated", program };
+        if let Err(failure) = conformance::run(scheduler(&config), &scenario) {
+            return Err(TestCaseError::fail(format!("{failure}\n{:?}", scenario.program)));
+        }
+    }
+}
+
+proptest! {
+    // every case runs a thread for every process
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// The processor ends the simulation of any workload, the logs
+    /// agree with the decisions of the scheduler and the processor finds
+    /// no invalid decision or timings.
+    #[test]
+    fn simulation_ends(program in program()) {
+        let config = Config::from_env();
+        let logs = Processor::builder()
+            .narrate(io::sink())
+            .validate(true)
+            .check_timings(true)
+            .run(scheduler(&config), |process| perform(&program, process));
+
+        for (iteration, log) in logs.iter().enumerate() {
+            if let Some(violation) = log.violation {
+                prop_assert!(false, "iteration {}: {violation}", iteration + 1);
+            }
+        }
+
+        let last = logs.last().unwrap();
+        prop_assert!(
+            matches!(last.decision, SchedulingDecision::Done | SchedulingDecision::Deadlock | SchedulingDecision::Panic),
+            "the simulation ended with {}", last.decision
+        );
+        if last.decision == SchedulingDecision::Deadlock {
+            prop_assert!(
+                last.processes.values().all(|info| matches!(info.state, ProcessState::Waiting { event: Some(_) })),
+                "deadlock while a process does not wait for an event"
+            );
+        }
+
+        let mut timings = HashMap::new();
+        for (iteration, log) in logs.iter().enumerate() {
+            for (pid, info) in &log.processes {
+                if let Some(previous) = timings.insert(*pid, info.timings) {
+                    prop_assert!(
+                        info.timings.total >= previous.total
+                            && info.timings.syscall >= previous.syscall
+                            && info.timings.execution >= previous.execution,
+                        "the timings of process {pid} decrease at iteration {}", iteration + 1
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use processor::scenario::Scenario;
+use processor::strace;
+
+/// A capture of `strace -f -ttt -T -o` with interrupted calls, exits and
+/// signals becomes the scenario of its processes.
+#[test]
+fn fork_wait() {
+    let capture = include_str!("../../captures/fork_wait.strace");
+    let imported = strace::import(capture, Duration::from_millis(1)).unwrap();
+
+    let child = Scenario::builder().exec(1).sleep(5).exec(2);
+    let expected = Scenario::builder().exec(1).fork(child, 0).exec(2).sleep(6).exec(2).build();
+    assert_eq!(imported, expected);
+}
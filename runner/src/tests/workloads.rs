@@ -0,0 +1,46 @@
+use std::io;
+
+use processor::workloads;
+use processor::{process_tree, Processor};
+use scheduler::{PidPolicy, Policy, SchedulerConfig, SchedulingDecision};
+
+use super::{scheduler, Config, SCHEDULER};
+
+/// Every standard workload ends without a deadlock or a panic.
+#[test]
+fn standard_workloads_end() {
+    let config = Config::from_env();
+    for workload in workloads::standard().iter().copied() {
+        let logs = Processor::builder()
+            .narrate(io::sink())
+            .run(scheduler(&config), move |process| workload.run(process));
+        let decision = logs.last().unwrap().decision;
+        assert_eq!(decision, SchedulingDecision::Done, "{workload} ended with {decision}");
+    }
+}
+
+/// A scheduler that recycles PIDs runs with the default processor,
+/// which checks the PIDs with the policy of the scheduler.
+#[test]
+fn recycled_pids() {
+    let config = Config::from_env();
+    let policy = Policy::from_name(SCHEDULER).unwrap_or(Policy::RoundRobin);
+    let scheduler = SchedulerConfig::new(policy)
+        .min_remaining(config.remaining)
+        .pid_policy(PidPolicy::Recycle);
+    let scheduler = match policy {
+        Policy::Cfs => scheduler.cpu_time(config.cpu_slices),
+        _ => scheduler.timeslice(config.timeslice),
+    };
+    let logs = Processor::run(
+        scheduler.build().unwrap(),
+        process_tree! {
+            repeat 3 {
+                fork { exec 1 };
+                sleep 5
+            }
+        },
+    );
+
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+}
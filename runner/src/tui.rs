@@ -0,0 +1,246 @@
+//! The terminal interface of `runner tui`, which steps through the
+//! iterations of a simulation.
+//!
+//! Every iteration shows the decision of the scheduler, the processes
+//! that are ready or waiting, the counters of every process and the
+//! time line of the simulation up to the iteration.
+
+use std::io;
+use std::time::Duration;
+
+use processor::timeline::{self, Activity};
+use processor::Log;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use scheduler::{Pid, ProcessState};
+
+/// The colors of the processes in the time line.
+const COLORS: [Color; 6] = [Color::Cyan, Color::Yellow, Color::Green, Color::Magenta, Color::Blue, Color::Red];
+
+/// The width of the PID column of the time line.
+const LABEL_WIDTH: usize = 6;
+
+/// Shows the logs of a simulation until the user quits.
+///
+/// * `title` - describes the simulation.
+/// * `logs` - the logs of the simulation.
+/// * `delay` - the time between two iterations when playing.
+pub(crate) fn show(title: String, logs: &[Log], delay: Duration) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = View::new(title, logs, delay).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+struct View<'a> {
+    title: String,
+    logs: &'a [Log],
+    delay: Duration,
+    /// The iteration that is shown.
+    iteration: usize,
+    playing: bool,
+    /// The virtual time interval of every iteration.
+    times: Vec<(usize, usize)>,
+    /// The process that runs during every time unit, [`None`] when the
+    /// processor sleeps.
+    running: Vec<Option<Pid>>,
+}
+
+impl<'a> View<'a> {
+    fn new(title: String, logs: &'a [Log], delay: Duration) -> View<'a> {
+        let mut running = Vec::new();
+        for segment in timeline::segments(logs) {
+            let pid = match segment.activity {
+                Activity::Run { pid, .. } => Some(pid),
+                Activity::Sleep => None,
+            };
+            running.extend((segment.start..segment.end).map(|_| pid));
+        }
+        View {
+            title,
+            logs,
+            delay,
+            iteration: 0,
+            playing: false,
+            times: timeline::iteration_times(logs),
+            running,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            // while playing, the next iteration is shown if no key is pressed
+            if self.playing && !event::poll(self.delay)? {
+                self.step(1);
+                self.playing &= self.iteration + 1 < self.logs.len();
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Char('l') => self.step(1),
+                KeyCode::Left | KeyCode::Char('h') => self.step(-1),
+                KeyCode::PageDown => self.step(10),
+                KeyCode::PageUp => self.step(-10),
+                KeyCode::Home => self.iteration = 0,
+                KeyCode::End => self.iteration = self.logs.len().saturating_sub(1),
+                KeyCode::Char(' ') => self.playing = !self.playing,
+                _ => {}
+            }
+        }
+    }
+
+    fn step(&mut self, iterations: isize) {
+        let last = self.logs.len().saturating_sub(1);
+        self.iteration = self.iteration.saturating_add_signed(iterations).min(last);
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let Some(log) = self.logs.get(self.iteration) else {
+            frame.render_widget(Paragraph::new("The simulation has no iterations, press q to quit"), frame.area());
+            return;
+        };
+        let pids = self.pids();
+        // a line for every process and for the idle processor
+        let timeline_height = (pids.len() as u16 + 3).min(frame.area().height / 3);
+        let [header, middle, bottom, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(timeline_height),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(middle);
+        let [decision, queues] = Layout::vertical([Constraint::Length(5), Constraint::Min(3)]).areas(left);
+
+        let (start, end) = self.times[self.iteration];
+        let status = format!(
+            "iteration {}/{}, time {start}..{end}{}",
+            self.iteration + 1,
+            self.logs.len(),
+            if self.playing { ", playing" } else { "" }
+        );
+        frame.render_widget(Paragraph::new(status).block(Block::bordered().title(self.title.as_str())), header);
+
+        let mut lines = vec![Line::from(log.decision.to_string()).style(Style::new().add_modifier(Modifier::BOLD))];
+        if let Some((reason, result)) = log.stop_reason {
+            lines.push(Line::from(format!("{reason} -> {result:?}")));
+        }
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Decision")), decision);
+
+        frame.render_widget(Paragraph::new(queue_lines(log)).block(Block::bordered().title("Queues")), queues);
+        self.draw_processes(frame, log, right);
+        self.draw_timeline(frame, &pids, bottom);
+
+        let keys = "←/→ step  PgUp/PgDn 10 steps  Home/End first/last  space play/pause  q quit";
+        frame.render_widget(Paragraph::new(keys).style(Style::new().fg(Color::DarkGray)), help);
+    }
+
+    fn draw_processes(&self, frame: &mut Frame, log: &Log, area: Rect) {
+        let mut processes = log.processes.values().collect::<Vec<_>>();
+        processes.sort_by_key(|info| info.pid);
+        let rows = processes.into_iter().map(|info| {
+            let row = Row::new([
+                info.pid.to_string(),
+                info.state.to_string(),
+                info.priority.to_string(),
+                info.timings.total.to_string(),
+                info.timings.syscall.to_string(),
+                info.timings.execution.to_string(),
+                info.extra.clone(),
+            ]);
+            match info.state {
+                ProcessState::Running => row.style(Style::new().fg(color(info.pid)).add_modifier(Modifier::BOLD)),
+                _ => row,
+            }
+        });
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Length(7),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Min(5),
+        ];
+        let header = Row::new(["PID", "STATE", "PRI", "TOTAL", "SYSCALL", "EXECUTE", "EXTRA"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let table = Table::new(rows, widths).header(header).block(Block::bordered().title("Processes"));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_timeline(&self, frame: &mut Frame, pids: &[Pid], area: Rect) {
+        let block = Block::bordered().title("Timeline");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // the last time units before the end of the iteration
+        let width = (inner.width as usize).saturating_sub(LABEL_WIDTH);
+        let end = self.times[self.iteration].1;
+        let start = end.saturating_sub(width);
+        let strip = |selected: Option<Pid>, style: Style| {
+            let units = self.running[start..end]
+                .iter()
+                .map(|pid| if *pid == selected { '█' } else { ' ' })
+                .collect::<String>();
+            Span::styled(units, style)
+        };
+
+        let mut lines = pids
+            .iter()
+            .map(|pid| Line::from(vec![Span::raw(format!("{:<LABEL_WIDTH$}", pid.to_string())), strip(Some(*pid), Style::new().fg(color(*pid)))]))
+            .collect::<Vec<_>>();
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:<LABEL_WIDTH$}", "idle")),
+            strip(None, Style::new().fg(Color::DarkGray)),
+        ]));
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    /// The processes that have existed until the iteration, sorted.
+    fn pids(&self) -> Vec<Pid> {
+        let mut pids = self.logs[..=self.iteration]
+            .iter()
+            .flat_map(|log| log.processes.keys().copied())
+            .collect::<Vec<_>>();
+        pids.sort();
+        pids.dedup();
+        pids
+    }
+}
+
+/// The processes that are ready, sleeping and waiting for events.
+fn queue_lines(log: &Log) -> Vec<Line<'static>> {
+    let mut processes = log.processes.values().collect::<Vec<_>>();
+    processes.sort_by_key(|info| info.pid);
+    let list = |selected: fn(&ProcessState) -> bool| {
+        let pids = processes
+            .iter()
+            .filter(|info| selected(&info.state))
+            .map(|info| match info.state {
+                ProcessState::Waiting { event: Some(event) } => format!("{} ({event})", info.pid),
+                _ => info.pid.to_string(),
+            })
+            .collect::<Vec<_>>();
+        pids.join(" ")
+    };
+    vec![
+        Line::from(format!("Ready:    {}", list(|state| *state == ProcessState::Ready))),
+        Line::from(format!("Sleeping: {}", list(|state| *state == ProcessState::Waiting { event: None }))),
+        Line::from(format!("Waiting:  {}", list(|state| matches!(state, ProcessState::Waiting { event: Some(_) })))),
+    ]
+}
+
+fn color(pid: Pid) -> Color {
+    COLORS[pid.get() % COLORS.len()]
+}
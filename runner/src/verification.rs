@@ -0,0 +1,170 @@
+//! The results of `runner verify`, written for people or for the tools
+//! of continuous integration systems.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use processor::{export, report, LogDiff};
+
+/// The result of the comparison of a reference log with its simulation.
+pub(crate) struct Case {
+    /// The path of the reference log, relative to the outputs folder.
+    pub(crate) path: PathBuf,
+    /// The folder of the configuration, like `round-robin`.
+    pub(crate) folder: String,
+    pub(crate) outcome: Outcome,
+}
+
+pub(crate) enum Outcome {
+    Matching,
+    Missing,
+    /// The simulation differs from the reference log.
+    Mismatching {
+        /// Where the logs diverge first, on a single line.
+        divergence: String,
+        /// How the logs differ, on several lines.
+        details: String,
+    },
+    /// No test reads the log anymore.
+    Stale,
+}
+
+impl Outcome {
+    /// The outcome of logs that differ.
+    ///
+    /// * `diff` - the differences, or the reason why the logs cannot be
+    ///   compared.
+    pub(crate) fn mismatching(diff: Result<LogDiff, String>) -> Outcome {
+        match diff {
+            Ok(diff) => {
+                let differences = diff.differences.iter().map(|difference| difference.to_string()).collect::<Vec<_>>();
+                Outcome::Mismatching {
+                    // the logs differ, so there is an iteration
+                    divergence: format!("iteration {}: {}", diff.iteration.map_or(0, |iteration| iteration + 1), differences.join("; ")),
+                    details: diff.to_string(),
+                }
+            }
+            Err(error) => Outcome::Mismatching {
+                divergence: error.clone(),
+                details: error,
+            },
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Matching => "matching",
+            Outcome::Missing => "missing",
+            Outcome::Mismatching { .. } => "mismatching",
+            Outcome::Stale => "stale",
+        }
+    }
+
+    // why the case has failed, if it has
+    fn message(&self) -> Option<String> {
+        match self {
+            Outcome::Matching => None,
+            Outcome::Missing => Some("the reference log is missing".to_string()),
+            Outcome::Mismatching { divergence, .. } => Some(divergence.clone()),
+            Outcome::Stale => Some("no test reads the reference log".to_string()),
+        }
+    }
+}
+
+// the number of cases with every status
+fn counts(cases: &[Case]) -> [usize; 4] {
+    let count = |status: &str| cases.iter().filter(|case| case.outcome.status() == status).count();
+    [count("matching"), count("mismatching"), count("missing"), count("stale")]
+}
+
+/// A line for every case that has failed, with the differences if
+/// `diff` is set, and the number of cases of every status.
+pub(crate) fn text(cases: &[Case], diff: bool) -> String {
+    let mut text = String::new();
+    for case in cases {
+        let status = match case.outcome {
+            Outcome::Matching => continue,
+            Outcome::Mismatching { .. } => "mismatch",
+            ref outcome => outcome.status(),
+        };
+        let _ = writeln!(text, "{status} {}", case.path.display());
+        if let (true, Outcome::Mismatching { details, .. }) = (diff, &case.outcome) {
+            let _ = writeln!(text, "{}", details.trim_end_matches('\n'));
+        }
+    }
+    let [matching, mismatching, missing, stale] = counts(cases);
+    let _ = writeln!(text, "{matching} matching, {mismatching} mismatching, {missing} missing, {stale} stale");
+    text
+}
+
+/// A JUnit XML report, with a test suite for every configuration folder
+/// and a test case for every reference log.
+pub(crate) fn junit(cases: &[Case]) -> String {
+    let mut folders = Vec::new();
+    for case in cases {
+        if !folders.contains(&case.folder.as_str()) {
+            folders.push(case.folder.as_str());
+        }
+    }
+    let failures = |cases: &[&Case]| cases.iter().filter(|case| !matches!(case.outcome, Outcome::Matching)).count();
+
+    let all = cases.iter().collect::<Vec<_>>();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(xml, "<testsuites name=\"verify\" tests=\"{}\" failures=\"{}\">", all.len(), failures(&all));
+    for folder in folders {
+        let cases = cases.iter().filter(|case| case.folder == folder).collect::<Vec<_>>();
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            report::escape(folder),
+            cases.len(),
+            failures(&cases)
+        );
+        for case in cases {
+            let name = report::escape(&case.path.display().to_string());
+            let classname = report::escape(folder);
+            let Some(message) = case.outcome.message() else {
+                let _ = writeln!(xml, "    <testcase name=\"{name}\" classname=\"{classname}\"/>");
+                continue;
+            };
+            let details = match &case.outcome {
+                Outcome::Mismatching { details, .. } => details.as_str(),
+                _ => "",
+            };
+            let _ = writeln!(xml, "    <testcase name=\"{name}\" classname=\"{classname}\">");
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                report::escape(&message),
+                case.outcome.status(),
+                report::escape(details)
+            );
+            let _ = writeln!(xml, "    </testcase>");
+        }
+        let _ = writeln!(xml, "  </testsuite>");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// A JSON object with the number of cases of every status and an
+/// object for every case.
+pub(crate) fn json(cases: &[Case]) -> String {
+    let [matching, mismatching, missing, stale] = counts(cases);
+    let objects = cases.iter().map(|case| {
+        let divergence = match &case.outcome {
+            Outcome::Mismatching { divergence, .. } => export::json_string(divergence),
+            _ => "null".to_string(),
+        };
+        format!(
+            "{{\"path\":{},\"configuration\":{},\"status\":\"{}\",\"divergence\":{divergence}}}",
+            export::json_string(&case.path.display().to_string()),
+            export::json_string(&case.folder),
+            case.outcome.status()
+        )
+    });
+    format!(
+        "{{\"matching\":{matching},\"mismatching\":{mismatching},\"missing\":{missing},\"stale\":{stale},\"cases\":[{}]}}\n",
+        objects.collect::<Vec<_>>().join(",")
+    )
+}
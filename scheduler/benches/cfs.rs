@@ -0,0 +1,46 @@
+use std::hint::black_box;
+use std::num::NonZeroUsize;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use scheduler::{Priority, Scheduler, SchedulingDecision, StopReason, Syscall};
+
+const PROCESSES: usize = 10_000;
+
+/// Returns a CFS scheduler with `PROCESSES` ready processes, forked
+/// by the first one.
+fn workload() -> impl Scheduler {
+    let mut scheduler = scheduler::cfs(NonZeroUsize::new(PROCESSES).unwrap(), 1);
+    scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+    for _ in 1..PROCESSES {
+        scheduler.next();
+        scheduler.stop(StopReason::Syscall {
+            syscall: Syscall::Fork(Priority::new(0)),
+            remaining: 0,
+        });
+    }
+    scheduler
+}
+
+/// Runs every process until its timeslice expires.
+fn expire(scheduler: &mut impl Scheduler) {
+    for _ in 0..PROCESSES {
+        match scheduler.next() {
+            SchedulingDecision::Run { .. } => black_box(scheduler.stop(StopReason::Expired)),
+            decision => panic!("unexpected decision {decision}"),
+        };
+    }
+}
+
+fn cfs(c: &mut Criterion) {
+    c.bench_function("cfs fork 10k", |b| b.iter(workload));
+    c.bench_function("cfs expire 10k", |b| {
+        b.iter_batched_ref(workload, expire, BatchSize::LargeInput)
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = cfs
+}
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+use std::num::NonZeroUsize;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use scheduler::{Event, Priority, Scheduler, SchedulingDecision, StopReason, Syscall};
+
+const EVENTS: [usize; 3] = [10, 1_000, 100_000];
+
+/// The number of processes that the workload keeps alive.
+const PROCESSES: usize = 256;
+
+/// Sends `events` system calls and expirations to a new scheduler.
+///
+/// The processes fork, sleep, wait for and signal 4 events and exit.
+/// PID 1 never waits or exits, so the workload never deadlocks.
+fn drive(mut scheduler: impl Scheduler, events: usize) {
+    scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+    let mut processes = 1;
+    let mut event = 0;
+    while event < events {
+        let pid = match scheduler.next() {
+            SchedulingDecision::Run { pid, .. } => pid,
+            SchedulingDecision::Sleep(amount) => {
+                scheduler.on_tick(amount);
+                continue;
+            }
+            decision => panic!("unexpected decision {decision}"),
+        };
+        let syscall = match event % 6 {
+            0 if processes < PROCESSES => Some(Syscall::Fork(Priority::new(0))),
+            1 => Some(Syscall::Sleep(2)),
+            2 if pid != 1 => Some(Syscall::Wait(Event::user(event % 4))),
+            3 => Some(Syscall::Signal(Event::user((event / 6) % 4))),
+            4 if pid != 1 && processes > PROCESSES / 2 => Some(Syscall::Exit),
+            _ => None,
+        };
+        match syscall {
+            Some(syscall) => {
+                match syscall {
+                    Syscall::Fork(_) => processes += 1,
+                    Syscall::Exit => processes -= 1,
+                    _ => {}
+                }
+                scheduler.stop(StopReason::Syscall { syscall, remaining: 0 });
+            }
+            None => {
+                scheduler.stop(StopReason::Expired);
+            }
+        }
+        event += 1;
+    }
+}
+
+fn schedulers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stop and next");
+    group.sample_size(10);
+    for events in EVENTS {
+        group.throughput(Throughput::Elements(events as u64));
+        group.bench_with_input(BenchmarkId::new("round robin", events), &events, |b, &events| {
+            b.iter(|| drive(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), events))
+        });
+        group.bench_with_input(BenchmarkId::new("priority queue", events), &events, |b, &events| {
+            b.iter(|| drive(scheduler::priority_queue(NonZeroUsize::new(3).unwrap(), 1), events))
+        });
+        group.bench_with_input(BenchmarkId::new("cfs", events), &events, |b, &events| {
+            b.iter(|| drive(scheduler::cfs(NonZeroUsize::new(10).unwrap(), 1), events))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, schedulers);
+criterion_main!(benches);
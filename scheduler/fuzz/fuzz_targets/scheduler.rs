@@ -0,0 +1,89 @@
+//! Runs the bundled schedulers on programs decoded from the fuzzer input.
+//!
+//! The programs are simulated by [`conformance::run`], which only sends
+//! the system calls that a processor would send and checks the
+//! decisions of the scheduler after every call. Any failure, including
+//! a panic of the scheduler, is reported as a crash.
+//!
+//! ```text
+//! cargo +nightly fuzz run scheduler
+//! ```
+
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use scheduler::conformance::{self, Action, Scenario};
+use scheduler::{PidPolicy, Policy, SchedulerConfig};
+
+/// The longest execution or sleep of an action, so that every
+/// simulation ends before the limit of decisions of the conformance checks.
+const MAX_UNITS: usize = 16;
+
+/// The most actions of all the processes of a simulation.
+const MAX_ACTIONS: usize = 64;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    policy: u8,
+    timeslice: u8,
+    min_remaining: u8,
+    recycle_pids: bool,
+    program: Vec<Instruction>,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Instruction {
+    Exec(u8),
+    Fork(i8, Vec<Instruction>),
+    Sleep(u8),
+    Wait(u8),
+    Signal(u8),
+}
+
+// converts the instructions to actions, until `budget` actions are used
+fn program(instructions: &[Instruction], budget: &mut usize) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for instruction in instructions {
+        if *budget == 0 {
+            break;
+        }
+        *budget -= 1;
+        let units = |units: u8| usize::from(units) % MAX_UNITS + 1;
+        actions.push(match instruction {
+            Instruction::Exec(amount) => Action::Exec(units(*amount)),
+            Instruction::Fork(priority, instructions) => Action::Fork(*priority, program(instructions, budget)),
+            Instruction::Sleep(amount) => Action::Sleep(units(*amount)),
+            // a few events, so that processes wait for the same ones
+            Instruction::Wait(event) => Action::Wait(usize::from(*event % 4)),
+            Instruction::Signal(event) => Action::Signal(usize::from(*event % 4)),
+        });
+    }
+    actions
+}
+
+fuzz_target!(|input: Input| {
+    let policy = Policy::ALL[usize::from(input.policy) % Policy::ALL.len()];
+    let timeslice = usize::from(input.timeslice % 20) + 1;
+    let config = match policy {
+        Policy::Cfs => SchedulerConfig::new(policy).cpu_time(timeslice),
+        _ => SchedulerConfig::new(policy).timeslice(timeslice),
+    };
+    let config = config
+        .min_remaining(usize::from(input.min_remaining) % (timeslice + 1))
+        .pid_policy(match input.recycle_pids {
+            true => PidPolicy::Recycle,
+            false => PidPolicy::Increasing,
+        });
+    let mut budget = MAX_ACTIONS;
+    let scenario = Scenario {
+        name: "fuzz",
+        program: program(&input.program, &mut budget),
+    };
+
+    // the parameters are always valid
+    let scheduler = config.build().unwrap();
+    if let Err(failure) = conformance::run(scheduler, &scenario) {
+        panic!("{failure}\n{config:?}\n{:?}", scenario.program);
+    }
+});
@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+
+use crate::snapshot::{Decoder, Encoder, SnapshotError};
+use crate::Pid;
+
+/// The way a [`PidAllocator`] chooses PIDs for new processes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PidPolicy {
+    /// Every process receives a new PID, greater than all the PIDs
+    /// allocated before.
+    #[default]
+    Increasing,
+
+    /// A process receives the lowest PID released by an exited process,
+    /// or a new PID if no PID was released.
+    Recycle,
+}
+
+/// Allocates the PIDs of the processes created by a scheduler.
+///
+/// The first allocated PID is always 1.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Pid, PidAllocator, PidPolicy};
+///
+/// let mut pids = PidAllocator::new(PidPolicy::Recycle);
+/// assert_eq!(pids.allocate(), Pid::new(1));
+/// assert_eq!(pids.allocate(), Pid::new(2));
+/// assert_eq!(pids.allocate(), Pid::new(3));
+///
+/// pids.release(Pid::new(2));
+/// assert_eq!(pids.allocate(), Pid::new(2));
+/// assert_eq!(pids.allocate(), Pid::new(4));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PidAllocator {
+    policy: PidPolicy,
+    next: usize,
+    released: BTreeSet<usize>,
+}
+
+impl PidAllocator {
+    /// Creates an allocator that has not allocated any PID.
+    ///
+    /// * `policy` - the way PIDs are chosen.
+    pub fn new(policy: PidPolicy) -> PidAllocator {
+        PidAllocator {
+            policy,
+            next: 1,
+            released: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the policy of the allocator.
+    pub fn policy(&self) -> PidPolicy {
+        self.policy
+    }
+
+    /// Returns `true` if no PID has been allocated yet.
+    pub fn is_fresh(&self) -> bool {
+        self.next == 1
+    }
+
+    /// Returns the PID for a new process.
+    pub fn allocate(&mut self) -> Pid {
+        if let Some(pid) = self.released.pop_first() {
+            return Pid::new(pid);
+        }
+        let pid = self.next;
+        self.next += 1;
+        Pid::new(pid)
+    }
+
+    /// Marks the PID of an exited process as available.
+    ///
+    /// The PID is used again only by the [`PidPolicy::Recycle`] policy.
+    ///
+    /// * `pid` - the PID of the exited process.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the PID was never allocated, or if the policy is
+    /// [`PidPolicy::Recycle`] and the PID was already released.
+    pub fn release(&mut self, pid: Pid) {
+        if pid.get() >= self.next {
+            panic!("PID {pid} was never allocated");
+        }
+        if self.policy == PidPolicy::Recycle && !self.released.insert(pid.get()) {
+            panic!("PID {pid} was already released");
+        }
+    }
+
+    /// Marks a PID as allocated without returning it from [`PidAllocator::allocate`],
+    /// for processes that were created outside the allocator.
+    ///
+    /// Returns `false` if the PID is already allocated.
+    ///
+    /// * `pid` - the PID of the process.
+    pub fn reserve(&mut self, pid: Pid) -> bool {
+        if pid.get() < self.next {
+            return self.released.remove(&pid.get());
+        }
+        if self.policy == PidPolicy::Recycle {
+            self.released.extend(self.next..pid.get());
+        }
+        self.next = pid.get() + 1;
+        true
+    }
+
+    pub(crate) fn save(&self, encoder: &mut Encoder) {
+        encoder.bool(self.policy == PidPolicy::Recycle);
+        encoder.usize(self.next);
+        encoder.usize(self.released.len());
+        for pid in &self.released {
+            encoder.usize(*pid);
+        }
+    }
+
+    pub(crate) fn restore(decoder: &mut Decoder) -> Result<PidAllocator, SnapshotError> {
+        let policy = match decoder.bool()? {
+            true => PidPolicy::Recycle,
+            false => PidPolicy::Increasing,
+        };
+        let next = decoder.non_zero()?.get();
+        let released = (0..decoder.usize()?)
+            .map(|_| decoder.usize())
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        if released.iter().any(|pid| *pid == 0 || *pid >= next) {
+            return Err(SnapshotError::new("invalid released PID"));
+        }
+        Ok(PidAllocator {
+            policy,
+            next,
+            released,
+        })
+    }
+}
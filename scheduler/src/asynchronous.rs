@@ -0,0 +1,131 @@
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use crate::{Process, Scheduler, SchedulingDecision, StopReason, SyscallResult};
+
+/// A scheduler that takes its decisions asynchronously.
+///
+/// This is useful for schedulers that consult an external service,
+/// like a policy server. The scheduler is used by the processor
+/// through a [`BlockingScheduler`].
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{
+///     AsyncScheduler, BlockingScheduler, Priority, Process, Scheduler, SchedulingDecision,
+///     StopReason, Syscall, SyscallResult,
+/// };
+/// use std::future::Future;
+/// use std::num::NonZeroUsize;
+///
+/// struct Remote<S>(S);
+///
+/// impl<S: Scheduler> AsyncScheduler for Remote<S> {
+///     fn next(&mut self) -> impl Future<Output = SchedulingDecision> + Send {
+///         async { self.0.next() }
+///     }
+///
+///     fn stop(&mut self, reason: StopReason) -> impl Future<Output = SyscallResult> + Send {
+///         async move { self.0.stop(reason) }
+///     }
+///
+///     fn list(&mut self) -> Vec<&dyn Process> {
+///         self.0.list()
+///     }
+///
+///     fn on_tick(&mut self, elapsed: NonZeroUsize) {
+///         self.0.on_tick(elapsed)
+///     }
+/// }
+///
+/// let remote = Remote(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1));
+/// let mut scheduler = BlockingScheduler::new(remote);
+/// scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+/// assert!(matches!(scheduler.next(), SchedulingDecision::Run { pid, .. } if pid == 1));
+/// ```
+pub trait AsyncScheduler: Send {
+    /// Returns the action that the OS has to perform next.
+    fn next(&mut self) -> impl Future<Output = SchedulingDecision> + Send;
+
+    /// The scheduler is informed about the stopping of a process
+    /// and the reason.
+    fn stop(&mut self, reason: StopReason) -> impl Future<Output = SyscallResult> + Send;
+
+    /// Returns the list of processes.
+    fn list(&mut self) -> Vec<&dyn Process>;
+    /// Called after the OS has slept, like [`Scheduler::on_tick`].
+    ///
+    /// The default implementation does nothing.
+    fn on_tick(&mut self, _elapsed: NonZeroUsize) {}
+}
+
+/// Adapts an [`AsyncScheduler`] to the [`Scheduler`] trait, so that
+/// it can be used by the processor.
+///
+/// The futures are polled on the thread that calls the scheduler,
+/// which is parked while they are pending. Futures that need a
+/// runtime, like the ones of an async HTTP client, have to be
+/// driven by that runtime, for example by awaiting the result
+/// of a task spawned on it.
+pub struct BlockingScheduler<A: AsyncScheduler> {
+    scheduler: A,
+}
+
+impl<A: AsyncScheduler> BlockingScheduler<A> {
+    /// Returns the adapter for `scheduler`.
+    ///
+    /// * `scheduler` - the asynchronous scheduler.
+    pub fn new(scheduler: A) -> BlockingScheduler<A> {
+        BlockingScheduler { scheduler }
+    }
+
+    /// Returns the asynchronous scheduler.
+    pub fn into_inner(self) -> A {
+        self.scheduler
+    }
+}
+
+impl<A: AsyncScheduler> Scheduler for BlockingScheduler<A> {
+    fn next(&mut self) -> SchedulingDecision {
+        block_on(self.scheduler.next())
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        block_on(self.scheduler.stop(reason))
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        self.scheduler.list()
+    }
+
+    fn on_tick(&mut self, elapsed: NonZeroUsize) {
+        self.scheduler.on_tick(elapsed)
+    }
+}
+
+/// Wakes up a thread parked by [`block_on`].
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` on the current thread until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
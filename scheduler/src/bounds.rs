@@ -0,0 +1,116 @@
+use crate::snapshot::{Decoder, Encoder, SnapshotError};
+use crate::Priority;
+
+/// What a scheduler does with a priority that is outside of its
+/// [`PriorityBounds`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OutOfBounds {
+    /// The priority is replaced by the closest bound.
+    #[default]
+    Clamp,
+
+    /// The process is not created and the system call returns
+    /// [`SyscallResult::InvalidPriority`](crate::SyscallResult::InvalidPriority).
+    Reject,
+}
+
+/// The range of priorities accepted by a scheduler.
+///
+/// The bounds apply to the priority requested for a new process. Some
+/// scheduling algorithms change the priority of a process afterwards,
+/// using their own rules.
+///
+/// The default bounds accept every priority.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{OutOfBounds, Priority, PriorityBounds};
+///
+/// let bounds = PriorityBounds::new(Priority::new(0), Priority::new(5), OutOfBounds::Clamp).unwrap();
+/// assert_eq!(bounds.apply(Priority::new(9)), Some(Priority::new(5)));
+///
+/// let bounds = PriorityBounds::new(Priority::new(0), Priority::new(5), OutOfBounds::Reject).unwrap();
+/// assert_eq!(bounds.apply(Priority::new(9)), None);
+/// assert_eq!(bounds.apply(Priority::new(3)), Some(Priority::new(3)));
+///
+/// assert!(PriorityBounds::new(Priority::new(5), Priority::new(0), OutOfBounds::Clamp).is_none());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PriorityBounds {
+    min: Priority,
+    max: Priority,
+    policy: OutOfBounds,
+}
+
+impl PriorityBounds {
+    /// Returns the bounds, or [`None`] if `min` is greater than `max`.
+    ///
+    /// * `min` - the lowest accepted priority.
+    /// * `max` - the highest accepted priority.
+    /// * `policy` - what happens to priorities outside of the bounds.
+    pub fn new(min: Priority, max: Priority, policy: OutOfBounds) -> Option<PriorityBounds> {
+        if min > max {
+            return None;
+        }
+        Some(PriorityBounds { min, max, policy })
+    }
+
+    /// Returns the lowest accepted priority.
+    pub fn min(&self) -> Priority {
+        self.min
+    }
+
+    /// Returns the highest accepted priority.
+    pub fn max(&self) -> Priority {
+        self.max
+    }
+
+    /// Returns what happens to priorities outside of the bounds.
+    pub fn policy(&self) -> OutOfBounds {
+        self.policy
+    }
+
+    /// Returns `true` if the priority is within the bounds.
+    pub fn contains(&self, priority: Priority) -> bool {
+        self.min <= priority && priority <= self.max
+    }
+
+    /// Returns the priority that a process receives when it asks for
+    /// `priority`, or [`None`] if the priority is rejected.
+    ///
+    /// * `priority` - the requested priority.
+    pub fn apply(&self, priority: Priority) -> Option<Priority> {
+        match self.policy {
+            _ if self.contains(priority) => Some(priority),
+            OutOfBounds::Clamp => Some(priority.clamp(self.min, self.max)),
+            OutOfBounds::Reject => None,
+        }
+    }
+
+    pub(crate) fn save(&self, encoder: &mut Encoder) {
+        encoder.i8(self.min.get());
+        encoder.i8(self.max.get());
+        encoder.bool(self.policy == OutOfBounds::Reject);
+    }
+
+    pub(crate) fn restore(decoder: &mut Decoder) -> Result<PriorityBounds, SnapshotError> {
+        let min = Priority::new(decoder.i8()?);
+        let max = Priority::new(decoder.i8()?);
+        let policy = match decoder.bool()? {
+            true => OutOfBounds::Reject,
+            false => OutOfBounds::Clamp,
+        };
+        PriorityBounds::new(min, max, policy).ok_or_else(|| SnapshotError::new("invalid priority bounds"))
+    }
+}
+
+impl Default for PriorityBounds {
+    fn default() -> Self {
+        PriorityBounds {
+            min: Priority::new(i8::MIN),
+            max: Priority::new(i8::MAX),
+            policy: OutOfBounds::Clamp,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/// The scheduling class of a process.
+///
+/// Classes are ordered by precedence: a ready process of a higher
+/// class is always scheduled before the processes of a lower class,
+/// whatever their priority. Within a class, processes are scheduled
+/// by the scheduling policy.
+///
+/// A forked process inherits the class of its parent, the first
+/// process has the [`Class::Normal`] class.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::Class;
+///
+/// assert!(Class::RealTime > Class::Normal);
+/// assert!(Class::Normal > Class::Idle);
+/// assert_eq!(Class::default(), Class::Normal);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Class {
+    /// Processes that only run when no other process is ready.
+    Idle,
+
+    /// The class of most processes.
+    #[default]
+    Normal,
+
+    /// Processes that run before all the other ones.
+    RealTime,
+}
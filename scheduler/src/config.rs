@@ -0,0 +1,265 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+
+use crate::schedulers::{CFS, PriorityQueue, RoundRobin};
+use crate::{EventSemantics, PidPolicy, PriorityBounds, Scheduler};
+
+/// A scheduling policy implemented by this crate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Policy {
+    /// Round robin, see [`round_robin`](crate::round_robin).
+    RoundRobin,
+
+    /// Priority queue, see [`priority_queue`](crate::priority_queue).
+    PriorityQueue,
+
+    /// Simplified completely fair scheduler, see [`cfs`](crate::cfs).
+    Cfs,
+}
+
+impl Policy {
+    /// All the policies implemented by this crate.
+    pub const ALL: [Policy; 3] = [Policy::RoundRobin, Policy::PriorityQueue, Policy::Cfs];
+
+    /// Returns the name of the policy, as accepted by [`by_name`](crate::by_name).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Policy::RoundRobin => "round-robin",
+            Policy::PriorityQueue => "priority-queue",
+            Policy::Cfs => "cfs",
+        }
+    }
+
+    /// Returns the policy with the given name, if there is one.
+    ///
+    /// * `name` - the name of the policy, like `round-robin`,
+    ///   `priority-queue` or `cfs`.
+    pub fn from_name(name: &str) -> Option<Policy> {
+        Policy::ALL.into_iter().find(|policy| policy.name() == name)
+    }
+}
+
+/// The parameters used by [`by_name`](crate::by_name).
+///
+/// Every policy uses only the parameters that it needs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// The time quanta used by the round robin and priority queue policies.
+    pub timeslice: NonZeroUsize,
+
+    /// The minimum remaining timeslice that allows a process
+    /// to be scheduled again after a system call.
+    pub minimum_remaining_timeslice: usize,
+
+    /// The total time units that the cpu has for an iteration,
+    /// used by the cfs policy.
+    pub cpu_time: NonZeroUsize,
+}
+
+/// Builds one of the schedulers implemented by this crate.
+///
+/// The [`Policy::RoundRobin`] and [`Policy::PriorityQueue`] policies
+/// require a `timeslice`, while the [`Policy::Cfs`] policy requires
+/// a `cpu_time`. The `minimum_remaining_timeslice` is 1 if it is not set,
+/// PIDs are not recycled unless a [`PidPolicy`] is set, every priority
+/// is accepted unless [`PriorityBounds`] are set and signals are not
+/// remembered unless [`EventSemantics::Sticky`] is set.
+///
+/// The bundled schedulers never schedule a process for less than 1 time
+/// unit: a `minimum_remaining_timeslice` of 0 behaves like 1, and the cfs
+/// policy gives every process a timeslice of 1 when there are more
+/// processes than `cpu_time` units.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Policy, SchedulerConfig};
+///
+/// let scheduler = SchedulerConfig::new(Policy::Cfs).cpu_time(10).min_remaining(1).build();
+/// assert!(scheduler.is_ok());
+///
+/// let scheduler = SchedulerConfig::new(Policy::RoundRobin).timeslice(0).build();
+/// assert!(scheduler.is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulerConfig {
+    policy: Policy,
+    timeslice: Option<usize>,
+    cpu_time: Option<usize>,
+    minimum_remaining_timeslice: usize,
+    pid_policy: PidPolicy,
+    priority_bounds: PriorityBounds,
+    event_semantics: EventSemantics,
+}
+
+impl SchedulerConfig {
+    /// Starts the configuration of a scheduler.
+    ///
+    /// * `policy` - the scheduling policy.
+    pub fn new(policy: Policy) -> SchedulerConfig {
+        SchedulerConfig {
+            policy,
+            timeslice: None,
+            cpu_time: None,
+            minimum_remaining_timeslice: 1,
+            pid_policy: PidPolicy::Increasing,
+            priority_bounds: PriorityBounds::default(),
+            event_semantics: EventSemantics::Transient,
+        }
+    }
+
+    /// Sets the time quanta that a process can run before it is preempted.
+    ///
+    /// * `timeslice` - the time quanta, used by the round robin
+    ///   and priority queue policies.
+    pub fn timeslice(mut self, timeslice: usize) -> SchedulerConfig {
+        self.timeslice = Some(timeslice);
+        self
+    }
+
+    /// Sets the total time units that the cpu has for an iteration.
+    ///
+    /// * `cpu_time` - the time units used to compute the timeslice
+    ///   of each process by the cfs policy.
+    pub fn cpu_time(mut self, cpu_time: usize) -> SchedulerConfig {
+        self.cpu_time = Some(cpu_time);
+        self
+    }
+
+    /// Sets the minimum remaining timeslice that allows a process
+    /// to be scheduled again after a system call.
+    ///
+    /// * `minimum_remaining_timeslice` - the minimum remaining timeslice.
+    pub fn min_remaining(mut self, minimum_remaining_timeslice: usize) -> SchedulerConfig {
+        self.minimum_remaining_timeslice = minimum_remaining_timeslice;
+        self
+    }
+
+    /// Sets the way PIDs are allocated to new processes.
+    ///
+    /// * `pid_policy` - whether the PIDs of exited processes are reused.
+    pub fn pid_policy(mut self, pid_policy: PidPolicy) -> SchedulerConfig {
+        self.pid_policy = pid_policy;
+        self
+    }
+
+    /// Sets the range of priorities that new processes can have.
+    ///
+    /// * `priority_bounds` - the accepted priorities and what happens
+    ///   to the other ones.
+    pub fn priority_bounds(mut self, priority_bounds: PriorityBounds) -> SchedulerConfig {
+        self.priority_bounds = priority_bounds;
+        self
+    }
+
+    /// Sets what happens to the signals sent while no process waits
+    /// for the event.
+    ///
+    /// * `event_semantics` - whether these signals are lost or remembered.
+    pub fn event_semantics(mut self, event_semantics: EventSemantics) -> SchedulerConfig {
+        self.event_semantics = event_semantics;
+        self
+    }
+
+    /// Returns the policy of the scheduler.
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// Builds the scheduler.
+    ///
+    /// Returns an error if the parameter required by the policy is missing
+    /// or is 0, or if a parameter that the policy does not use is set.
+    pub fn build(&self) -> Result<Box<dyn Scheduler>, ConfigError> {
+        let non_zero = |value: Option<usize>, parameter: &'static str| {
+            let Some(value) = value else {
+                return Err(ConfigError::Missing {
+                    policy: self.policy,
+                    parameter,
+                });
+            };
+            NonZeroUsize::new(value).ok_or(ConfigError::Zero { parameter })
+        };
+        let scheduler: Box<dyn Scheduler> = match self.policy {
+            Policy::RoundRobin | Policy::PriorityQueue if self.cpu_time.is_some() => {
+                return Err(ConfigError::Unused {
+                    policy: self.policy,
+                    parameter: "cpu_time",
+                });
+            }
+            Policy::Cfs if self.timeslice.is_some() => {
+                return Err(ConfigError::Unused {
+                    policy: self.policy,
+                    parameter: "timeslice",
+                });
+            }
+            Policy::RoundRobin => Box::new(RoundRobin::new(
+                non_zero(self.timeslice, "timeslice")?,
+                self.minimum_remaining_timeslice,
+                self.pid_policy,
+                self.priority_bounds,
+                self.event_semantics,
+            )),
+            Policy::PriorityQueue => Box::new(PriorityQueue::new(
+                non_zero(self.timeslice, "timeslice")?,
+                self.minimum_remaining_timeslice,
+                self.pid_policy,
+                self.priority_bounds,
+                self.event_semantics,
+            )),
+            Policy::Cfs => Box::new(CFS::new(
+                non_zero(self.cpu_time, "cpu_time")?,
+                self.minimum_remaining_timeslice,
+                self.pid_policy,
+                self.priority_bounds,
+                self.event_semantics,
+            )),
+        };
+        Ok(scheduler)
+    }
+}
+
+/// The error returned by [`SchedulerConfig::build`] for an invalid configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The policy requires a parameter that was not set.
+    Missing {
+        /// The policy of the scheduler.
+        policy: Policy,
+
+        /// The name of the missing parameter.
+        parameter: &'static str,
+    },
+
+    /// A parameter that cannot be 0 was set to 0.
+    Zero {
+        /// The name of the parameter.
+        parameter: &'static str,
+    },
+
+    /// A parameter was set, but the policy does not use it.
+    Unused {
+        /// The policy of the scheduler.
+        policy: Policy,
+
+        /// The name of the unused parameter.
+        parameter: &'static str,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Missing { policy, parameter } => {
+                write!(f, "the {} policy requires a {parameter}", policy.name())
+            }
+            ConfigError::Zero { parameter } => write!(f, "the {parameter} cannot be 0"),
+            ConfigError::Unused { policy, parameter } => {
+                write!(f, "the {} policy does not use a {parameter}", policy.name())
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
@@ -0,0 +1,611 @@
+//! Generic checks that any [`Scheduler`] implementation has to pass.
+//!
+//! The checks simulate the processor without threads: every scenario is
+//! a set of programs ([`Action`] lists) that are run according to the
+//! decisions of the scheduler. After every decision, the state reported
+//! by the scheduler is compared with the state of the simulation:
+//!
+//! * the first process receives PID 1 and PIDs are never reused by processes that are alive;
+//! * only ready processes (or sleeping processes whose time has passed) are scheduled;
+//! * the processor sleeps only when no process is ready, for exactly the time
+//!   until the first sleeping process wakes up;
+//! * a process that sleeps for 0 time units stays ready, a signal wakes up only
+//!   the processes that already wait for the event and is lost otherwise, unless
+//!   the scheduler remembers it for the next wait (see [`EventSemantics`](crate::EventSemantics));
+//! * [`SchedulingDecision::Deadlock`] is returned only when all the processes
+//!   are waiting for events, [`SchedulingDecision::Done`] only when there are
+//!   no processes and [`SchedulingDecision::Panic`] only after the process
+//!   with PID 1 has exited;
+//! * [`Scheduler::list`] reports every process that is alive exactly once,
+//!   with the right state;
+//! * [`Scheduler::for_each_process`] visits the same processes as [`Scheduler::list`],
+//!   in the same order;
+//! * the timings of the processes never decrease;
+//! * the parent of a process, if reported, is the process that has forked it;
+//! * the creation and last scheduling times, if reported, match the virtual time.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use scheduler::{conformance, EventSemantics, PidPolicy, Policy, SchedulerConfig};
+//! use std::num::NonZeroUsize;
+//!
+//! conformance::assert_conforms(|| scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1));
+//! conformance::assert_conforms(|| scheduler::priority_queue(NonZeroUsize::new(3).unwrap(), 1));
+//! conformance::assert_conforms(|| scheduler::cfs(NonZeroUsize::new(10).unwrap(), 1));
+//!
+//! let config = SchedulerConfig::new(Policy::RoundRobin).timeslice(3).pid_policy(PidPolicy::Recycle);
+//! conformance::assert_conforms(|| config.build().unwrap());
+//!
+//! let config = SchedulerConfig::new(Policy::Cfs).cpu_time(10).event_semantics(EventSemantics::Sticky);
+//! conformance::assert_conforms(|| config.build().unwrap());
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{
+    Event, Pid, Priority, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    Timings,
+};
+
+/// The maximum number of decisions a scenario can take.
+const MAX_DECISIONS: usize = 10_000;
+
+/// An instruction of a simulated process.
+///
+/// A process exits after its last action.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Execute for a number of time units.
+    Exec(usize),
+
+    /// Create a new process with a priority and a program.
+    Fork(i8, Vec<Action>),
+
+    /// Sleep for a number of time units.
+    Sleep(usize),
+
+    /// Wait for an event.
+    Wait(usize),
+
+    /// Signal an event.
+    Signal(usize),
+}
+
+/// A named program for the process with PID 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// The name of the scenario, used in failure messages.
+    pub name: &'static str,
+
+    /// The program of the process with PID 1.
+    pub program: Vec<Action>,
+}
+
+/// A check that has failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    /// The name of the scenario.
+    pub scenario: &'static str,
+
+    /// The number of decisions taken before the failure.
+    pub decisions: usize,
+
+    /// The description of the failure.
+    pub message: String,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scenario `{}`, after {} decisions: {}",
+            self.scenario, self.decisions, self.message
+        )
+    }
+}
+
+/// Returns the scenarios used by [`check`].
+pub fn scenarios() -> Vec<Scenario> {
+    use Action::*;
+
+    vec![
+        Scenario {
+            name: "single process",
+            program: vec![Exec(7)],
+        },
+        Scenario {
+            name: "fork",
+            program: vec![
+                Fork(0, vec![Exec(3)]),
+                Fork(0, vec![Exec(2), Fork(0, vec![Exec(1)])]),
+                Exec(4),
+                Sleep(30),
+            ],
+        },
+        Scenario {
+            name: "sleep",
+            program: vec![Fork(0, vec![Sleep(3), Exec(1)]), Sleep(7), Exec(1)],
+        },
+        Scenario {
+            name: "wait and signal",
+            program: vec![
+                Fork(0, vec![Wait(1), Exec(2)]),
+                Fork(0, vec![Wait(1), Exec(1)]),
+                Exec(3),
+                Signal(1),
+                Sleep(20),
+            ],
+        },
+        Scenario {
+            name: "sleep while waiting",
+            program: vec![Fork(0, vec![Sleep(4), Signal(1)]), Wait(1), Exec(1)],
+        },
+        Scenario {
+            name: "yield",
+            program: vec![
+                Fork(0, vec![Exec(2), Sleep(0), Exec(1)]),
+                Sleep(0),
+                Exec(2),
+                Sleep(0),
+                Sleep(10),
+            ],
+        },
+        Scenario {
+            name: "signal without waiters",
+            program: vec![Signal(1), Fork(0, vec![Signal(2), Exec(1), Wait(3)]), Sleep(4), Signal(3), Exec(1)],
+        },
+        Scenario {
+            name: "wait after signal",
+            program: vec![Fork(0, vec![Signal(1)]), Sleep(3), Wait(1)],
+        },
+        Scenario {
+            name: "deadlock",
+            program: vec![Fork(0, vec![Exec(2), Wait(2)]), Wait(1)],
+        },
+        Scenario {
+            name: "panic",
+            program: vec![Fork(0, vec![Exec(10)]), Exec(1)],
+        },
+        Scenario {
+            name: "priorities",
+            program: vec![
+                Fork(5, vec![Exec(4), Sleep(2), Exec(1)]),
+                Fork(-3, vec![Exec(6)]),
+                Fork(1, vec![Exec(4), Sleep(2)]),
+                Sleep(40),
+            ],
+        },
+        Scenario {
+            name: "many processes",
+            program: (0..20)
+                .map(|index| {
+                    Fork(
+                        (index % 5) as i8,
+                        vec![Exec(index % 4 + 1), Sleep(index % 3 + 1), Exec(2)],
+                    )
+                })
+                .chain([Sleep(200)])
+                .collect(),
+        },
+    ]
+}
+
+/// Runs all the [`scenarios`] and returns the failed checks.
+///
+/// Every scenario uses a new scheduler.
+///
+/// * `factory` - a function that creates the scheduler.
+pub fn check<S, F>(factory: F) -> Vec<Failure>
+where
+    S: Scheduler,
+    F: Fn() -> S,
+{
+    scenarios()
+        .iter()
+        .filter_map(|scenario| run(factory(), scenario).err())
+        .collect()
+}
+
+/// Runs all the [`scenarios`] and panics if any check fails.
+///
+/// * `factory` - a function that creates the scheduler.
+pub fn assert_conforms<S, F>(factory: F)
+where
+    S: Scheduler,
+    F: Fn() -> S,
+{
+    let failures = check(factory);
+    if !failures.is_empty() {
+        let messages = failures
+            .iter()
+            .map(Failure::to_string)
+            .collect::<Vec<_>>();
+        panic!("The scheduler does not conform:\n{}", messages.join("\n"));
+    }
+}
+
+/// Runs a scenario and verifies the decisions of the scheduler.
+///
+/// A panic of the scheduler is reported as a failure.
+///
+/// * `scheduler` - a new scheduler.
+/// * `scenario` - the scenario to run.
+pub fn run<S: Scheduler>(mut scheduler: S, scenario: &Scenario) -> Result<(), Failure> {
+    let mut simulation = Simulation::new(scenario.program.clone());
+    let result = panic::catch_unwind(AssertUnwindSafe(|| simulation.run(&mut scheduler)));
+    let message = match result {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(message)) => message,
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|reason| reason.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            format!("the scheduler has panicked: {reason}")
+        }
+    };
+    Err(Failure {
+        scenario: scenario.name,
+        decisions: simulation.decisions,
+        message,
+    })
+}
+
+/// The state of a simulated process.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum State {
+    Ready,
+    Sleeping { until: usize },
+    Waiting { event: Event },
+}
+
+#[derive(Debug)]
+struct Task {
+    parent: Option<usize>,
+    created_at: usize,
+    scheduled_at: Option<usize>,
+    actions: Vec<Action>,
+    next: usize,
+    executed: usize,
+    state: State,
+    timings: Timings,
+}
+
+impl Task {
+    fn new(parent: Option<usize>, created_at: usize, actions: Vec<Action>) -> Task {
+        Task {
+            parent,
+            created_at,
+            scheduled_at: None,
+            actions,
+            next: 0,
+            executed: 0,
+            state: State::Ready,
+            timings: Timings::default(),
+        }
+    }
+}
+
+struct Simulation {
+    tasks: BTreeMap<usize, Task>,
+    main: Option<Vec<Action>>,
+    now: usize,
+    decisions: usize,
+    exited_first: bool,
+    /// The events signaled while no process waited for them.
+    lost: BTreeSet<Event>,
+}
+
+impl Simulation {
+    fn new(program: Vec<Action>) -> Simulation {
+        Simulation {
+            tasks: BTreeMap::new(),
+            main: Some(program),
+            now: 0,
+            decisions: 0,
+            exited_first: false,
+            lost: BTreeSet::new(),
+        }
+    }
+
+    fn run(&mut self, scheduler: &mut dyn Scheduler) -> Result<(), String> {
+        match scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0)))) {
+            SyscallResult::Pid(pid) if pid == 1 => {}
+            result => return Err(format!("the first process has to be PID 1, got {result:?}")),
+        }
+        let program = self.main.take().unwrap_or_default();
+        self.tasks.insert(1, Task::new(None, 0, program));
+
+        loop {
+            if self.decisions == MAX_DECISIONS {
+                return Err(format!("the simulation did not end after {MAX_DECISIONS} decisions"));
+            }
+            let decision = scheduler.next();
+            self.decisions += 1;
+            match decision {
+                SchedulingDecision::Run { pid, timeslice } => {
+                    self.verify_run(pid)?;
+                    self.tasks.get_mut(&pid.get()).unwrap().scheduled_at = Some(self.now);
+                    self.verify_list(scheduler, Some(pid))?;
+                    self.execute(scheduler, pid.get(), timeslice.get())?;
+                }
+                SchedulingDecision::Sleep(amount) => {
+                    self.verify_sleep(amount.get())?;
+                    self.verify_list(scheduler, None)?;
+                    self.now += amount.get();
+                    scheduler.on_tick(amount);
+                }
+                SchedulingDecision::Deadlock => {
+                    return self.verify_deadlock();
+                }
+                SchedulingDecision::Panic => {
+                    if !self.exited_first || self.tasks.is_empty() {
+                        return Err(String::from(
+                            "panic is allowed only if PID 1 has exited before other processes",
+                        ));
+                    }
+                    return Ok(());
+                }
+                SchedulingDecision::Done => {
+                    if !self.tasks.is_empty() {
+                        return Err(format!(
+                            "done while processes {:?} are still alive",
+                            self.tasks.keys().collect::<Vec<_>>()
+                        ));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the process can be scheduled.
+    fn runnable(&self, state: State) -> bool {
+        match state {
+            State::Ready => true,
+            State::Sleeping { until } => until <= self.now,
+            State::Waiting { .. } => false,
+        }
+    }
+
+    fn verify_run(&mut self, pid: Pid) -> Result<(), String> {
+        let Some(task) = self.tasks.get(&pid.get()) else {
+            return Err(format!("scheduled PID {pid}, which does not exist"));
+        };
+        if !self.runnable(task.state) {
+            return Err(format!("scheduled PID {pid}, which is {:?}", task.state));
+        }
+        Ok(())
+    }
+
+    fn verify_sleep(&self, amount: usize) -> Result<(), String> {
+        if let Some((pid, _)) = self.tasks.iter().find(|(_, task)| self.runnable(task.state)) {
+            return Err(format!("slept while PID {pid} can run"));
+        }
+        let wake = self
+            .tasks
+            .values()
+            .filter_map(|task| match task.state {
+                State::Sleeping { until } => Some(until - self.now),
+                _ => None,
+            })
+            .min();
+        match wake {
+            Some(wake) if wake == amount => Ok(()),
+            Some(wake) => Err(format!(
+                "slept for {amount} units, but the first process wakes up after {wake} units"
+            )),
+            None => Err(format!("slept for {amount} units, but no process is sleeping")),
+        }
+    }
+
+    fn verify_deadlock(&self) -> Result<(), String> {
+        if self.tasks.is_empty() {
+            return Err(String::from("deadlock without any process"));
+        }
+        match self
+            .tasks
+            .iter()
+            .find(|(_, task)| !matches!(task.state, State::Waiting { .. }))
+        {
+            Some((pid, task)) => Err(format!("deadlock while PID {pid} is {:?}", task.state)),
+            None => Ok(()),
+        }
+    }
+
+    fn verify_list(&mut self, scheduler: &mut dyn Scheduler, running: Option<Pid>) -> Result<(), String> {
+        let order: Vec<usize> = scheduler.list().iter().map(|process| process.pid().get()).collect();
+        let mut visited = Vec::new();
+        scheduler.for_each_process(&mut |process| visited.push(process.pid().get()));
+        if visited != order {
+            return Err(format!("the processes are visited in the order {visited:?}, but listed in the order {order:?}"));
+        }
+
+        let mut listed = BTreeMap::new();
+        for process in scheduler.list() {
+            let pid = process.pid().get();
+            let entry = (
+                process.state(),
+                process.timings(),
+                process.parent(),
+                process.created_at(),
+                process.last_scheduled_at(),
+            );
+            if listed.insert(pid, entry).is_some() {
+                return Err(format!("PID {pid} is listed more than once"));
+            }
+        }
+
+        for (pid, task) in self.tasks.iter_mut() {
+            let Some((state, timings, parent, created_at, scheduled_at)) = listed.remove(pid) else {
+                return Err(format!("PID {pid} is missing from the list"));
+            };
+            let expected = match task.state {
+                _ if running == Some(Pid::new(*pid)) => Some(ProcessState::Running),
+                State::Ready => Some(ProcessState::Ready),
+                State::Sleeping { until } if until > self.now => {
+                    Some(ProcessState::Waiting { event: None })
+                }
+                State::Sleeping { .. } => None,
+                State::Waiting { event } => Some(ProcessState::Waiting { event: Some(event) }),
+            };
+            if expected.is_some_and(|expected| expected != state) {
+                return Err(format!(
+                    "PID {pid} is listed as {state}, expected {}",
+                    expected.unwrap()
+                ));
+            }
+
+            if parent.is_some_and(|parent| Some(parent.get()) != task.parent) {
+                return Err(format!(
+                    "PID {pid} is listed with parent {parent:?}, expected {:?}",
+                    task.parent
+                ));
+            }
+
+            if created_at.is_some_and(|created_at| created_at != task.created_at) {
+                return Err(format!(
+                    "PID {pid} is listed as created at {created_at:?}, expected {}",
+                    task.created_at
+                ));
+            }
+            if scheduled_at.is_some() && scheduled_at != task.scheduled_at {
+                return Err(format!(
+                    "PID {pid} is listed as last scheduled at {scheduled_at:?}, expected {:?}",
+                    task.scheduled_at
+                ));
+            }
+
+            let previous = task.timings;
+            if timings.total < previous.total
+                || timings.syscall < previous.syscall
+                || timings.execution < previous.execution
+            {
+                return Err(format!(
+                    "the timings of PID {pid} have decreased from {previous:?} to {timings:?}"
+                ));
+            }
+            task.timings = timings;
+        }
+
+        match listed.keys().next() {
+            Some(pid) => Err(format!("PID {pid} is listed, but it does not exist")),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs a process until it sends a system call or its timeslice expires.
+    fn execute(&mut self, scheduler: &mut dyn Scheduler, pid: usize, timeslice: usize) -> Result<(), String> {
+        let mut remaining = timeslice;
+        let task = self.tasks.get_mut(&pid).unwrap();
+        task.state = State::Ready;
+
+        loop {
+            let task = self.tasks.get_mut(&pid).unwrap();
+            let action = task.actions.get(task.next).cloned();
+            let syscall = match action {
+                Some(Action::Exec(units)) => {
+                    if task.executed < units {
+                        task.executed += 1;
+                        remaining -= 1;
+                        self.now += 1;
+                        if remaining == 0 {
+                            scheduler.stop(StopReason::Expired);
+                            return Ok(());
+                        }
+                        if scheduler.preempt(remaining) {
+                            scheduler.stop(StopReason::Preempted { remaining });
+                            return Ok(());
+                        }
+                        continue;
+                    }
+                    task.next += 1;
+                    task.executed = 0;
+                    continue;
+                }
+                Some(Action::Fork(priority, _)) => Syscall::Fork(Priority::new(priority)),
+                Some(Action::Sleep(amount)) => Syscall::Sleep(amount),
+                Some(Action::Wait(event)) => Syscall::Wait(Event::user(event)),
+                Some(Action::Signal(event)) => Syscall::Signal(Event::user(event)),
+                None => Syscall::Exit,
+            };
+
+            task.next += 1;
+            remaining -= 1;
+            self.now += 1;
+            let result = scheduler.stop(StopReason::Syscall { syscall, remaining });
+            return self.complete(scheduler, pid, syscall, action, result);
+        }
+    }
+
+    /// Updates the simulation after a system call.
+    fn complete(
+        &mut self,
+        scheduler: &mut dyn Scheduler,
+        pid: usize,
+        syscall: Syscall,
+        action: Option<Action>,
+        result: SyscallResult,
+    ) -> Result<(), String> {
+        match (syscall, result) {
+            (Syscall::Fork(_), SyscallResult::Pid(child)) => {
+                if self.tasks.contains_key(&child.get()) {
+                    return Err(format!("fork returned PID {child}, which is already used"));
+                }
+                let Some(Action::Fork(_, program)) = action else {
+                    unreachable!();
+                };
+                self.tasks.insert(child.get(), Task::new(Some(pid), self.now, program));
+            }
+            (Syscall::Fork(_), result) => {
+                return Err(format!("fork returned {result:?} instead of a PID"));
+            }
+            (_, SyscallResult::Success) => match syscall {
+                Syscall::Sleep(0) => {}
+                Syscall::Sleep(amount) => {
+                    self.tasks.get_mut(&pid).unwrap().state = State::Sleeping {
+                        until: self.now + amount,
+                    };
+                }
+                Syscall::Wait(event) => {
+                    // a scheduler that remembers the lost signal does not
+                    // make the process wait
+                    let waiting = !self.lost.remove(&event)
+                        || scheduler.list().iter().any(|process| {
+                            process.pid() == Pid::new(pid) && process.state() == (ProcessState::Waiting { event: Some(event) })
+                        });
+                    if waiting {
+                        self.tasks.get_mut(&pid).unwrap().state = State::Waiting { event };
+                    }
+                }
+                Syscall::Signal(event) => {
+                    let mut woken = false;
+                    for task in self.tasks.values_mut() {
+                        if task.state == (State::Waiting { event }) {
+                            task.state = State::Ready;
+                            woken = true;
+                        }
+                    }
+                    if !woken {
+                        self.lost.insert(event);
+                    }
+                }
+                Syscall::Exit => {
+                    self.tasks.remove(&pid);
+                    if pid == 1 && !self.tasks.is_empty() {
+                        self.exited_first = true;
+                    }
+                }
+                Syscall::Fork(_) => unreachable!(),
+            },
+            (_, result) => {
+                return Err(format!("{syscall:?} returned {result:?} instead of Success"));
+            }
+        }
+        Ok(())
+    }
+}
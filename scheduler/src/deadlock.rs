@@ -0,0 +1,202 @@
+//! Explain a deadlock with the graph of the processes that wait for
+//! each other.
+//!
+//! A process that waits for an event waits for the processes that can
+//! signal it. The schedulers do not know which processes signal an
+//! event, so the signalers are given by the caller, usually the
+//! processes that have signaled the event before.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use scheduler::deadlock::WaitForGraph;
+//! use scheduler::{Event, Pid};
+//!
+//! let mut graph = WaitForGraph::new();
+//! graph.wait(Pid::new(2), Event::user(1));
+//! graph.wait(Pid::new(3), Event::user(2));
+//! graph.wait(Pid::new(4), Event::user(3));
+//! graph.signaler(Event::user(1), Pid::new(3));
+//! graph.signaler(Event::user(2), Pid::new(2));
+//!
+//! let deadlock = graph.deadlock();
+//! assert_eq!(deadlock.cycles, vec![vec![Pid::new(2), Pid::new(3)]]);
+//! assert_eq!(deadlock.events(), vec![Event::user(1), Event::user(2), Event::user(3)]);
+//! assert!(deadlock.to_string().contains("4 waits for event 3, which no waiting process signals"));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+use crate::{Event, Pid, ProcessState, Scheduler};
+
+/// The processes that wait for events and the processes that signal
+/// these events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaitForGraph {
+    waiting: BTreeMap<Pid, Event>,
+    signalers: BTreeMap<Event, BTreeSet<Pid>>,
+}
+
+impl WaitForGraph {
+    /// A graph without processes.
+    pub fn new() -> WaitForGraph {
+        WaitForGraph::default()
+    }
+
+    /// A graph with the processes that the scheduler lists as waiting
+    /// for an event, without any signaler.
+    ///
+    /// * `scheduler` - the scheduler, usually after it has decided
+    ///   [`SchedulingDecision::Deadlock`](crate::SchedulingDecision::Deadlock).
+    pub fn from_scheduler<S: Scheduler + ?Sized>(scheduler: &mut S) -> WaitForGraph {
+        let mut graph = WaitForGraph::new();
+        scheduler.for_each_process(&mut |process| {
+            if let ProcessState::Waiting { event: Some(event) } = process.state() {
+                graph.wait(process.pid(), event);
+            }
+        });
+        graph
+    }
+
+    /// Adds a process that waits for an event.
+    pub fn wait(&mut self, pid: Pid, event: Event) {
+        self.waiting.insert(pid, event);
+    }
+
+    /// Adds a process that can signal an event.
+    pub fn signaler(&mut self, event: Event, pid: Pid) {
+        self.signalers.entry(event).or_default().insert(pid);
+    }
+
+    /// The waiting processes that signal the event `pid` waits for, in
+    /// the order of their PIDs.
+    pub fn waits_for(&self, pid: Pid) -> Vec<Pid> {
+        let Some(event) = self.waiting.get(&pid) else {
+            return Vec::new();
+        };
+        self.signalers
+            .get(event)
+            .into_iter()
+            .flatten()
+            .filter(|signaler| self.waiting.contains_key(signaler))
+            .copied()
+            .collect()
+    }
+
+    /// The groups of processes that wait for each other, every group in
+    /// the order of the PIDs. A process that waits for an event that only
+    /// it signals is a group on its own.
+    pub fn cycles(&self) -> Vec<Vec<Pid>> {
+        // the strongly connected components, with the algorithm of Tarjan
+        let mut search = Search {
+            graph: self,
+            index: BTreeMap::new(),
+            low: BTreeMap::new(),
+            stack: Vec::new(),
+            components: Vec::new(),
+        };
+        for pid in self.waiting.keys() {
+            if !search.index.contains_key(pid) {
+                search.visit(*pid);
+            }
+        }
+        let mut cycles = search
+            .components
+            .into_iter()
+            .filter(|component| component.len() > 1 || self.waits_for(component[0]).contains(&component[0]))
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect::<Vec<_>>();
+        cycles.sort();
+        cycles
+    }
+
+    /// The blocked processes and the groups of processes that wait for
+    /// each other.
+    pub fn deadlock(&self) -> Deadlock {
+        Deadlock {
+            blocked: self.waiting.iter().map(|(pid, event)| (*pid, *event)).collect(),
+            cycles: self.cycles(),
+            signalers: self.waiting.keys().map(|pid| self.waits_for(*pid)).collect(),
+        }
+    }
+}
+
+struct Search<'a> {
+    graph: &'a WaitForGraph,
+    index: BTreeMap<Pid, usize>,
+    low: BTreeMap<Pid, usize>,
+    stack: Vec<Pid>,
+    components: Vec<Vec<Pid>>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, pid: Pid) {
+        let index = self.index.len();
+        self.index.insert(pid, index);
+        self.low.insert(pid, index);
+        self.stack.push(pid);
+        for next in self.graph.waits_for(pid) {
+            if !self.index.contains_key(&next) {
+                self.visit(next);
+                let low = self.low[&pid].min(self.low[&next]);
+                self.low.insert(pid, low);
+            } else if self.stack.contains(&next) {
+                let low = self.low[&pid].min(self.index[&next]);
+                self.low.insert(pid, low);
+            }
+        }
+        if self.low[&pid] == index {
+            let start = self.stack.iter().position(|other| *other == pid).unwrap();
+            self.components.push(self.stack.split_off(start));
+        }
+    }
+}
+
+/// The processes blocked by a deadlock and why they cannot continue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deadlock {
+    /// The processes that wait, and the event every one of them waits
+    /// for, in the order of their PIDs.
+    pub blocked: Vec<(Pid, Event)>,
+
+    /// The groups of processes that wait for each other, see
+    /// [`WaitForGraph::cycles`].
+    pub cycles: Vec<Vec<Pid>>,
+
+    /// The waiting processes that signal the event every blocked
+    /// process waits for, in the same order as `blocked`.
+    signalers: Vec<Vec<Pid>>,
+}
+
+impl Deadlock {
+    /// The events that the blocked processes wait for, without duplicates.
+    pub fn events(&self) -> Vec<Event> {
+        let events = self.blocked.iter().map(|(_, event)| *event).collect::<BTreeSet<_>>();
+        events.into_iter().collect()
+    }
+}
+
+/// A line for every blocked process and for every group of processes
+/// that wait for each other.
+impl Display for Deadlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |pids: &[Pid]| pids.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(", ");
+        for ((pid, event), signalers) in self.blocked.iter().zip(&self.signalers) {
+            match signalers.is_empty() {
+                true => writeln!(f, "{pid} waits for event {event}, which no waiting process signals")?,
+                false => writeln!(f, "{pid} waits for event {event}, signaled by {}", list(signalers))?,
+            }
+        }
+        for cycle in &self.cycles {
+            match cycle.as_slice() {
+                [pid] => writeln!(f, "process {pid} waits for itself")?,
+                cycle => writeln!(f, "processes {} wait for each other", list(cycle))?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt::{self, Display};
+use std::num::{NonZeroUsize, ParseIntError};
+use std::str::FromStr;
+
+use crate::Pid;
+
+/// The namespace of an [`Event`].
+///
+/// Events with the same number in different namespaces are
+/// different events.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum EventNamespace {
+    /// Events chosen by the processes.
+    User,
+
+    /// Events signaled by a device.
+    Io,
+
+    /// Events signaled when a child process exits.
+    ChildExit,
+}
+
+impl EventNamespace {
+    /// Returns the prefix used when displaying the events of the
+    /// namespace, user events have no prefix.
+    fn prefix(&self) -> Option<&'static str> {
+        match self {
+            EventNamespace::User => None,
+            EventNamespace::Io => Some("io"),
+            EventNamespace::ChildExit => Some("child"),
+        }
+    }
+}
+
+/// What happens to a [`Syscall::Signal`](crate::Syscall::Signal) sent
+/// while no process waits for the event.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{EventSemantics, Policy, Priority, SchedulerConfig, StopReason, Syscall};
+/// use scheduler::{ProcessState, SchedulingDecision, SyscallResult};
+///
+/// let mut scheduler = SchedulerConfig::new(Policy::RoundRobin)
+///     .timeslice(5)
+///     .event_semantics(EventSemantics::Sticky)
+///     .build()
+///     .unwrap();
+/// scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+/// scheduler.next();
+/// scheduler.stop(StopReason::Syscall { syscall: Syscall::Signal(1.into()), remaining: 4 });
+/// scheduler.next();
+///
+/// // the signal was remembered, the process does not wait
+/// scheduler.stop(StopReason::Syscall { syscall: Syscall::Wait(1.into()), remaining: 3 });
+/// assert!(matches!(scheduler.next(), SchedulingDecision::Run { .. }));
+/// assert_eq!(scheduler.list()[0].state(), ProcessState::Running);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum EventSemantics {
+    /// The signal is lost, a process that waits for the event later
+    /// waits for the next signal.
+    #[default]
+    Transient,
+
+    /// The event is remembered, like a binary semaphore: the next
+    /// [`Syscall::Wait`](crate::Syscall::Wait) for it returns immediately
+    /// and the process continues like after a signal. Signaling an event
+    /// that is already remembered has no effect.
+    Sticky,
+}
+
+/// An event that processes can wait for and signal.
+///
+/// User events are displayed as a bare number, events from other
+/// namespaces are prefixed with the name of the namespace.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Event, Pid};
+///
+/// assert_eq!(Event::from(3), Event::user(3));
+/// assert_ne!(Event::user(3), Event::io(3));
+/// assert_eq!(Event::io(3).to_string(), "io:3");
+/// assert_eq!("child:2".parse(), Ok(Event::child_exit(Pid::new(2))));
+/// assert_eq!(format!("{:?}", Event::user(3)), "3");
+/// ```
+#[derive(PartialEq, Eq, Copy, Clone, Hash, Ord, PartialOrd)]
+pub struct Event {
+    namespace: EventNamespace,
+    number: usize,
+}
+
+impl Event {
+    /// Returns an event chosen by the processes.
+    ///
+    /// * `number` - the number of the event.
+    pub const fn user(number: usize) -> Event {
+        Event {
+            namespace: EventNamespace::User,
+            number,
+        }
+    }
+
+    /// Returns the event signaled by a device.
+    ///
+    /// * `device` - the number of the device.
+    pub const fn io(device: usize) -> Event {
+        Event {
+            namespace: EventNamespace::Io,
+            number: device,
+        }
+    }
+
+    /// Returns the event signaled when a process exits, that its
+    /// parent can wait for.
+    ///
+    /// * `pid` - the PID of the child process.
+    pub fn child_exit(pid: Pid) -> Event {
+        Event {
+            namespace: EventNamespace::ChildExit,
+            number: pid.get(),
+        }
+    }
+
+    /// Returns the namespace of the event.
+    pub fn namespace(&self) -> EventNamespace {
+        self.namespace
+    }
+
+    /// Returns the number of the event within its namespace.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+}
+
+impl From<usize> for Event {
+    fn from(number: usize) -> Event {
+        Event::user(number)
+    }
+}
+
+impl PartialEq<usize> for Event {
+    fn eq(&self, other: &usize) -> bool {
+        *self == Event::user(*other)
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.namespace.prefix() {
+            Some(prefix) => write!(f, "{prefix}:{}", self.number),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+impl fmt::Debug for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Event {
+    type Err = ParseIntError;
+
+    fn from_str(event: &str) -> Result<Event, ParseIntError> {
+        if let Some(device) = event.strip_prefix("io:") {
+            return device.parse().map(Event::io);
+        }
+        if let Some(pid) = event.strip_prefix("child:") {
+            return pid.parse::<NonZeroUsize>().map(|pid| Event {
+                namespace: EventNamespace::ChildExit,
+                number: pid.get(),
+            });
+        }
+        event.parse().map(Event::user)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Pid, Process, Scheduler};
+
+/// A scheduler whose queues can be inspected without changing it.
+///
+/// Unlike [`Scheduler::list`], this shows the order of the ready
+/// processes.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Pid, Priority, Scheduler, StopReason, Syscall};
+/// use std::num::NonZeroUsize;
+///
+/// let mut scheduler = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+/// scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+/// scheduler.next();
+/// scheduler.stop(StopReason::Syscall { syscall: Syscall::Fork(Priority::new(0)), remaining: 0 });
+///
+/// let inspectable = scheduler.as_inspectable().unwrap();
+/// assert!(inspectable.current().is_none());
+/// let ready: Vec<_> = inspectable.ready().iter().map(|process| process.pid()).collect();
+/// assert_eq!(ready, [2, 1]);
+/// assert!(inspectable.waiting().is_empty());
+/// assert_eq!(inspectable.process(Pid::new(2)).unwrap().pid(), 2);
+/// assert!(inspectable.process(Pid::new(3)).is_none());
+/// ```
+pub trait InspectableScheduler: Scheduler {
+    /// Returns the process that is running, if there is one.
+    fn current(&self) -> Option<&dyn Process>;
+
+    /// Returns the processes that are ready, starting with the one
+    /// that would be scheduled next if no other process became ready.
+    fn ready(&self) -> Vec<&dyn Process>;
+
+    /// Returns the processes that are waiting for an event or sleeping,
+    /// in no particular order.
+    fn waiting(&self) -> Vec<&dyn Process>;
+
+    /// Returns the process with the PID `pid`, whatever queue it is in.
+    ///
+    /// The default implementation searches [`current`](Self::current),
+    /// [`ready`](Self::ready) and [`waiting`](Self::waiting). The bundled
+    /// schedulers keep an index of their processes and find it in
+    /// constant time.
+    ///
+    /// * `pid` - the PID of the process
+    fn process(&self, pid: Pid) -> Option<&dyn Process> {
+        self.current().into_iter().chain(self.ready()).chain(self.waiting()).find(|process| process.pid() == pid)
+    }
+}
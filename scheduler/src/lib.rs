@@ -6,11 +6,41 @@
 
 use std::num::NonZeroUsize;
 
+mod allocator;
+mod asynchronous;
+mod bounds;
+mod class;
+mod config;
+pub mod deadlock;
+mod event;
+mod inspect;
+#[cfg(feature = "memory-usage")]
+mod memory;
+pub mod conformance;
 mod scheduler;
+mod seed;
+mod snapshot;
+mod timer;
+mod timings;
+mod tuning;
 
 pub use crate::scheduler::{
-    Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    Pid, Priority, Process, ProcessState, Scheduler, SchedulerStats, SchedulingDecision, StopReason,
+    Syscall, SyscallResult,
 };
+pub use crate::allocator::{PidAllocator, PidPolicy};
+pub use crate::asynchronous::{AsyncScheduler, BlockingScheduler};
+pub use crate::bounds::{OutOfBounds, PriorityBounds};
+pub use crate::class::Class;
+pub use crate::config::{ConfigError, Params, Policy, SchedulerConfig};
+pub use crate::event::{Event, EventNamespace, EventSemantics};
+pub use crate::inspect::InspectableScheduler;
+#[cfg(feature = "memory-usage")]
+pub use crate::memory::MemoryUsage;
+pub use crate::seed::{Seed, SeedError, SeedState, SeedableScheduler};
+pub use crate::snapshot::{SnapshotError, SnapshotScheduler};
+pub use crate::timings::Timings;
+pub use crate::tuning::{Parameter, TunableScheduler};
 
 use crate::schedulers::{CFS, PriorityQueue, RoundRobin};
 mod schedulers;
@@ -25,8 +55,8 @@ mod schedulers;
 ///                                 again of the remaining quanta is greater or equal to
 ///                                 the `minimum_remaining_timeslice` value.
 #[allow(unused_variables)]
-pub fn round_robin(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
-    RoundRobin::new(timeslice, minimum_remaining_timeslice)
+pub fn round_robin(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl SnapshotScheduler {
+    RoundRobin::new(timeslice, minimum_remaining_timeslice, PidPolicy::Increasing, PriorityBounds::default(), EventSemantics::Transient)
 }
 
 /// Returns a structure that implements the `Scheduler` trait with a priority queue scheduler policy
@@ -41,8 +71,8 @@ pub fn round_robin(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize)
 pub fn priority_queue(
     timeslice: NonZeroUsize,
     minimum_remaining_timeslice: usize,
-) -> impl Scheduler {
-    PriorityQueue::new(timeslice, minimum_remaining_timeslice)
+) -> impl SnapshotScheduler {
+    PriorityQueue::new(timeslice, minimum_remaining_timeslice, PidPolicy::Increasing, PriorityBounds::default(), EventSemantics::Transient)
 }
 
 /// Returns a structure that implements the `Scheduler` trait with a simplified [cfs](https://opensource.com/article/19/2/fair-scheduling-linux) scheduler policy
@@ -55,6 +85,38 @@ pub fn priority_queue(
 ///                                 again of the remaining quanta is greater or equal to
 ///                                 the `minimum_remaining_timeslice` value.
 #[allow(unused_variables)]
-pub fn cfs(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
-    CFS::new(cpu_time, minimum_remaining_timeslice)
+pub fn cfs(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl SnapshotScheduler {
+    CFS::new(cpu_time, minimum_remaining_timeslice, PidPolicy::Increasing, PriorityBounds::default(), EventSemantics::Transient)
+}
+
+/// Returns the scheduler with the given policy name, or [`None`] if
+/// there is no such policy. This allows choosing the policy at runtime.
+///
+/// * `name` - the name of the policy: `round-robin`, `priority-queue` or `cfs`.
+/// * `params` - the parameters of the scheduler, every policy uses
+///   only the ones it needs.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::Params;
+/// use std::num::NonZeroUsize;
+///
+/// let params = Params {
+///     timeslice: NonZeroUsize::new(3).unwrap(),
+///     minimum_remaining_timeslice: 1,
+///     cpu_time: NonZeroUsize::new(10).unwrap(),
+/// };
+/// assert!(scheduler::by_name("cfs", &params).is_some());
+/// assert!(scheduler::by_name("lottery", &params).is_none());
+/// ```
+pub fn by_name(name: &str, params: &Params) -> Option<Box<dyn Scheduler>> {
+    let policy = Policy::from_name(name)?;
+    let config = SchedulerConfig::new(policy).min_remaining(params.minimum_remaining_timeslice);
+    let config = match policy {
+        Policy::RoundRobin | Policy::PriorityQueue => config.timeslice(params.timeslice.get()),
+        Policy::Cfs => config.cpu_time(params.cpu_time.get()),
+    };
+    // the parameters are valid: they are not 0 and only the required ones are set
+    Some(config.build().unwrap())
 }
@@ -0,0 +1,23 @@
+/// The memory used by a part of a simulation, in bytes.
+///
+/// The amounts are estimated from the sizes and the capacities of the
+/// collections that store it, so they include the memory that is
+/// allocated but not used yet.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The memory used now.
+    pub current: usize,
+
+    /// The most memory used at the same time.
+    pub peak: usize,
+}
+
+impl MemoryUsage {
+    /// Sets the memory used now, and the peak if it is higher.
+    ///
+    /// * `current` - the memory used now, in bytes.
+    pub fn update(&mut self, current: usize) {
+        self.current = current;
+        self.peak = self.peak.max(current);
+    }
+}
@@ -1,6 +1,10 @@
+use std::borrow::Cow;
 use std::fmt::{self, Display};
-use std::num::NonZeroUsize;
+use std::num::{NonZeroUsize, ParseIntError, TryFromIntError};
 use std::ops::Add;
+use std::str::FromStr;
+
+use crate::{Class, Event, InspectableScheduler, PidPolicy, SeedableScheduler, Timings, TunableScheduler};
 
 /// The PID of a process
 ///
@@ -13,6 +17,11 @@ impl Pid {
     pub fn new(pid: usize) -> Pid {
         Pid(NonZeroUsize::new(pid).unwrap())
     }
+
+    /// Returns the PID as a number.
+    pub fn get(&self) -> usize {
+        self.0.get()
+    }
 }
 
 impl PartialEq<usize> for Pid {
@@ -41,6 +50,112 @@ impl Add<usize> for Pid {
     }
 }
 
+/// The priority of a process
+///
+/// Higher values mean more important processes. Some scheduling
+/// algorithms ignore the priority.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::Priority;
+///
+/// assert_eq!(Priority::from_nice(-5), Some(Priority::new(5)));
+/// assert_eq!(Priority::from_nice(20), None);
+/// assert_eq!(Priority::new(-19).nice(), Some(19));
+/// assert!(Priority::try_from(200).is_err());
+/// ```
+#[derive(PartialEq, Eq, Copy, Clone, Hash, Ord, PartialOrd, Default)]
+#[repr(transparent)]
+pub struct Priority(i8);
+
+impl Priority {
+    /// The lowest niceness value that can be converted to a priority.
+    pub const MIN_NICE: i8 = -20;
+
+    /// The highest niceness value that can be converted to a priority.
+    pub const MAX_NICE: i8 = 19;
+
+    pub const fn new(priority: i8) -> Priority {
+        Priority(priority)
+    }
+
+    /// Returns the priority as a number.
+    pub const fn get(&self) -> i8 {
+        self.0
+    }
+
+    /// Returns the priority that corresponds to a Unix niceness value,
+    /// or [`None`] if the niceness is not between [`Priority::MIN_NICE`]
+    /// and [`Priority::MAX_NICE`].
+    ///
+    /// Lower niceness values mean more important processes, so
+    /// the priority is the negated niceness.
+    ///
+    /// * `nice` - the niceness value.
+    pub fn from_nice(nice: i8) -> Option<Priority> {
+        (Priority::MIN_NICE..=Priority::MAX_NICE)
+            .contains(&nice)
+            .then(|| Priority(-nice))
+    }
+
+    /// Returns the Unix niceness value of the priority, or [`None`]
+    /// if the priority has no niceness equivalent.
+    pub fn nice(&self) -> Option<i8> {
+        let nice = self.0.checked_neg()?;
+        (Priority::MIN_NICE..=Priority::MAX_NICE)
+            .contains(&nice)
+            .then_some(nice)
+    }
+}
+
+impl From<i8> for Priority {
+    fn from(priority: i8) -> Priority {
+        Priority(priority)
+    }
+}
+
+impl From<Priority> for i8 {
+    fn from(priority: Priority) -> i8 {
+        priority.0
+    }
+}
+
+impl TryFrom<i32> for Priority {
+    type Error = TryFromIntError;
+
+    /// Converts a number to a priority, failing if it is outside the range of `i8`.
+    fn try_from(priority: i32) -> Result<Priority, TryFromIntError> {
+        i8::try_from(priority).map(Priority)
+    }
+}
+
+impl PartialEq<i8> for Priority {
+    fn eq(&self, other: &i8) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Debug for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Priority {
+    type Err = ParseIntError;
+
+    fn from_str(priority: &str) -> Result<Priority, ParseIntError> {
+        priority.parse().map(Priority)
+    }
+}
+
 /// The action that the scheduler asks the OS to take.
 ///
 /// This is returned by the [`Scheduler::next`] function.
@@ -91,10 +206,14 @@ pub enum Syscall {
     /// Create a new process and return its PID.
     Fork(
         /// The process's priority. Some scheduling algorithms can ignore this value.
-        i8,
+        Priority,
     ),
 
     /// Ask the scheduler to suspend for an amount of time
+    ///
+    /// Sleeping for 0 time units yields the processor: the process stays
+    /// in the [`ProcessState::Ready`] state and runs again after the
+    /// processes that are ready, in the order of the scheduler.
     Sleep(
         /// The amount of time that the process should sleep. The process
         /// will be placed in the [`ProcessState::Waiting`] state for this
@@ -103,18 +222,30 @@ pub enum Syscall {
     ),
 
     /// Wait for an event
+    ///
+    /// With [`EventSemantics::Transient`](crate::EventSemantics::Transient),
+    /// only the signals sent after the process starts waiting wake it
+    /// up. With [`EventSemantics::Sticky`](crate::EventSemantics::Sticky),
+    /// an event that was signaled while no process waited for it is
+    /// remembered, and the wait returns immediately.
     Wait(
-        /// The event number. The process will be placed in the [`ProcessState::Waiting`]
+        /// The event. The process will be placed in the [`ProcessState::Waiting`]
         /// until another process issues a [`Syscall::Signal`] system call with this
-        /// event number.
-        usize,
+        /// event.
+        Event,
     ),
 
     /// Signal all processes that wait for an event.
+    ///
+    /// Signaling an event that no process waits for has no effect with
+    /// [`EventSemantics::Transient`](crate::EventSemantics::Transient).
+    /// With [`EventSemantics::Sticky`](crate::EventSemantics::Sticky), the
+    /// event is remembered for the next wait, once, however many times
+    /// it is signaled.
     Signal(
-        /// The event number. All processes that are waiting for this event
+        /// The event. All processes that are waiting for this event
         /// will be woken up and placed in the [`ProcessState::Ready`] state.
-        usize,
+        Event,
     ),
 
     /// Ask the scheduler to finish the process.
@@ -145,6 +276,11 @@ pub enum SyscallResult {
 
     /// The system call was issues while no process was scheduled.
     NoRunningProcess,
+
+    /// Returned after a [`Syscall::Fork`] system call with a priority
+    /// that is rejected by the [`PriorityBounds`](crate::PriorityBounds)
+    /// of the scheduler. No process is created.
+    InvalidPriority,
 }
 
 /// The reason that a process has stopped and the OS
@@ -163,6 +299,13 @@ pub enum StopReason {
     /// The timeslice allocated for the process has expired and the process
     /// has been preempted.
     Expired,
+
+    /// The process has been preempted before its timeslice expired,
+    /// because the scheduler asked for it using [`Scheduler::preempt`].
+    Preempted {
+        /// The number of time units that the process has not used from its quanta
+        remaining: usize,
+    },
 }
 
 impl Display for StopReason {
@@ -174,6 +317,9 @@ impl Display for StopReason {
             StopReason::Expired => {
                 write!(f, "Expired")
             }
+            StopReason::Preempted { remaining } => {
+                write!(f, "Preempted, remaining {remaining}")
+            }
         }
     }
 }
@@ -187,14 +333,41 @@ impl StopReason {
     }
 
     pub fn set_remaining(&mut self, remaining: usize) {
-        if let StopReason::Syscall { syscall, .. } = *self {
-            *self = StopReason::Syscall { syscall, remaining };
+        match *self {
+            StopReason::Syscall { syscall, .. } => *self = StopReason::Syscall { syscall, remaining },
+            StopReason::Preempted { .. } => *self = StopReason::Preempted { remaining },
+            StopReason::Expired => {}
         }
     }
 
     pub fn expired() -> StopReason {
         StopReason::Expired
     }
+
+    pub fn preempted() -> StopReason {
+        StopReason::Preempted { remaining: 0 }
+    }
+}
+
+/// Counters kept by a scheduler, returned by [`Scheduler::stats`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SchedulerStats {
+    /// The number of times the scheduler has decided to run
+    /// a different process than the previous one.
+    pub context_switches: usize,
+
+    /// The number of times a process has been preempted because
+    /// its timeslice has expired.
+    ///
+    /// Preemptions requested by the scheduler using
+    /// [`Scheduler::preempt`] are not counted.
+    pub preemptions: usize,
+
+    /// The total time units the scheduler has asked the processor to sleep.
+    pub idle: usize,
+
+    /// The number of times a waiting process has become ready.
+    pub wakeups: usize,
 }
 
 /// The trait that any scheduler has to implement.
@@ -208,10 +381,191 @@ pub trait Scheduler: Send {
 
     /// Returns the list of processes.
     fn list(&mut self) -> Vec<&dyn Process>;
+
+    /// Calls `f` for every process, in the order of [`Scheduler::list`].
+    ///
+    /// The processor uses this function to take a snapshot of the
+    /// processes on every iteration, so a scheduler can implement it
+    /// without allocating a new vector each time.
+    ///
+    /// The default implementation calls `f` for the processes returned
+    /// by [`Scheduler::list`].
+    fn for_each_process(&mut self, f: &mut dyn FnMut(&dyn Process)) {
+        for process in self.list() {
+            f(process);
+        }
+    }
+
+    /// Called after the running process has executed a time unit
+    /// without using its whole timeslice.
+    ///
+    /// Returning `true` preempts the process immediately, and the
+    /// scheduler is informed using [`StopReason::Preempted`]. This
+    /// allows, for example, a process with a higher priority to run
+    /// as soon as it wakes up.
+    ///
+    /// * `remaining` - the number of time units that the process has
+    ///   not used from its quanta.
+    ///
+    /// The default implementation returns `false`.
+    fn preempt(&mut self, _remaining: usize) -> bool {
+        false
+    }
+
+    /// Called after the OS has slept as asked by a
+    /// [`SchedulingDecision::Sleep`] decision, before the next call
+    /// to [`Scheduler::next`].
+    ///
+    /// This allows the time based bookkeeping, like aging or alarms,
+    /// to be done in one place.
+    ///
+    /// * `elapsed` - the number of time units that have passed.
+    ///
+    /// The bundled schedulers advance their clock here. Calling this
+    /// function is optional for them: a sleep that was not reported is
+    /// accounted for at the next call to [`Scheduler::next`].
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use scheduler::{Priority, Scheduler, SchedulingDecision, StopReason, Syscall};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let mut scheduler = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+    /// scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+    /// scheduler.next();
+    /// scheduler.stop(StopReason::Syscall { syscall: Syscall::Sleep(3), remaining: 1 });
+    /// assert_eq!(scheduler.next(), SchedulingDecision::Sleep(NonZeroUsize::new(3).unwrap()));
+    ///
+    /// // without `on_tick`, the next decision accounts for the sleep
+    /// assert!(matches!(scheduler.next(), SchedulingDecision::Run { pid, .. } if pid == 1));
+    /// ```
+    fn on_tick(&mut self, _elapsed: NonZeroUsize) {}
+
+    /// Brings the scheduler back to the state it had when it was
+    /// created, keeping its configuration, so that it can be used
+    /// for another simulation.
+    ///
+    /// Returns `false` if the scheduler cannot be reset. This is
+    /// the default implementation.
+    fn reset(&mut self) -> bool {
+        false
+    }
+
+    /// Returns the scheduler as a [`TunableScheduler`], if its
+    /// parameters can be changed during a simulation.
+    ///
+    /// The default implementation returns [`None`].
+    fn as_tunable(&mut self) -> Option<&mut dyn TunableScheduler> {
+        None
+    }
+
+    /// Returns the scheduler as a [`SeedableScheduler`], if it can
+    /// start with processes that already exist.
+    ///
+    /// The default implementation returns [`None`].
+    fn as_seedable(&mut self) -> Option<&mut dyn SeedableScheduler> {
+        None
+    }
+
+    /// Returns the scheduler as an [`InspectableScheduler`], if its
+    /// queues can be inspected.
+    ///
+    /// The default implementation returns [`None`].
+    fn as_inspectable(&self) -> Option<&dyn InspectableScheduler> {
+        None
+    }
+
+    /// Returns the counters kept by the scheduler since it was created.
+    ///
+    /// The default implementation returns [`None`].
+    fn stats(&self) -> Option<SchedulerStats> {
+        None
+    }
+
+    /// Returns how the scheduler chooses the PIDs of new processes,
+    /// which the processor checks unless it is given another policy.
+    ///
+    /// The default implementation returns [`PidPolicy::Increasing`],
+    /// schedulers that reuse the PIDs of exited processes have to
+    /// return [`PidPolicy::Recycle`].
+    fn pid_policy(&self) -> PidPolicy {
+        PidPolicy::Increasing
+    }
+
+    /// Returns the memory used by the processes of the scheduler since
+    /// it was created.
+    ///
+    /// The default implementation returns [`None`].
+    #[cfg(feature = "memory-usage")]
+    fn memory_usage(&self) -> Option<crate::MemoryUsage> {
+        None
+    }
+}
+
+impl<S: Scheduler + ?Sized> Scheduler for Box<S> {
+    fn next(&mut self) -> SchedulingDecision {
+        (**self).next()
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        (**self).stop(reason)
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        (**self).list()
+    }
+
+    fn for_each_process(&mut self, f: &mut dyn FnMut(&dyn Process)) {
+        (**self).for_each_process(f)
+    }
+
+    fn preempt(&mut self, remaining: usize) -> bool {
+        (**self).preempt(remaining)
+    }
+
+    fn on_tick(&mut self, elapsed: NonZeroUsize) {
+        (**self).on_tick(elapsed)
+    }
+
+    fn reset(&mut self) -> bool {
+        (**self).reset()
+    }
+
+    fn as_tunable(&mut self) -> Option<&mut dyn TunableScheduler> {
+        (**self).as_tunable()
+    }
+
+    fn as_seedable(&mut self) -> Option<&mut dyn SeedableScheduler> {
+        (**self).as_seedable()
+    }
+
+    fn as_inspectable(&self) -> Option<&dyn InspectableScheduler> {
+        (**self).as_inspectable()
+    }
+
+    fn stats(&self) -> Option<SchedulerStats> {
+        (**self).stats()
+    }
+
+    fn pid_policy(&self) -> PidPolicy {
+        (**self).pid_policy()
+    }
+
+    #[cfg(feature = "memory-usage")]
+    fn memory_usage(&self) -> Option<crate::MemoryUsage> {
+        (**self).memory_usage()
+    }
 }
 
 /// The state of a process.
+///
+/// More states might be added in the future, so code outside this
+/// crate that matches on the state needs a wildcard arm.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum ProcessState {
     /// The process is ready to be scheduled.
     Ready,
@@ -224,8 +578,14 @@ pub enum ProcessState {
         /// The event that the process is waiting for.
         ///
         /// If the event is [`None`], the process is sleeping.
-        event: Option<usize>,
+        event: Option<Event>,
     },
+
+    /// The process was suspended and cannot be scheduled until it is resumed.
+    Stopped,
+
+    /// The process has exited, but it is still listed until it is reaped.
+    Zombie,
 }
 
 impl Display for ProcessState {
@@ -240,6 +600,8 @@ impl Display for ProcessState {
                     write!(f, "SLEEP")
                 }
             }
+            ProcessState::Stopped => write!(f, "STOPPED"),
+            ProcessState::Zombie => write!(f, "ZOMBIE"),
         }
     }
 }
@@ -252,15 +614,51 @@ pub trait Process {
     /// Return the PID of the process.
     fn pid(&self) -> Pid;
 
+    /// Return the PID of the process that has created this process,
+    /// or [`None`] if it is not known.
+    ///
+    /// The parent of a process is the process that was running when
+    /// the scheduler received the [`Syscall::Fork`] system call.
+    fn parent(&self) -> Option<Pid> {
+        None
+    }
+
+    /// Return the virtual time when the process was created,
+    /// or [`None`] if the scheduler does not keep track of time.
+    ///
+    /// The virtual time starts at 0 and advances with the time units
+    /// used by the processes and the time the processor sleeps.
+    fn created_at(&self) -> Option<usize> {
+        None
+    }
+
+    /// Return the virtual time when the process was last scheduled, or
+    /// [`None`] if it was never scheduled or the scheduler does not keep
+    /// track of time.
+    fn last_scheduled_at(&self) -> Option<usize> {
+        None
+    }
+
     /// Return the state of the process.
     fn state(&self) -> ProcessState;
 
-    /// Returns process timings as a tuple of (total, syscalls, execution)
-    fn timings(&self) -> (usize, usize, usize);
+    /// Returns the process timings
+    fn timings(&self) -> Timings;
 
     /// Returns the process priority
-    fn priority(&self) -> i8;
+    fn priority(&self) -> Priority;
+
+    /// Returns the scheduling class of the process.
+    ///
+    /// The default implementation returns [`Class::Normal`], for
+    /// schedulers that do not use classes.
+    fn class(&self) -> Class {
+        Class::Normal
+    }
 
     /// Returns details information
-    fn extra(&self) -> String;
+    ///
+    /// Processes without details can return `Cow::Borrowed("")`, so
+    /// listing them does not allocate.
+    fn extra(&self) -> Cow<'_, str>;
 }
@@ -1,30 +1,50 @@
-use std::cmp::Ordering;
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
 use std::num::NonZeroUsize;
-use crate::{Pid, Process, ProcessState, Scheduler, StopReason, SyscallResult};
+use crate::{Class, EventSemantics, Pid, Priority, Process, ProcessState, Scheduler, SchedulerStats, StopReason, SyscallResult, Timings};
 use crate::ProcessState::{Ready, Running, Waiting};
 use crate::SchedulingDecision::{Deadlock, Done, Panic, Run, Sleep};
 use crate::Syscall;
+use crate::tuning::{Parameter, TunableScheduler};
+use crate::inspect::InspectableScheduler;
+use crate::seed::{self, Seed, SeedError, SeedState, SeedableScheduler};
+use crate::allocator::{PidAllocator, PidPolicy};
+use crate::bounds::PriorityBounds;
+use crate::timer::TimerWheel;
+use crate::snapshot::{Decoder, Encoder, SnapshotError, SnapshotScheduler};
 use crate::SyscallResult::{NoRunningProcess, Success};
+use super::{ProcessKey, ProcessTable, WaitQueue};
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 struct PCB {
     pid: usize,
+    parent: Option<usize>,
+    created_at: usize,
+    last_scheduled_at: Option<usize>,
     state: ProcessState,
-    timings: (usize, usize, usize),
+    timings: Cell<Timings>,
+    // the time up to which `timings.total` is up to date
+    since: Cell<usize>,
     priority: i8,
-    sleep: i32,
+    class: Class,
     vruntime: usize,
 }
 
 impl PCB {
-    fn new(pid: usize, state: ProcessState, timings: (usize, usize, usize), priority: i8) -> Self {
+    fn new(pid: usize, parent: Option<usize>, created_at: usize, state: ProcessState, timings: Timings, priority: i8, class: Class) -> Self {
         PCB {
             pid,
+            parent,
+            created_at,
+            last_scheduled_at: None,
             state,
-            timings,
+            timings: Cell::new(timings),
+            since: Cell::new(created_at),
             priority,
-            sleep: 0,
+            class,
             vruntime: 0,
         }
     }
@@ -35,353 +55,499 @@ impl Process for PCB {
         Pid::new(self.pid)
     }
 
+    fn parent(&self) -> Option<Pid> {
+        self.parent.map(Pid::new)
+    }
+
+    fn created_at(&self) -> Option<usize> {
+        Some(self.created_at)
+    }
+
+    fn last_scheduled_at(&self) -> Option<usize> {
+        self.last_scheduled_at
+    }
+
     fn state(&self) -> ProcessState {
         self.state
     }
 
-    fn timings(&self) -> (usize, usize, usize) {
-        self.timings
+    fn timings(&self) -> Timings {
+        self.timings.get()
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::new(self.priority)
+    }
+
+    fn class(&self) -> Class {
+        self.class
     }
 
-    fn priority(&self) -> i8 {
-        self.priority
+    fn extra(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("vruntime={}", self.vruntime))
+    }
+}
+
+impl PCB {
+    // a process spends every time unit while it exists, so its total
+    // time is only brought up to date when it is read
+    fn settle(&self, now: usize) {
+        let mut timings = self.timings.get();
+        timings.total += now - self.since.get();
+        self.timings.set(timings);
+        self.since.set(now);
+    }
+
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.usize(self.pid);
+        encoder.bool(self.parent.is_some());
+        encoder.usize(self.parent.unwrap_or(0));
+        encoder.usize(self.created_at);
+        encoder.bool(self.last_scheduled_at.is_some());
+        encoder.usize(self.last_scheduled_at.unwrap_or(0));
+        encoder.state(self.state);
+        let timings = self.timings.get();
+        encoder.usize(timings.total);
+        encoder.usize(timings.syscall);
+        encoder.usize(timings.execution);
+        encoder.i8(self.priority);
+        encoder.class(self.class);
+        encoder.usize(self.vruntime);
     }
 
-    fn extra(&self) -> String {
-        format!("vruntime={}", self.vruntime)
+    fn restore(decoder: &mut Decoder) -> Result<Self, SnapshotError> {
+        Ok(PCB {
+            pid: decoder.usize()?,
+            parent: match (decoder.bool()?, decoder.usize()?) {
+                (true, pid) => Some(pid),
+                (false, _) => None,
+            },
+            created_at: decoder.usize()?,
+            last_scheduled_at: match (decoder.bool()?, decoder.usize()?) {
+                (true, time) => Some(time),
+                (false, _) => None,
+            },
+            state: decoder.state()?,
+            timings: Cell::new(Timings::new(decoder.usize()?, decoder.usize()?, decoder.usize()?)),
+            // set to the time of the scheduler once it is restored
+            since: Cell::new(0),
+            priority: decoder.i8()?,
+            class: decoder.class()?,
+            vruntime: decoder.usize()?,
+        })
+    }
+}
+
+// the position of a process in the run queue
+type Position = (Reverse<Class>, usize, usize);
+
+impl PCB {
+    fn position(&self) -> Position {
+        (Reverse(self.class), self.vruntime, self.pid)
     }
 }
 
-impl PartialOrd for PCB {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.vruntime == other.vruntime {
-            Some(self.pid.cmp(&other.pid))
+// the ready processes, ordered by class and then by vruntime, like the red-black tree of the kernel
+struct RunQueue {
+    // the process that continues its timeslice, it runs before the processes of the same or a lower class
+    resumed: Option<(Position, ProcessKey)>,
+    tree: BTreeMap<Position, ProcessKey>,
+}
+
+impl RunQueue {
+    fn new() -> Self {
+        RunQueue {
+            resumed: None,
+            tree: BTreeMap::new(),
         }
-        else {
-            Some(self.vruntime.cmp(&other.vruntime))
+    }
+
+    fn push(&mut self, key: ProcessKey, process: &PCB) {
+        self.tree.insert(process.position(), key);
+    }
+
+    fn resume(&mut self, key: ProcessKey, process: &PCB) {
+        if let Some((position, resumed)) = self.resumed.replace((process.position(), key)) {
+            self.tree.insert(position, resumed);
         }
     }
+
+    // returns the next process and whether it continues its timeslice
+    fn pop(&mut self) -> Option<(ProcessKey, bool)> {
+        match self.resumed.take() {
+            Some((position, resumed)) if self.tree.first_key_value().is_some_and(|(first, _)| first.0.0 > position.0.0) => {
+                self.tree.insert(position, resumed);
+                self.tree.pop_first().map(|(_, key)| (key, false))
+            }
+            Some((_, resumed)) => Some((resumed, true)),
+            None => self.tree.pop_first().map(|(_, key)| (key, false)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.tree.len() + self.resumed.iter().len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn iter(&self) -> impl Iterator<Item = ProcessKey> + '_ {
+        self.resumed.iter().map(|&(_, key)| key).chain(self.tree.values().copied())
+    }
+}
+
+// the vruntimes of the processes that are not running, with the number of processes that have each one,
+// a process that is not running keeps its vruntime, so the minimum is the first one
+struct Vruntimes {
+    counts: BTreeMap<usize, usize>,
+}
+
+impl Vruntimes {
+    fn new() -> Self {
+        Vruntimes {
+            counts: BTreeMap::new(),
+        }
+    }
+
+    fn insert(&mut self, vruntime: usize) {
+        *self.counts.entry(vruntime).or_default() += 1;
+    }
+
+    fn remove(&mut self, vruntime: usize) {
+        if let Entry::Occupied(mut count) = self.counts.entry(vruntime) {
+            *count.get_mut() -= 1;
+            if *count.get() == 0 {
+                count.remove();
+            }
+        }
+    }
+
+    fn first(&self) -> Option<usize> {
+        self.counts.first_key_value().map(|(&vruntime, _)| vruntime)
+    }
 }
 
 pub struct CFS {
-    ready_queue: VecDeque<PCB>,
-    waiting_queue: Vec<PCB>,
-    current_process: Option<PCB>,
-    next_pid: usize,
+    processes: ProcessTable<PCB>,
+    ready_queue: RunQueue,
+    waiting_queue: WaitQueue,
+    sleeping_queue: TimerWheel<ProcessKey>,
+    current_process: Option<ProcessKey>,
+    pids: PidAllocator,
+    priorities: PriorityBounds,
     timeslice: NonZeroUsize,
     minimum_remaining_timeslice: usize,
     panic: bool,
     remaining: usize,
-    sleep: i32,
     cpu_time: NonZeroUsize,
     minimum_vruntime: usize,
+    vruntimes: Vruntimes,
+    stats: SchedulerStats,
+    last_pid: Option<usize>,
+    clock: usize,
+    // the sleep of the last decision, until `on_tick` reports it
+    sleep: usize,
 }
 
 impl CFS {
-    pub fn new(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize) -> Self {
+    pub fn new(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize, pid_policy: PidPolicy, priorities: PriorityBounds, events: EventSemantics) -> Self {
         CFS {
-            ready_queue: VecDeque::new(),
-            waiting_queue: Vec::new(),
+            processes: ProcessTable::new(),
+            ready_queue: RunQueue::new(),
+            waiting_queue: WaitQueue::new(events),
+            sleeping_queue: TimerWheel::new(),
             current_process: None,
-            next_pid: 1,
+            pids: PidAllocator::new(pid_policy),
+            priorities,
             timeslice: cpu_time,
             minimum_remaining_timeslice,
             panic: false,
             remaining: cpu_time.get(),
-            sleep: 0,
             cpu_time,
             minimum_vruntime: 0,
+            vruntimes: Vruntimes::new(),
+            stats: SchedulerStats::default(),
+            last_pid: None,
+            clock: 0,
+            sleep: 0,
         }
     }
 
     pub fn wake(&mut self) {
-        self.waiting_queue.retain(|process| {
-            if let Waiting {event: Some(_)} = process.state {
-                true
-            }
-            else if process.sleep <= 0 {
-                let mut ready_process = process.clone();
-                ready_process.state = Ready;
-                self.ready_queue.push_back(ready_process.clone());
-                false
-            }
-            else {
-                true
-            }
-        });
+        while let Some((_, key)) = self.sleeping_queue.pop_due(self.clock) {
+            self.processes[key].state = Ready;
+            self.ready_queue.push(key, &self.processes[key]);
+            self.stats.wakeups += 1;
+            #[cfg(feature = "tracing")]
+            tracing::trace!(pid = self.processes[key].pid, "woken up");
+        }
     }
 
-    fn update_ready_timings(&mut self, remaining: usize) {
-        for waiting_process in &mut self.ready_queue {
-            waiting_process.timings.0 += self.remaining - remaining;
+    fn switch_to(&mut self, pid: Pid) {
+        if self.last_pid.is_some_and(|last_pid| last_pid != pid.get()) {
+            self.stats.context_switches += 1;
         }
+        self.last_pid = Some(pid.get());
     }
 
-    fn update_waiting_timings(&mut self, remaining: usize) {
-        for waiting_process in &mut self.waiting_queue {
-            waiting_process.timings.0 += self.remaining - remaining;
-            if let Waiting { event: Some(_) } = waiting_process.state {
-                continue;
-            }
-            waiting_process.sleep -= (self.remaining - remaining) as i32;
-        }
+    fn settled(&self, key: ProcessKey) -> &PCB {
+        let process = &self.processes[key];
+        process.settle(self.clock);
+        process
+    }
+
+    // the processes in the order in which they are listed
+    fn processes(&self) -> impl Iterator<Item = &PCB> {
+        self.current_process.into_iter()
+            .chain(self.ready_queue.iter())
+            .chain(self.waiting_queue.iter().copied())
+            .chain(self.sleeping_queue.values().copied())
+            .map(|key| self.settled(key))
     }
 
-    fn reschedule_process(&mut self, remaining: usize, process: PCB) {
-        if remaining >= self.minimum_remaining_timeslice {
-            // partial_cmp always returns some value
-            self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
-            self.ready_queue.push_front(process.clone());
+    fn reschedule_process(&mut self, remaining: usize, key: ProcessKey) {
+        // a process cannot be scheduled again for 0 time units
+        if remaining >= self.minimum_remaining_timeslice.max(1) {
+            self.ready_queue.resume(key, &self.processes[key]);
             self.remaining = remaining;
         } else {
-            self.ready_queue.push_back(process.clone());
-            // partial_cmp always returns some value
-            self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
+            self.ready_queue.push(key, &self.processes[key]);
             self.remaining = self.timeslice.get();
         }
     }
 
     fn update_minimum_vruntime(&mut self, current: usize) {
-        let mut all_vruntime: Vec<usize> = self.ready_queue.iter().map(|process| process.vruntime)
-            .chain(self.waiting_queue.iter().map(|process| process.vruntime))
-            .collect();
+        self.minimum_vruntime = self.vruntimes.first().map_or(current, |first| first.min(current));
+    }
 
-        all_vruntime.push(current);
-        
-        if let Some(min) = all_vruntime.iter().cloned().min() {
-            self.minimum_vruntime = min;
-        }
+    // the process has run for `used` time units and stops running
+    fn charge(&mut self, key: ProcessKey, used: usize) {
+        let process = &mut self.processes[key];
+        process.vruntime += used;
+        self.vruntimes.insert(process.vruntime);
     }
 
     fn update_timeslice(&mut self, process_cnt: usize) {
-        if let Some(new_timeslice) = NonZeroUsize::new(self.cpu_time.get() / process_cnt) {
-            self.timeslice = new_timeslice;
-        }
-        else {
-            self.timeslice = self.cpu_time;
-        }
+        // with more processes than cpu time units, every process gets the minimum timeslice
+        self.timeslice = NonZeroUsize::new(self.cpu_time.get() / process_cnt).unwrap_or(NonZeroUsize::MIN);
     }
 }
 
 impl Scheduler for CFS {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret))]
     fn next(&mut self) -> crate::SchedulingDecision {
         if self.panic {
             return Panic;
         }
 
-        self.waiting_queue.sort_by_key(|process| process.sleep);
-
-        if self.sleep != 0 {
-            // partial_cmp always returns some value
-            self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-            let amount = self.sleep;
-            self.sleep = 0;
-            for process in self.waiting_queue.iter_mut() {
-                process.timings.0 += amount as usize;
-                if let Waiting {event: Some(_)} = process.state {
-                    continue;
-                }
-                process.sleep -= amount;
-            }
+        // the processor has slept even if it did not call `on_tick`
+        if let Some(elapsed) = NonZeroUsize::new(self.sleep) {
+            self.on_tick(elapsed);
         }
 
         self.wake();
 
-        if self.current_process == None && self.ready_queue.is_empty() && !self.waiting_queue.is_empty() {
-            let mut amount = 0;
-            for process in &self.waiting_queue {
-                if let Waiting {event: Some(_)} = process.state {
-                    continue;
-                }
-                amount = process.sleep;
-                break;
-            }
-            if amount == 0 {
+        if self.current_process == None && self.ready_queue.is_empty() && !(self.waiting_queue.is_empty() && self.sleeping_queue.is_empty()) {
+            let Some(wake_at) = self.sleeping_queue.next_wake() else {
                 return Deadlock;
-            }
+            };
+            let amount = wake_at - self.clock;
             self.sleep = amount;
 
-            // amount can't be 0, case handled above
-            return Sleep(NonZeroUsize::new(amount as usize).unwrap());
+            // amount can't be 0, the processes that are due have been woken up,
+            // the clock advances in `on_tick`, once the processor has slept
+            return Sleep(NonZeroUsize::new(amount).unwrap());
         }
 
-        if !self.ready_queue.is_empty() {
-            // ready_queue has at least 1 process
-            let mut process = self.ready_queue.pop_front().unwrap();
+        if let Some((key, resumed)) = self.ready_queue.pop() {
+            if !resumed {
+                // only the process that continues its timeslice keeps the rest of it
+                self.remaining = self.timeslice.get();
+            }
+            let process = &mut self.processes[key];
             process.state = Running;
-            self.current_process = Some(process.clone());
+            process.last_scheduled_at = Some(self.clock);
             let pid = process.pid();
+            self.vruntimes.remove(process.vruntime);
+            self.current_process = Some(key);
             self.remaining = self.remaining.min(self.timeslice.get());
             // self.remaining can't be 0 (a process cannot have 0 remaining timeslice)
             let timeslice = NonZeroUsize::new(self.remaining).unwrap();
             
+            self.switch_to(pid);
             return Run {pid, timeslice};
         }
 
-        if let Some(process) = self.current_process {
+        if let Some(key) = self.current_process {
+            let process = &mut self.processes[key];
+            process.last_scheduled_at = Some(self.clock);
             let pid = process.pid();
             self.remaining = self.remaining.min(self.timeslice.get());
             // self.remaining can't be 0 (a process cannot have 0 remaining timeslice)
             let timeslice = NonZeroUsize::new(self.remaining).unwrap();
+            self.switch_to(pid);
             return Run {pid, timeslice};
         }
 
         Done
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret))]
     fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        if self.current_process.is_some() {
+            self.clock += match reason {
+                StopReason::Syscall { remaining, .. } | StopReason::Preempted { remaining } => self.remaining - remaining,
+                StopReason::Expired => self.remaining,
+            };
+        }
+
         return match reason {
             StopReason::Syscall { syscall, remaining } => {
-                if self.current_process == None && self.next_pid != 1 {
+                if self.current_process == None && !self.pids.is_fresh() {
                     return NoRunningProcess;
                 }
 
                 match syscall {
                     Syscall::Fork(priority) => {
-                        let mut process = PCB::new(self.next_pid, Ready, (0, 0, 0), priority);
-                        self.next_pid += 1;
-
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
+                        let parent = self.current_process.map(|key| self.processes[key].pid);
+                        let class = self.current_process.map_or(Class::Normal, |key| self.processes[key].class);
+                        // the process is not created if its priority is rejected
+                        let child = self.priorities.apply(priority)
+                            .map(|priority| self.processes.insert(PCB::new(self.pids.allocate().get(), parent, self.clock, Ready, Timings::default(), priority.get(), class)));
 
                         self.wake();
 
-                        if process.pid == 1 {
-                            self.ready_queue.push_back(process.clone());
+                        if let Some(child) = child.filter(|&child| self.processes[child].pid == 1) {
+                            self.ready_queue.push(child, &self.processes[child]);
+                            self.vruntimes.insert(self.processes[child].vruntime);
                         }
 
-                        if let Some(mut current_process) = self.current_process {
+                        if let Some(key) = self.current_process {
                             self.current_process = None;
+                            let current_process = &mut self.processes[key];
                             current_process.state = Ready;
-                            current_process.timings.2 += self.remaining - remaining - 1;
-                            current_process.timings.1 += 1;
-                            current_process.timings.0 += self.remaining - remaining;
-                            current_process.vruntime += self.remaining - remaining;
-
-                            self.update_minimum_vruntime(current_process.vruntime);
-                            process.vruntime = self.minimum_vruntime;
-                            self.ready_queue.push_back(process.clone());
+                            let timings = current_process.timings.get_mut();
+                            timings.execution += self.remaining - remaining - 1;
+                            timings.syscall += 1;
+                            self.charge(key, self.remaining - remaining);
+
+                            self.update_minimum_vruntime(self.processes[key].vruntime);
+                            if let Some(child) = child {
+                                self.processes[child].vruntime = self.minimum_vruntime;
+                                self.ready_queue.push(child, &self.processes[child]);
+                                self.vruntimes.insert(self.minimum_vruntime);
+                            }
 
                             self.update_timeslice(self.ready_queue.len() + 1);
 
-                            self.reschedule_process(self.timeslice.get().min(remaining), current_process);
+                            self.reschedule_process(self.timeslice.get().min(remaining), key);
+                        }
+                        match child {
+                            Some(child) => SyscallResult::Pid(self.processes[child].pid()),
+                            None => SyscallResult::InvalidPriority,
                         }
-                        SyscallResult::Pid(process.pid().clone())
                     }
                     Syscall::Sleep(amount) => {
                         // current_process can't be none (case handled above)
-                        let mut process = self.current_process.unwrap();
+                        let key = self.current_process.unwrap();
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
                         self.wake();
 
                         if self.ready_queue.len() != 0 {
                             self.update_timeslice(self.ready_queue.len());
                         }
 
-                        let event = None;
-                        process.state = Waiting { event };
-                        process.sleep = amount as i32;
-                        process.timings.2 += self.remaining - remaining - 1;
-                        process.timings.1 += 1;
-                        process.timings.0 += self.remaining - remaining;
-                        process.vruntime += self.remaining - remaining;
-
-                        self.waiting_queue.push(process.clone());
+                        let process = &mut self.processes[key];
+                        let timings = process.timings.get_mut();
+                        timings.execution += self.remaining - remaining - 1;
+                        timings.syscall += 1;
+                        self.charge(key, self.remaining - remaining);
+
+                        if amount == 0 {
+                            // a sleep without time yields, the process is ready in the order of its vruntime
+                            self.processes[key].state = Ready;
+                            self.ready_queue.push(key, &self.processes[key]);
+                        } else {
+                            self.processes[key].state = Waiting { event: None };
+                            self.sleeping_queue.push(self.clock + amount, key);
+                        }
 
                         self.remaining = self.timeslice.get();
 
-                        // partial_cmp always returns some value
-                        self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
-
                         Success
                     }
                     Syscall::Wait(event) => {
                         // current_process can't be none (case handled above)
-                        let mut process = self.current_process.unwrap();
+                        let key = self.current_process.unwrap();
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
                         self.wake();
 
-                        if self.ready_queue.len() != 0 {
+                        // the event was signaled before, the process continues like after a signal
+                        let signaled = self.waiting_queue.consume(event);
+                        if signaled {
+                            self.update_timeslice(self.ready_queue.len() + 1);
+                        } else if self.ready_queue.len() != 0 {
                             self.update_timeslice(self.ready_queue.len());
                         }
 
-                        process.state = Waiting { event: Some(event) };
-                        process.timings.2 += self.remaining - remaining - 1;
-                        process.timings.1 += 1;
-                        process.timings.0 += self.remaining - remaining;
-                        process.vruntime += self.remaining - remaining;
-
-                        self.waiting_queue.push(process.clone());
-
-                        self.remaining = self.timeslice.get();
-
-                        // partial_cmp always returns some value
-                        self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
+                        let process = &mut self.processes[key];
+                        let timings = process.timings.get_mut();
+                        timings.execution += self.remaining - remaining - 1;
+                        timings.syscall += 1;
+                        self.charge(key, self.remaining - remaining);
+
+                        if signaled {
+                            self.processes[key].state = Ready;
+                            self.reschedule_process(remaining, key);
+                        } else {
+                            self.processes[key].state = Waiting { event: Some(event) };
+                            self.waiting_queue.push(event, key);
+                            self.remaining = self.timeslice.get();
+                        }
 
                         Success
                     }
                     Syscall::Signal(signal) => {
                         // current_process can't be none (case handled above)
-                        let mut process = self.current_process.unwrap();
+                        let key = self.current_process.unwrap();
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
-                        self.waiting_queue.retain(|process| {
-                            if let Waiting { event: Some(event) } = process.state {
-                                if event == signal {
-                                    let mut ready_process = process.clone();
-                                    ready_process.state = Ready;
-                                    self.ready_queue.push_back(ready_process.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        });
+                        for key in self.waiting_queue.take(signal) {
+                            let process = &mut self.processes[key];
+                            process.state = Ready;
+                            self.ready_queue.push(key, process);
+                            self.stats.wakeups += 1;
+                            #[cfg(feature = "tracing")]
+                            tracing::trace!(pid = process.pid, "woken up");
+                        }
 
                         self.wake();
 
                         self.update_timeslice(self.ready_queue.len() + 1);
 
+                        let process = &mut self.processes[key];
                         process.state = Ready;
-                        process.timings.2 += self.remaining - remaining - 1;
-                        process.timings.1 += 1;
-                        process.timings.0 += self.remaining - remaining;
-                        process.vruntime += self.remaining - remaining;
+                        let timings = process.timings.get_mut();
+                        timings.execution += self.remaining - remaining - 1;
+                        timings.syscall += 1;
+                        self.charge(key, self.remaining - remaining);
 
-                        self.reschedule_process(remaining, process);
+                        self.reschedule_process(remaining, key);
 
                         Success
                     }
                     Syscall::Exit => {
                         // current_process can't be none (case handled above)
-                        let process = self.current_process.unwrap();
-                        if process.pid == 1 && (!self.ready_queue.is_empty() || !self.waiting_queue.is_empty()) {
+                        let process = self.processes.remove(self.current_process.unwrap()).unwrap();
+                        self.pids.release(process.pid());
+                        if process.pid == 1 && (!self.ready_queue.is_empty() || !self.waiting_queue.is_empty() || !self.sleeping_queue.is_empty()) {
                             self.panic = true;
                         }
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
                         self.wake();
 
                         if self.ready_queue.len() != 0 {
@@ -390,60 +556,283 @@ impl Scheduler for CFS {
 
                         self.remaining = self.timeslice.get();
 
-                        // partial_cmp always returns some value
-                        self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
-
                         Success
                     }
                 }
             }
-            StopReason::Expired => {
-                // current_process can't be none if the process expired
-                let mut process = self.current_process.unwrap();
-                process.state = Ready;
-                process.timings.2 += self.remaining;
-                process.timings.0 += self.remaining;
-                process.vruntime += self.remaining;
-
-                for waiting_process in &mut self.ready_queue {
-                    waiting_process.timings.0 += self.remaining;
-                }
-
-                for waiting_process in &mut self.waiting_queue {
-                    waiting_process.timings.0 += self.remaining;
-                    if let Waiting { event: Some(_) } = waiting_process.state {
-                        continue;
-                    }
-                    waiting_process.sleep -= self.remaining as i32;
+            StopReason::Expired | StopReason::Preempted { .. } => {
+                let used = match reason {
+                    StopReason::Preempted { remaining } => self.remaining - remaining,
+                    _ => self.remaining,
+                };
+                if reason == StopReason::Expired {
+                    self.stats.preemptions += 1;
                 }
+                // current_process can't be none if the process was preempted
+                let key = self.current_process.unwrap();
+                let process = &mut self.processes[key];
+                process.state = Ready;
+                process.timings.get_mut().execution += used;
+                self.charge(key, used);
 
                 self.wake();
 
                 self.update_timeslice(self.ready_queue.len() + 1);
 
                 self.remaining = self.timeslice.get();
-                self.ready_queue.push_back(process.clone());
+                self.ready_queue.push(key, &self.processes[key]);
                 self.current_process = None;
 
-                // partial_cmp always returns some value
-                self.ready_queue.make_contiguous().sort_by(|a, b| a.partial_cmp(b).unwrap());
-
                 Success
             }
         }
     }
 
     fn list(&mut self) -> Vec<&dyn Process> {
-        let mut vec: Vec<&dyn Process> = Vec::new();
-        if let Some(ref process) = self.current_process {
-            vec.push(process);
+        self.processes().map(|process| process as &dyn Process).collect()
+    }
+
+    fn for_each_process(&mut self, f: &mut dyn FnMut(&dyn Process)) {
+        for process in self.processes() {
+            f(process);
+        }
+    }
+
+    fn on_tick(&mut self, elapsed: NonZeroUsize) {
+        // the processes have spent the time the processor slept
+        self.sleep = 0;
+        self.stats.idle += elapsed.get();
+        self.clock += elapsed.get();
+    }
+
+    fn reset(&mut self) -> bool {
+        *self = CFS::new(self.cpu_time, self.minimum_remaining_timeslice, self.pids.policy(), self.priorities, self.waiting_queue.semantics());
+        true
+    }
+
+    fn as_tunable(&mut self) -> Option<&mut dyn TunableScheduler> {
+        Some(self)
+    }
+
+    fn as_seedable(&mut self) -> Option<&mut dyn SeedableScheduler> {
+        Some(self)
+    }
+
+    fn as_inspectable(&self) -> Option<&dyn InspectableScheduler> {
+        Some(self)
+    }
+
+    fn stats(&self) -> Option<SchedulerStats> {
+        Some(self.stats)
+    }
+
+    fn pid_policy(&self) -> PidPolicy {
+        self.pids.policy()
+    }
+
+    #[cfg(feature = "memory-usage")]
+    fn memory_usage(&self) -> Option<crate::MemoryUsage> {
+        Some(self.processes.memory_usage())
+    }
+}
+
+impl InspectableScheduler for CFS {
+    fn current(&self) -> Option<&dyn Process> {
+        self.current_process.map(|key| self.settled(key) as &dyn Process)
+    }
+
+    fn ready(&self) -> Vec<&dyn Process> {
+        // the processes of a higher class are scheduled first
+        let mut ready: Vec<&PCB> = self.ready_queue.iter().map(|key| self.settled(key)).collect();
+        ready.sort_by_key(|process| Reverse(process.class));
+        ready.into_iter().map(|process| process as &dyn Process).collect()
+    }
+
+    fn process(&self, pid: Pid) -> Option<&dyn Process> {
+        self.processes.find(pid).map(|process| {
+            process.settle(self.clock);
+            process as &dyn Process
+        })
+    }
+
+    fn waiting(&self) -> Vec<&dyn Process> {
+        self.waiting_queue.iter()
+            .chain(self.sleeping_queue.values())
+            .map(|&key| self.settled(key) as &dyn Process)
+            .collect()
+    }
+}
+
+impl SeedableScheduler for CFS {
+    fn seed(&mut self, processes: &[Seed]) -> Result<(), SeedError> {
+        if !self.pids.is_fresh() {
+            return Err(SeedError::Started);
+        }
+        seed::check(processes, &self.priorities)?;
+
+        // reserving the PIDs in order never fails, the PIDs are different
+        let mut pids = processes.iter().map(|seed| seed.pid).collect::<Vec<_>>();
+        pids.sort();
+        for pid in pids {
+            self.pids.reserve(pid);
+        }
+
+        for seed in processes {
+            let parent = seed.parent.map(|parent| parent.get());
+            // the priorities have been checked above
+            let priority = self.priorities.apply(seed.priority).unwrap();
+            let mut process = PCB::new(seed.pid.get(), parent, self.clock, Ready, Timings::default(), priority.get(), seed.class);
+            self.vruntimes.insert(process.vruntime);
+            match seed.state {
+                SeedState::Ready => {
+                    let key = self.processes.insert(process);
+                    self.ready_queue.push(key, &self.processes[key]);
+                }
+                SeedState::Waiting(event) => {
+                    process.state = Waiting { event: Some(event) };
+                    self.waiting_queue.push(event, self.processes.insert(process));
+                }
+                SeedState::Sleeping(amount) => {
+                    process.state = Waiting { event: None };
+                    self.sleeping_queue.push(self.clock + amount.get(), self.processes.insert(process));
+                }
+            }
+        }
+        if !self.ready_queue.is_empty() {
+            self.update_timeslice(self.ready_queue.len());
+        }
+        Ok(())
+    }
+}
+
+impl TunableScheduler for CFS {
+    fn tune(&mut self, parameter: Parameter) -> bool {
+        match parameter {
+            Parameter::CpuTime(cpu_time) => self.cpu_time = cpu_time,
+            Parameter::MinimumRemainingTimeslice(minimum_remaining_timeslice) => {
+                self.minimum_remaining_timeslice = minimum_remaining_timeslice;
+            }
+            Parameter::Timeslice(_) => return false,
+        }
+        true
+    }
+}
+
+const SNAPSHOT_TAG: &[u8] = b"cfs";
+
+impl SnapshotScheduler for CFS {
+    fn save(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(SNAPSHOT_TAG);
+        encoder.usize(self.ready_queue.len());
+        for key in self.ready_queue.iter() {
+            self.settled(key).save(&mut encoder);
+        }
+        encoder.usize(self.waiting_queue.len());
+        for &key in self.waiting_queue.iter() {
+            self.settled(key).save(&mut encoder);
+        }
+        self.waiting_queue.save_pending(&mut encoder);
+        encoder.usize(self.sleeping_queue.len());
+        for (wake_at, &key) in self.sleeping_queue.iter() {
+            encoder.usize(wake_at);
+            self.settled(key).save(&mut encoder);
+        }
+        encoder.bool(self.current_process.is_some());
+        if let Some(key) = self.current_process {
+            self.settled(key).save(&mut encoder);
+        }
+        self.pids.save(&mut encoder);
+        self.priorities.save(&mut encoder);
+        encoder.usize(self.timeslice.get());
+        encoder.usize(self.minimum_remaining_timeslice);
+        encoder.bool(self.panic);
+        encoder.usize(self.remaining);
+        encoder.usize(self.cpu_time.get());
+        encoder.usize(self.minimum_vruntime);
+        encoder.usize(self.stats.context_switches);
+        encoder.usize(self.stats.preemptions);
+        encoder.usize(self.stats.idle);
+        encoder.usize(self.stats.wakeups);
+        encoder.bool(self.last_pid.is_some());
+        encoder.usize(self.last_pid.unwrap_or(0));
+        encoder.usize(self.clock);
+        encoder.usize(self.sleep);
+        encoder.bool(self.ready_queue.resumed.is_some());
+        encoder.finish()
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<(), SnapshotError> {
+        let mut decoder = Decoder::new(snapshot, SNAPSHOT_TAG)?;
+        let mut processes = ProcessTable::new();
+        let ready_processes: Vec<ProcessKey> = (0..decoder.usize()?)
+            .map(|_| PCB::restore(&mut decoder).map(|process| processes.insert(process)))
+            .collect::<Result<_, _>>()?;
+        let mut waiting_queue = WaitQueue::new(EventSemantics::Transient);
+        for _ in 0..decoder.usize()? {
+            let process = PCB::restore(&mut decoder)?;
+            let Waiting { event: Some(event) } = process.state else {
+                return Err(SnapshotError::new("a waiting process does not wait for an event"));
+            };
+            waiting_queue.push(event, processes.insert(process));
+        }
+        waiting_queue.restore_pending(&mut decoder)?;
+        let mut sleeping_queue = TimerWheel::new();
+        for _ in 0..decoder.usize()? {
+            let wake_at = decoder.usize()?;
+            sleeping_queue.push(wake_at, processes.insert(PCB::restore(&mut decoder)?));
+        }
+        let current_process = match decoder.bool()? {
+            true => Some(processes.insert(PCB::restore(&mut decoder)?)),
+            false => None,
+        };
+        let mut scheduler = CFS {
+            processes,
+            ready_queue: RunQueue::new(),
+            waiting_queue,
+            sleeping_queue,
+            current_process,
+            pids: PidAllocator::restore(&mut decoder)?,
+            priorities: PriorityBounds::restore(&mut decoder)?,
+            timeslice: decoder.non_zero()?,
+            minimum_remaining_timeslice: decoder.usize()?,
+            panic: decoder.bool()?,
+            remaining: decoder.usize()?,
+            cpu_time: decoder.non_zero()?,
+            minimum_vruntime: decoder.usize()?,
+            vruntimes: Vruntimes::new(),
+            stats: SchedulerStats {
+                context_switches: decoder.usize()?,
+                preemptions: decoder.usize()?,
+                idle: decoder.usize()?,
+                wakeups: decoder.usize()?,
+            },
+            last_pid: match (decoder.bool()?, decoder.usize()?) {
+                (true, pid) => Some(pid),
+                (false, _) => None,
+            },
+            clock: decoder.usize()?,
+            sleep: decoder.usize()?,
+        };
+        // the process that continues its timeslice is saved first
+        let mut ready_processes = ready_processes.into_iter();
+        if decoder.bool()? {
+            if let Some(key) = ready_processes.next() {
+                scheduler.ready_queue.resume(key, &scheduler.processes[key]);
+            }
+        }
+        for key in ready_processes {
+            scheduler.ready_queue.push(key, &scheduler.processes[key]);
         }
-        for process in &self.ready_queue {
-            vec.push(process)
+        for key in scheduler.ready_queue.iter().chain(scheduler.waiting_queue.iter().copied()).chain(scheduler.sleeping_queue.values().copied()) {
+            scheduler.vruntimes.insert(scheduler.processes[key].vruntime);
         }
-        for process in &self.waiting_queue {
-            vec.push(process);
+        decoder.finish()?;
+        // the total times were up to date when the snapshot was taken
+        let now = scheduler.clock;
+        for process in scheduler.processes.values() {
+            process.since.set(now);
         }
-        vec
+        *self = scheduler;
+        Ok(())
     }
 }
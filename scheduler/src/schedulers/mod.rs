@@ -17,3 +17,12 @@ pub use priority_queue::PriorityQueue;
 
 mod cfs;
 pub use cfs::CFS;
+
+mod table;
+use table::{ProcessKey, ProcessTable};
+
+mod queue;
+use queue::KeyDeque;
+
+mod wait;
+use wait::WaitQueue;
@@ -0,0 +1,83 @@
+#[cfg(not(feature = "inline-queues"))]
+use std::collections::VecDeque;
+#[cfg(feature = "inline-queues")]
+use smallvec::SmallVec;
+use super::ProcessKey;
+
+/// The number of processes that a queue stores without allocating
+/// memory, with the `inline-queues` feature.
+#[cfg(feature = "inline-queues")]
+pub(super) const INLINE_PROCESSES: usize = 16;
+
+/// A queue of processes that are added and removed at both ends.
+#[cfg(not(feature = "inline-queues"))]
+pub(super) type KeyDeque = VecDeque<ProcessKey>;
+
+/// A queue of processes that are added and removed at both ends.
+#[cfg(feature = "inline-queues")]
+pub(super) type KeyDeque = InlineDeque;
+
+/// The subset of [`VecDeque`](std::collections::VecDeque) used by the
+/// schedulers, stored inline up to [`INLINE_PROCESSES`] processes.
+///
+/// Adding a process at the front moves the others, which costs less
+/// than an allocation for the small queues that it is meant for.
+#[cfg(feature = "inline-queues")]
+#[derive(Default)]
+pub(super) struct InlineDeque {
+    keys: SmallVec<[ProcessKey; INLINE_PROCESSES]>,
+}
+
+#[cfg(feature = "inline-queues")]
+impl InlineDeque {
+    pub(super) fn new() -> Self {
+        InlineDeque::default()
+    }
+
+    pub(super) fn push_front(&mut self, key: ProcessKey) {
+        self.keys.insert(0, key);
+    }
+
+    pub(super) fn push_back(&mut self, key: ProcessKey) {
+        self.keys.push(key);
+    }
+
+    pub(super) fn remove(&mut self, index: usize) -> Option<ProcessKey> {
+        (index < self.keys.len()).then(|| self.keys.remove(index))
+    }
+
+    pub(super) fn make_contiguous(&mut self) -> &mut [ProcessKey] {
+        &mut self.keys
+    }
+
+    pub(super) fn iter(&self) -> std::slice::Iter<'_, ProcessKey> {
+        self.keys.iter()
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(feature = "inline-queues")]
+impl FromIterator<ProcessKey> for InlineDeque {
+    fn from_iter<I: IntoIterator<Item = ProcessKey>>(keys: I) -> Self {
+        InlineDeque {
+            keys: keys.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "inline-queues")]
+impl<'a> IntoIterator for &'a InlineDeque {
+    type Item = &'a ProcessKey;
+    type IntoIter = std::slice::Iter<'a, ProcessKey>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.iter()
+    }
+}
@@ -1,28 +1,47 @@
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cmp::Reverse;
 use std::num::NonZeroUsize;
-use crate::{Pid, Process, ProcessState, Scheduler, StopReason, SyscallResult};
+use crate::{Class, EventSemantics, Pid, Priority, Process, ProcessState, Scheduler, SchedulerStats, StopReason, SyscallResult, Timings};
 use crate::ProcessState::{Ready, Running, Waiting};
 use crate::SchedulingDecision::{Deadlock, Done, Panic, Run, Sleep};
 use crate::Syscall;
+use crate::tuning::{Parameter, TunableScheduler};
+use crate::inspect::InspectableScheduler;
+use crate::seed::{self, Seed, SeedError, SeedState, SeedableScheduler};
+use crate::allocator::{PidAllocator, PidPolicy};
+use crate::bounds::PriorityBounds;
+use crate::timer::TimerWheel;
+use crate::snapshot::{Decoder, Encoder, SnapshotError, SnapshotScheduler};
 use crate::SyscallResult::{NoRunningProcess, Success};
+use super::{KeyDeque, ProcessKey, ProcessTable, WaitQueue};
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 struct PCB {
     pid: usize,
+    parent: Option<usize>,
+    created_at: usize,
+    last_scheduled_at: Option<usize>,
     state: ProcessState,
-    timings: (usize, usize, usize),
+    timings: Cell<Timings>,
+    // the time up to which `timings.total` is up to date
+    since: Cell<usize>,
     priority: i8,
-    sleep: i32,
+    class: Class,
 }
 
 impl PCB {
-    fn new(pid: usize, state: ProcessState, timings: (usize, usize, usize), priority: i8) -> Self {
+    fn new(pid: usize, parent: Option<usize>, created_at: usize, state: ProcessState, timings: Timings, priority: i8, class: Class) -> Self {
         PCB {
             pid,
+            parent,
+            created_at,
+            last_scheduled_at: None,
             state,
-            timings,
+            timings: Cell::new(timings),
+            since: Cell::new(created_at),
             priority,
-            sleep: 0,
+            class,
         }
     }
 }
@@ -32,203 +51,307 @@ impl Process for PCB {
         Pid::new(self.pid)
     }
 
+    fn parent(&self) -> Option<Pid> {
+        self.parent.map(Pid::new)
+    }
+
+    fn created_at(&self) -> Option<usize> {
+        Some(self.created_at)
+    }
+
+    fn last_scheduled_at(&self) -> Option<usize> {
+        self.last_scheduled_at
+    }
+
     fn state(&self) -> ProcessState {
         self.state
     }
 
-    fn timings(&self) -> (usize, usize, usize) {
-        self.timings
+    fn timings(&self) -> Timings {
+        self.timings.get()
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::new(self.priority)
     }
 
-    fn priority(&self) -> i8 {
-        self.priority
+    fn class(&self) -> Class {
+        self.class
     }
 
-    fn extra(&self) -> String {
-        String::from("")
+    fn extra(&self) -> Cow<'_, str> {
+        Cow::Borrowed("")
+    }
+}
+
+impl PCB {
+    // a process spends every time unit while it exists, so its total
+    // time is only brought up to date when it is read
+    fn settle(&self, now: usize) {
+        let mut timings = self.timings.get();
+        timings.total += now - self.since.get();
+        self.timings.set(timings);
+        self.since.set(now);
+    }
+
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.usize(self.pid);
+        encoder.bool(self.parent.is_some());
+        encoder.usize(self.parent.unwrap_or(0));
+        encoder.usize(self.created_at);
+        encoder.bool(self.last_scheduled_at.is_some());
+        encoder.usize(self.last_scheduled_at.unwrap_or(0));
+        encoder.state(self.state);
+        let timings = self.timings.get();
+        encoder.usize(timings.total);
+        encoder.usize(timings.syscall);
+        encoder.usize(timings.execution);
+        encoder.i8(self.priority);
+        encoder.class(self.class);
+    }
+
+    fn restore(decoder: &mut Decoder) -> Result<Self, SnapshotError> {
+        Ok(PCB {
+            pid: decoder.usize()?,
+            parent: match (decoder.bool()?, decoder.usize()?) {
+                (true, pid) => Some(pid),
+                (false, _) => None,
+            },
+            created_at: decoder.usize()?,
+            last_scheduled_at: match (decoder.bool()?, decoder.usize()?) {
+                (true, time) => Some(time),
+                (false, _) => None,
+            },
+            state: decoder.state()?,
+            timings: Cell::new(Timings::new(decoder.usize()?, decoder.usize()?, decoder.usize()?)),
+            // set to the time of the scheduler once it is restored
+            since: Cell::new(0),
+            priority: decoder.i8()?,
+            class: decoder.class()?,
+        })
     }
 }
 
 pub struct RoundRobin {
-    ready_queue: VecDeque<PCB>,
-    waiting_queue: Vec<PCB>,
-    current_process: Option<PCB>,
-    next_pid: usize,
+    processes: ProcessTable<PCB>,
+    ready_queue: KeyDeque,
+    waiting_queue: WaitQueue,
+    sleeping_queue: TimerWheel<ProcessKey>,
+    current_process: Option<ProcessKey>,
+    pids: PidAllocator,
+    priorities: PriorityBounds,
     timeslice: NonZeroUsize,
     minimum_remaining_timeslice: usize,
     panic: bool,
     remaining: usize,
-    sleep: i32,
+    stats: SchedulerStats,
+    last_pid: Option<usize>,
+    clock: usize,
+    // the sleep of the last decision, until `on_tick` reports it
+    sleep: usize,
 }
 
 impl RoundRobin {
-    pub fn new(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> Self {
+    pub fn new(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize, pid_policy: PidPolicy, priorities: PriorityBounds, events: EventSemantics) -> Self {
         RoundRobin {
-            ready_queue: VecDeque::new(),
-            waiting_queue: Vec::new(),
+            processes: ProcessTable::new(),
+            ready_queue: KeyDeque::new(),
+            waiting_queue: WaitQueue::new(events),
+            sleeping_queue: TimerWheel::new(),
             current_process: None,
-            next_pid: 1,
+            pids: PidAllocator::new(pid_policy),
+            priorities,
             timeslice,
             minimum_remaining_timeslice,
             panic: false,
             remaining: timeslice.get(),
+            stats: SchedulerStats::default(),
+            last_pid: None,
+            clock: 0,
             sleep: 0,
         }
     }
 
     pub fn wake(&mut self) {
-        self.waiting_queue.retain(|process| {
-            if let Waiting {event: Some(_)} = process.state {
-                true
-            }
-            else if process.sleep <= 0 {
-                let mut ready_process = process.clone();
-                ready_process.state = Ready;
-                self.ready_queue.push_back(ready_process.clone());
-                false
-            }
-            else {
-                true
-            }
-        });
+        while let Some((_, key)) = self.sleeping_queue.pop_due(self.clock) {
+            self.processes[key].state = Ready;
+            self.ready_queue.push_back(key);
+            self.stats.wakeups += 1;
+            #[cfg(feature = "tracing")]
+            tracing::trace!(pid = self.processes[key].pid, "woken up");
+        }
     }
 
-    fn update_ready_timings(&mut self, remaining: usize) {
-        for waiting_process in &mut self.ready_queue {
-            waiting_process.timings.0 += self.remaining - remaining;
+    fn switch_to(&mut self, pid: Pid) {
+        if self.last_pid.is_some_and(|last_pid| last_pid != pid.get()) {
+            self.stats.context_switches += 1;
         }
+        self.last_pid = Some(pid.get());
     }
 
-    fn update_waiting_timings(&mut self, remaining: usize) {
-        for waiting_process in &mut self.waiting_queue {
-            waiting_process.timings.0 += self.remaining - remaining;
-            if let Waiting { event: Some(_) } = waiting_process.state {
-                continue;
-            }
-            waiting_process.sleep -= (self.remaining - remaining) as i32;
-        }
+    // the running process has used `elapsed` time units, the sleeping
+    // processes that are due wake up
+    fn advance_time(&mut self, elapsed: usize) {
+        self.clock += elapsed;
+        self.wake();
     }
 
-    fn reschedule_process(&mut self, remaining: usize, process: PCB) {
-        if remaining >= self.minimum_remaining_timeslice {
-            self.ready_queue.push_front(process.clone());
+    // the process has used its timeslice up to the system call, which
+    // takes a time unit
+    fn charge_syscall(&mut self, key: ProcessKey, remaining: usize) {
+        let timings = self.processes[key].timings.get_mut();
+        timings.execution += self.remaining - remaining - 1;
+        timings.syscall += 1;
+    }
+
+    fn settled(&self, key: ProcessKey) -> &PCB {
+        let process = &self.processes[key];
+        process.settle(self.clock);
+        process
+    }
+
+    // the processes in the order in which they are listed
+    fn processes(&self) -> impl Iterator<Item = &PCB> {
+        self.current_process.iter()
+            .chain(&self.ready_queue)
+            .chain(self.waiting_queue.iter())
+            .chain(self.sleeping_queue.values())
+            .map(|&key| self.settled(key))
+    }
+
+    fn reschedule_process(&mut self, remaining: usize, key: ProcessKey) {
+        // a process cannot be scheduled again for 0 time units
+        if remaining >= self.minimum_remaining_timeslice.max(1) {
+            self.ready_queue.push_front(key);
             self.remaining = remaining;
         } else {
-            self.ready_queue.push_back(process.clone());
+            self.ready_queue.push_back(key);
             self.remaining = self.timeslice.get();
         }
     }
+
+    fn next_index(&self) -> usize {
+        // the first ready process of the highest class
+        self.ready_queue.iter().map(|&key| self.processes[key].class).max()
+            .and_then(|class| self.ready_queue.iter().position(|&key| self.processes[key].class == class))
+            .unwrap_or(0)
+    }
 }
 
 impl Scheduler for RoundRobin {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret))]
     fn next(&mut self) -> crate::SchedulingDecision {
         if self.panic {
             return Panic;
         }
 
-        self.waiting_queue.sort_by_key(|process| process.sleep);
-
-        if self.sleep != 0 {
-            let amount = self.sleep;
-            self.sleep = 0;
-            for process in self.waiting_queue.iter_mut() {
-                process.timings.0 += amount as usize;
-                if let Waiting {event: Some(_)} = process.state {
-                    continue;
-                }
-                process.sleep -= amount;
-            }
+        // the processor has slept even if it did not call `on_tick`
+        if let Some(elapsed) = NonZeroUsize::new(self.sleep) {
+            self.on_tick(elapsed);
         }
 
         self.wake();
 
-        if self.current_process == None && self.ready_queue.is_empty() && !self.waiting_queue.is_empty() {
-            let mut amount = 0;
-            for process in &self.waiting_queue {
-                if let Waiting {event: Some(_)} = process.state {
-                    continue;
-                }
-                amount = process.sleep;
-                break;
-            }
-            if amount == 0 {
+        if self.current_process == None && self.ready_queue.is_empty() && !(self.waiting_queue.is_empty() && self.sleeping_queue.is_empty()) {
+            let Some(wake_at) = self.sleeping_queue.next_wake() else {
                 return Deadlock;
-            }
+            };
+            let amount = wake_at - self.clock;
             self.sleep = amount;
 
-            // amount can't be 0, case handled above
-            return Sleep(NonZeroUsize::new(amount as usize).unwrap());
+            // amount can't be 0, the processes that are due have been woken up,
+            // the clock advances in `on_tick`, once the processor has slept
+            return Sleep(NonZeroUsize::new(amount).unwrap());
         }
 
         if !self.ready_queue.is_empty() {
+            let index = self.next_index();
+            if index != 0 {
+                // the process at the front loses the rest of its timeslice
+                self.remaining = self.timeslice.get();
+            }
             // ready_queue has at least 1 process
-            let mut process = self.ready_queue.pop_front().unwrap();
+            let key = self.ready_queue.remove(index).unwrap();
+            let process = &mut self.processes[key];
             process.state = Running;
-            self.current_process = Some(process.clone());
+            process.last_scheduled_at = Some(self.clock);
             let pid = process.pid();
+            self.current_process = Some(key);
             // self.remaining can't be 0 (a process cannot have 0 remaining timeslice)
             let timeslice = NonZeroUsize::new(self.remaining).unwrap();
+            self.switch_to(pid);
             return Run {pid, timeslice};
         }
 
-        if let Some(process) = self.current_process {
+        if let Some(key) = self.current_process {
+            let process = &mut self.processes[key];
+            process.last_scheduled_at = Some(self.clock);
             let pid = process.pid();
             // self.remaining can't be 0 (a process cannot have 0 remaining timeslice)
             let timeslice = NonZeroUsize::new(self.remaining).unwrap();
+            self.switch_to(pid);
             return Run {pid, timeslice};
         }
 
         Done
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret))]
     fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        // the time used by the running process, the processes that are
+        // due wake up when every reason has been handled up to that point
+        let elapsed = match (self.current_process, reason) {
+            (None, _) => 0,
+            (Some(_), StopReason::Syscall { remaining, .. } | StopReason::Preempted { remaining }) => self.remaining - remaining,
+            (Some(_), StopReason::Expired) => self.remaining,
+        };
+
         return match reason {
             StopReason::Syscall { syscall, remaining } => {
-                if self.current_process == None && self.next_pid != 1 {
+                if self.current_process == None && !self.pids.is_fresh() {
                     return NoRunningProcess;
                 }
 
                 match syscall {
                     Syscall::Fork(priority) => {
-                        let process = PCB::new(self.next_pid, Ready, (0, 0, 0), priority);
-                        self.next_pid += 1;
-
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
+                        self.advance_time(elapsed);
 
-                        self.wake();
+                        let parent = self.current_process.map(|key| self.processes[key].pid);
+                        let class = self.current_process.map_or(Class::Normal, |key| self.processes[key].class);
+                        // the process is not created if its priority is rejected
+                        let child = self.priorities.apply(priority)
+                            .map(|priority| self.processes.insert(PCB::new(self.pids.allocate().get(), parent, self.clock, Ready, Timings::default(), priority.get(), class)));
 
-                        self.ready_queue.push_back(process.clone());
-                        if let Some(mut current_process) = self.current_process {
+                        if let Some(child) = child {
+                            self.ready_queue.push_back(child);
+                        }
+                        if let Some(key) = self.current_process {
                             self.current_process = None;
-                            current_process.state = Ready;
-                            current_process.timings.2 += self.remaining - remaining - 1;
-                            current_process.timings.1 += 1;
-                            current_process.timings.0 += self.remaining - remaining;
-                            self.reschedule_process(remaining, current_process);
+                            self.processes[key].state = Ready;
+                            self.charge_syscall(key, remaining);
+                            self.reschedule_process(remaining, key);
+                        }
+                        match child {
+                            Some(child) => SyscallResult::Pid(self.processes[child].pid()),
+                            None => SyscallResult::InvalidPriority,
                         }
-                        SyscallResult::Pid(process.pid().clone())
                     }
                     Syscall::Sleep(amount) => {
                         // current_process can't be none (case handled above)
-                        let mut process = self.current_process.unwrap();
+                        let key = self.current_process.unwrap();
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
-                        self.wake();
+                        self.advance_time(elapsed);
 
-                        let event = None;
-                        process.state = Waiting { event };
-                        process.sleep = amount as i32;
-                        process.timings.2 += self.remaining - remaining - 1;
-                        process.timings.1 += 1;
-                        process.timings.0 += self.remaining - remaining;
-
-                        self.waiting_queue.push(process.clone());
+                        self.charge_syscall(key, remaining);
+                        if amount == 0 {
+                            // a sleep without time yields, the process is ready after the others
+                            self.processes[key].state = Ready;
+                            self.ready_queue.push_back(key);
+                        } else {
+                            self.processes[key].state = Waiting { event: None };
+                            self.sleeping_queue.push(self.clock + amount, key);
+                        }
 
                         self.remaining = self.timeslice.get();
 
@@ -236,74 +359,58 @@ impl Scheduler for RoundRobin {
                     }
                     Syscall::Wait(event) => {
                         // current_process can't be none (case handled above)
-                        let mut process = self.current_process.unwrap();
+                        let key = self.current_process.unwrap();
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
-                        self.wake();
-
-                        process.state = Waiting { event: Some(event) };
-                        process.timings.2 += self.remaining - remaining - 1;
-                        process.timings.1 += 1;
-                        process.timings.0 += self.remaining - remaining;
-
-                        self.waiting_queue.push(process.clone());
-
-                        self.remaining = self.timeslice.get();
+                        self.advance_time(elapsed);
+
+                        self.charge_syscall(key, remaining);
+                        if self.waiting_queue.consume(event) {
+                            // the event was signaled before, the process continues like after a signal
+                            self.processes[key].state = Ready;
+                            self.reschedule_process(remaining, key);
+                        } else {
+                            self.processes[key].state = Waiting { event: Some(event) };
+                            self.waiting_queue.push(event, key);
+                            self.remaining = self.timeslice.get();
+                        }
 
                         Success
                     }
                     Syscall::Signal(signal) => {
                         // current_process can't be none (case handled above)
-                        let mut process = self.current_process.unwrap();
+                        let key = self.current_process.unwrap();
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
-                        self.waiting_queue.retain(|process| {
-                            if let Waiting { event: Some(event) } = process.state {
-                                if event == signal {
-                                    let mut ready_process = process.clone();
-                                    ready_process.state = Ready;
-                                    self.ready_queue.push_back(ready_process.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        });
+                        for key in self.waiting_queue.take(signal) {
+                            let process = &mut self.processes[key];
+                            process.state = Ready;
+                            self.ready_queue.push_back(key);
+                            self.stats.wakeups += 1;
+                            #[cfg(feature = "tracing")]
+                            tracing::trace!(pid = process.pid, "woken up");
+                        }
 
-                        self.wake();
+                        // the processes that were signaled are ready before the ones that are due
+                        self.advance_time(elapsed);
 
-                        process.state = Ready;
-                        process.timings.2 += self.remaining - remaining - 1;
-                        process.timings.1 += 1;
-                        process.timings.0 += self.remaining - remaining;
+                        self.processes[key].state = Ready;
+                        self.charge_syscall(key, remaining);
 
-                        self.reschedule_process(remaining, process);
+                        self.reschedule_process(remaining, key);
 
                         Success
                     }
                     Syscall::Exit => {
                         // current_process can't be none (case handled above)
-                        let process = self.current_process.unwrap();
-                        if process.pid == 1 && (!self.ready_queue.is_empty() || !self.waiting_queue.is_empty()) {
+                        let process = self.processes.remove(self.current_process.unwrap()).unwrap();
+                        self.pids.release(process.pid());
+                        if process.pid == 1 && (!self.ready_queue.is_empty() || !self.waiting_queue.is_empty() || !self.sleeping_queue.is_empty()) {
                             self.panic = true;
                         }
                         self.current_process = None;
 
-                        self.update_ready_timings(remaining);
-
-                        self.update_waiting_timings(remaining);
-
-                        self.wake();
+                        self.advance_time(elapsed);
 
                         self.remaining = self.timeslice.get();
 
@@ -311,29 +418,20 @@ impl Scheduler for RoundRobin {
                     }
                 }
             }
-            StopReason::Expired => {
-                // current_process can't be none if the process expired
-                let mut process = self.current_process.unwrap();
-                process.state = Ready;
-                process.timings.2 += self.remaining;
-                process.timings.0 += self.remaining;
-
-                for waiting_process in &mut self.ready_queue {
-                    waiting_process.timings.0 += self.remaining;
-                }
-
-                for waiting_process in &mut self.waiting_queue {
-                    waiting_process.timings.0 += self.remaining;
-                    if let Waiting { event: Some(_) } = waiting_process.state {
-                        continue;
-                    }
-                    waiting_process.sleep -= self.remaining as i32;
+            StopReason::Expired | StopReason::Preempted { .. } => {
+                if reason == StopReason::Expired {
+                    self.stats.preemptions += 1;
                 }
+                // current_process can't be none if the process was preempted
+                let key = self.current_process.unwrap();
+                let process = &mut self.processes[key];
+                process.state = Ready;
+                process.timings.get_mut().execution += elapsed;
 
-                self.wake();
+                self.advance_time(elapsed);
 
                 self.remaining = self.timeslice.get();
-                self.ready_queue.push_back(process.clone());
+                self.ready_queue.push_back(key);
                 self.current_process = None;
                 Success
             }
@@ -341,16 +439,231 @@ impl Scheduler for RoundRobin {
     }
 
     fn list(&mut self) -> Vec<&dyn Process> {
-        let mut vec: Vec<&dyn Process> = Vec::new();
-        if let Some(ref process) = self.current_process {
-            vec.push(process);
+        self.processes().map(|process| process as &dyn Process).collect()
+    }
+
+    fn for_each_process(&mut self, f: &mut dyn FnMut(&dyn Process)) {
+        for process in self.processes() {
+            f(process);
+        }
+    }
+
+    fn on_tick(&mut self, elapsed: NonZeroUsize) {
+        // the processes have spent the time the processor slept
+        self.sleep = 0;
+        self.stats.idle += elapsed.get();
+        self.clock += elapsed.get();
+    }
+
+    fn reset(&mut self) -> bool {
+        *self = RoundRobin::new(self.timeslice, self.minimum_remaining_timeslice, self.pids.policy(), self.priorities, self.waiting_queue.semantics());
+        true
+    }
+
+    fn as_tunable(&mut self) -> Option<&mut dyn TunableScheduler> {
+        Some(self)
+    }
+
+    fn as_seedable(&mut self) -> Option<&mut dyn SeedableScheduler> {
+        Some(self)
+    }
+
+    fn as_inspectable(&self) -> Option<&dyn InspectableScheduler> {
+        Some(self)
+    }
+
+    fn stats(&self) -> Option<SchedulerStats> {
+        Some(self.stats)
+    }
+
+    fn pid_policy(&self) -> PidPolicy {
+        self.pids.policy()
+    }
+
+    #[cfg(feature = "memory-usage")]
+    fn memory_usage(&self) -> Option<crate::MemoryUsage> {
+        Some(self.processes.memory_usage())
+    }
+}
+
+impl InspectableScheduler for RoundRobin {
+    fn current(&self) -> Option<&dyn Process> {
+        self.current_process.map(|key| self.settled(key) as &dyn Process)
+    }
+
+    fn ready(&self) -> Vec<&dyn Process> {
+        // the processes of a higher class are scheduled first
+        let mut ready: Vec<&PCB> = self.ready_queue.iter().map(|&key| self.settled(key)).collect();
+        ready.sort_by_key(|process| Reverse(process.class));
+        ready.into_iter().map(|process| process as &dyn Process).collect()
+    }
+
+    fn process(&self, pid: Pid) -> Option<&dyn Process> {
+        self.processes.find(pid).map(|process| {
+            process.settle(self.clock);
+            process as &dyn Process
+        })
+    }
+
+    fn waiting(&self) -> Vec<&dyn Process> {
+        self.waiting_queue.iter()
+            .chain(self.sleeping_queue.values())
+            .map(|&key| self.settled(key) as &dyn Process)
+            .collect()
+    }
+}
+
+impl SeedableScheduler for RoundRobin {
+    fn seed(&mut self, processes: &[Seed]) -> Result<(), SeedError> {
+        if !self.pids.is_fresh() {
+            return Err(SeedError::Started);
+        }
+        seed::check(processes, &self.priorities)?;
+
+        // reserving the PIDs in order never fails, the PIDs are different
+        let mut pids = processes.iter().map(|seed| seed.pid).collect::<Vec<_>>();
+        pids.sort();
+        for pid in pids {
+            self.pids.reserve(pid);
+        }
+
+        for seed in processes {
+            let parent = seed.parent.map(|parent| parent.get());
+            // the priorities have been checked above
+            let priority = self.priorities.apply(seed.priority).unwrap();
+            let mut process = PCB::new(seed.pid.get(), parent, self.clock, Ready, Timings::default(), priority.get(), seed.class);
+            match seed.state {
+                SeedState::Ready => self.ready_queue.push_back(self.processes.insert(process)),
+                SeedState::Waiting(event) => {
+                    process.state = Waiting { event: Some(event) };
+                    self.waiting_queue.push(event, self.processes.insert(process));
+                }
+                SeedState::Sleeping(amount) => {
+                    process.state = Waiting { event: None };
+                    self.sleeping_queue.push(self.clock + amount.get(), self.processes.insert(process));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TunableScheduler for RoundRobin {
+    fn tune(&mut self, parameter: Parameter) -> bool {
+        match parameter {
+            Parameter::Timeslice(timeslice) => {
+                // the next process receives the new timeslice, unless
+                // the previous one is rescheduled for its remaining time
+                if self.current_process.is_none() && self.remaining == self.timeslice.get() {
+                    self.remaining = timeslice.get();
+                }
+                self.timeslice = timeslice;
+            }
+            Parameter::MinimumRemainingTimeslice(minimum_remaining_timeslice) => {
+                self.minimum_remaining_timeslice = minimum_remaining_timeslice;
+            }
+            Parameter::CpuTime(_) => return false,
+        }
+        true
+    }
+}
+
+const SNAPSHOT_TAG: &[u8] = b"round robin";
+
+impl SnapshotScheduler for RoundRobin {
+    fn save(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(SNAPSHOT_TAG);
+        encoder.usize(self.ready_queue.len());
+        for &key in &self.ready_queue {
+            self.settled(key).save(&mut encoder);
+        }
+        encoder.usize(self.waiting_queue.len());
+        for &key in self.waiting_queue.iter() {
+            self.settled(key).save(&mut encoder);
+        }
+        self.waiting_queue.save_pending(&mut encoder);
+        encoder.usize(self.sleeping_queue.len());
+        for (wake_at, &key) in self.sleeping_queue.iter() {
+            encoder.usize(wake_at);
+            self.settled(key).save(&mut encoder);
+        }
+        encoder.bool(self.current_process.is_some());
+        if let Some(key) = self.current_process {
+            self.settled(key).save(&mut encoder);
+        }
+        self.pids.save(&mut encoder);
+        self.priorities.save(&mut encoder);
+        encoder.usize(self.timeslice.get());
+        encoder.usize(self.minimum_remaining_timeslice);
+        encoder.bool(self.panic);
+        encoder.usize(self.remaining);
+        encoder.usize(self.stats.context_switches);
+        encoder.usize(self.stats.preemptions);
+        encoder.usize(self.stats.idle);
+        encoder.usize(self.stats.wakeups);
+        encoder.bool(self.last_pid.is_some());
+        encoder.usize(self.last_pid.unwrap_or(0));
+        encoder.usize(self.clock);
+        encoder.usize(self.sleep);
+        encoder.finish()
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<(), SnapshotError> {
+        let mut decoder = Decoder::new(snapshot, SNAPSHOT_TAG)?;
+        let mut processes = ProcessTable::new();
+        let ready_queue = (0..decoder.usize()?)
+            .map(|_| PCB::restore(&mut decoder).map(|process| processes.insert(process)))
+            .collect::<Result<_, _>>()?;
+        let mut waiting_queue = WaitQueue::new(EventSemantics::Transient);
+        for _ in 0..decoder.usize()? {
+            let process = PCB::restore(&mut decoder)?;
+            let Waiting { event: Some(event) } = process.state else {
+                return Err(SnapshotError::new("a waiting process does not wait for an event"));
+            };
+            waiting_queue.push(event, processes.insert(process));
         }
-        for process in &self.ready_queue {
-            vec.push(process)
+        waiting_queue.restore_pending(&mut decoder)?;
+        let mut sleeping_queue = TimerWheel::new();
+        for _ in 0..decoder.usize()? {
+            let wake_at = decoder.usize()?;
+            sleeping_queue.push(wake_at, processes.insert(PCB::restore(&mut decoder)?));
         }
-        for process in &self.waiting_queue {
-            vec.push(process);
+        let current_process = match decoder.bool()? {
+            true => Some(processes.insert(PCB::restore(&mut decoder)?)),
+            false => None,
+        };
+        let scheduler = RoundRobin {
+            processes,
+            ready_queue,
+            waiting_queue,
+            sleeping_queue,
+            current_process,
+            pids: PidAllocator::restore(&mut decoder)?,
+            priorities: PriorityBounds::restore(&mut decoder)?,
+            timeslice: decoder.non_zero()?,
+            minimum_remaining_timeslice: decoder.usize()?,
+            panic: decoder.bool()?,
+            remaining: decoder.usize()?,
+            stats: SchedulerStats {
+                context_switches: decoder.usize()?,
+                preemptions: decoder.usize()?,
+                idle: decoder.usize()?,
+                wakeups: decoder.usize()?,
+            },
+            last_pid: match (decoder.bool()?, decoder.usize()?) {
+                (true, pid) => Some(pid),
+                (false, _) => None,
+            },
+            clock: decoder.usize()?,
+            sleep: decoder.usize()?,
+        };
+        decoder.finish()?;
+        // the total times were up to date when the snapshot was taken
+        let now = scheduler.clock;
+        for process in scheduler.processes.values() {
+            process.since.set(now);
         }
-        vec
+        *self = scheduler;
+        Ok(())
     }
 }
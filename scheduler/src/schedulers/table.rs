@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+use slotmap::SlotMap;
+use crate::{Pid, Process};
+#[cfg(feature = "memory-usage")]
+use crate::MemoryUsage;
+
+slotmap::new_key_type! {
+    /// The key of a process in a [`ProcessTable`]. PIDs are not used as
+    /// keys because they can be recycled.
+    pub(super) struct ProcessKey;
+}
+
+/// The PCBs of a scheduler, stored once and indexed by PID.
+///
+/// The queues of a scheduler hold keys into this table, so a process
+/// is found in constant time by PID, whatever queue it is in.
+pub(super) struct ProcessTable<P> {
+    processes: SlotMap<ProcessKey, P>,
+    pids: HashMap<Pid, ProcessKey>,
+}
+
+impl<P: Process> ProcessTable<P> {
+    pub(super) fn new() -> Self {
+        ProcessTable {
+            processes: SlotMap::with_key(),
+            pids: HashMap::new(),
+        }
+    }
+
+    /// Adds a process and indexes it by its PID.
+    pub(super) fn insert(&mut self, process: P) -> ProcessKey {
+        let pid = process.pid();
+        let key = self.processes.insert(process);
+        self.pids.insert(pid, key);
+        key
+    }
+
+    /// Removes a process and its PID from the index.
+    pub(super) fn remove(&mut self, key: ProcessKey) -> Option<P> {
+        let process = self.processes.remove(key)?;
+        self.pids.remove(&process.pid());
+        Some(process)
+    }
+
+    /// Returns the processes in no particular order.
+    pub(super) fn values(&self) -> impl Iterator<Item = &P> {
+        self.processes.values()
+    }
+
+    /// Returns the process with the PID `pid`.
+    pub(super) fn find(&self, pid: Pid) -> Option<&P> {
+        self.pids.get(&pid).map(|&key| &self.processes[key])
+    }
+
+    /// Returns the memory used by the PCBs and the index, the memory
+    /// owned by the PCBs is not included.
+    #[cfg(feature = "memory-usage")]
+    pub(super) fn memory_usage(&self) -> MemoryUsage {
+        // the table never releases memory, the memory used now is the peak
+        let current = self.processes.capacity() * std::mem::size_of::<(P, u32)>()
+            + self.pids.capacity() * std::mem::size_of::<(Pid, ProcessKey)>();
+        MemoryUsage { current, peak: current }
+    }
+}
+
+impl<P> Index<ProcessKey> for ProcessTable<P> {
+    type Output = P;
+
+    fn index(&self, key: ProcessKey) -> &P {
+        &self.processes[key]
+    }
+}
+
+impl<P> IndexMut<ProcessKey> for ProcessTable<P> {
+    fn index_mut(&mut self, key: ProcessKey) -> &mut P {
+        &mut self.processes[key]
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::{Event, EventSemantics};
+use crate::snapshot::{Decoder, Encoder, SnapshotError};
+use super::ProcessKey;
+
+/// The processes of a scheduler that wait for an event, indexed by
+/// the event.
+///
+/// The processes are ordered by a rank chosen by the scheduler, then
+/// by the time at which they started waiting. Signaling an event only
+/// touches the processes that wait for it.
+pub(super) struct WaitQueue<R = ()> {
+    order: BTreeMap<(R, usize), ProcessKey>,
+    events: HashMap<Event, Vec<(R, usize)>>,
+    sequence: usize,
+    /// The events signaled while no process waited for them, if the
+    /// signals are remembered.
+    pending: Option<BTreeSet<Event>>,
+}
+
+impl<R: Ord + Copy> WaitQueue<R> {
+    pub(super) fn new(semantics: EventSemantics) -> Self {
+        WaitQueue {
+            order: BTreeMap::new(),
+            events: HashMap::new(),
+            sequence: 0,
+            pending: (semantics == EventSemantics::Sticky).then(BTreeSet::new),
+        }
+    }
+
+    pub(super) fn semantics(&self) -> EventSemantics {
+        match self.pending {
+            Some(_) => EventSemantics::Sticky,
+            None => EventSemantics::Transient,
+        }
+    }
+
+    /// Returns `true` if `event` was signaled while no process waited for
+    /// it, and forgets the signal.
+    pub(super) fn consume(&mut self, event: Event) -> bool {
+        self.pending.as_mut().is_some_and(|pending| pending.remove(&event))
+    }
+
+    /// Adds a process that waits for `event`, after the processes
+    /// with the same rank.
+    pub(super) fn push(&mut self, event: Event, key: ProcessKey)
+    where
+        R: Default,
+    {
+        self.push_ranked(event, R::default(), key);
+    }
+
+    /// Adds a process that waits for `event`, after the processes with
+    /// a lower or the same rank.
+    pub(super) fn push_ranked(&mut self, event: Event, rank: R, key: ProcessKey) {
+        let position = (rank, self.sequence);
+        self.sequence += 1;
+        self.order.insert(position, key);
+        self.events.entry(event).or_default().push(position);
+    }
+
+    /// Removes the processes that wait for `event`, in order, or
+    /// remembers the signal if no process waits for it.
+    pub(super) fn take(&mut self, event: Event) -> impl Iterator<Item = ProcessKey> + '_ {
+        let mut positions = self.events.remove(&event).unwrap_or_default();
+        if let (true, Some(pending)) = (positions.is_empty(), &mut self.pending) {
+            pending.insert(event);
+        }
+        // the positions are sorted by rank, then by the order in which
+        // the processes started waiting
+        positions.sort_unstable();
+        // every position of an event is in the order
+        positions.into_iter().map(|position| self.order.remove(&position).unwrap())
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = &ProcessKey> {
+        self.order.values()
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Saves the semantics and the remembered signals, the processes
+    /// are saved by the scheduler.
+    pub(super) fn save_pending(&self, encoder: &mut Encoder) {
+        encoder.bool(self.pending.is_some());
+        let pending = self.pending.iter().flatten().collect::<Vec<_>>();
+        encoder.usize(pending.len());
+        for event in pending {
+            encoder.event(*event);
+        }
+    }
+
+    /// Restores the values saved by [`WaitQueue::save_pending`].
+    pub(super) fn restore_pending(&mut self, decoder: &mut Decoder) -> Result<(), SnapshotError> {
+        let sticky = decoder.bool()?;
+        let pending = (0..decoder.usize()?).map(|_| decoder.event()).collect::<Result<BTreeSet<_>, _>>()?;
+        self.pending = match sticky {
+            true => Some(pending),
+            false if pending.is_empty() => None,
+            false => return Err(SnapshotError::new("signals are remembered without sticky events")),
+        };
+        Ok(())
+    }
+}
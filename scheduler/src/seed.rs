@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+
+use crate::{Class, Event, Pid, Priority, PriorityBounds, Scheduler};
+
+/// The state of a process that exists before the simulation begins.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeedState {
+    /// The process is ready to be scheduled.
+    Ready,
+
+    /// The process is waiting for an event.
+    Waiting(Event),
+
+    /// The process is sleeping for an amount of time.
+    Sleeping(NonZeroUsize),
+}
+
+/// A process that exists before the simulation begins.
+///
+/// The process has not used any time and is considered to be created
+/// at virtual time 0.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Seed {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// The PID of the parent process, if it has one.
+    pub parent: Option<Pid>,
+
+    /// The priority of the process.
+    pub priority: Priority,
+
+    /// The scheduling class of the process.
+    pub class: Class,
+
+    /// The state of the process.
+    pub state: SeedState,
+}
+
+impl Seed {
+    /// Returns a process of the [`Class::Normal`] class that is ready
+    /// to be scheduled and has no parent.
+    ///
+    /// * `pid` - the PID of the process.
+    /// * `priority` - the priority of the process.
+    pub fn new(pid: Pid, priority: Priority) -> Seed {
+        Seed {
+            pid,
+            parent: None,
+            priority,
+            class: Class::Normal,
+            state: SeedState::Ready,
+        }
+    }
+
+    /// Sets the parent of the process.
+    ///
+    /// * `parent` - the PID of the parent process.
+    pub fn parent(mut self, parent: Pid) -> Seed {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Sets the scheduling class of the process.
+    ///
+    /// * `class` - the scheduling class of the process.
+    pub fn class(mut self, class: Class) -> Seed {
+        self.class = class;
+        self
+    }
+
+    /// Sets the state of the process.
+    ///
+    /// * `state` - the state of the process.
+    pub fn state(mut self, state: SeedState) -> Seed {
+        self.state = state;
+        self
+    }
+}
+
+/// The error returned when a scheduler cannot be seeded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// The scheduler has already created processes.
+    Started,
+
+    /// Several processes have the same PID.
+    DuplicatePid(Pid),
+
+    /// The priority of the process with this PID is rejected by the
+    /// [`PriorityBounds`] of the scheduler.
+    InvalidPriority(Pid),
+}
+
+impl Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::Started => write!(f, "the scheduler has already created processes"),
+            SeedError::DuplicatePid(pid) => write!(f, "PID {pid} is used by several processes"),
+            SeedError::InvalidPriority(pid) => write!(f, "the priority of PID {pid} is out of bounds"),
+        }
+    }
+}
+
+impl Error for SeedError {}
+
+/// Verifies that the processes have different PIDs and priorities
+/// that are accepted by `bounds`.
+pub(crate) fn check(processes: &[Seed], bounds: &PriorityBounds) -> Result<(), SeedError> {
+    let mut pids = HashSet::new();
+    if let Some(seed) = processes.iter().find(|seed| !pids.insert(seed.pid)) {
+        return Err(SeedError::DuplicatePid(seed.pid));
+    }
+    match processes.iter().find(|seed| bounds.apply(seed.priority).is_none()) {
+        Some(seed) => Err(SeedError::InvalidPriority(seed.pid)),
+        None => Ok(()),
+    }
+}
+
+/// A scheduler that can start with processes that already exist.
+///
+/// This allows starting a simulation from the middle of a scenario.
+/// The PIDs of the seeded processes are not allocated to new processes.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Event, Pid, Priority, Scheduler, SchedulingDecision, Seed, SeedState};
+/// use std::num::NonZeroUsize;
+///
+/// let mut scheduler = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+/// let seedable = scheduler.as_seedable().unwrap();
+/// seedable
+///     .seed(&[
+///         Seed::new(Pid::new(1), Priority::new(0)).state(SeedState::Waiting(Event::user(1))),
+///         Seed::new(Pid::new(4), Priority::new(0)).parent(Pid::new(1)),
+///     ])
+///     .unwrap();
+///
+/// assert!(matches!(scheduler.next(), SchedulingDecision::Run { pid, .. } if pid == 4));
+/// ```
+pub trait SeedableScheduler: Scheduler {
+    /// Adds the processes to a scheduler that has not created any process.
+    ///
+    /// The scheduler is left unchanged if an error is returned.
+    ///
+    /// * `processes` - the processes, ready processes are scheduled in this order.
+    fn seed(&mut self, processes: &[Seed]) -> Result<(), SeedError>;
+}
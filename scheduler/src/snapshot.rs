@@ -0,0 +1,230 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+
+use crate::{Class, Event, EventNamespace, Pid, ProcessState, Scheduler};
+
+/// A scheduler whose state can be saved and restored.
+///
+/// Snapshots allow checkpointing a simulation, continuing it with
+/// another scheduler instance or comparing the internal state of a
+/// scheduler against a known good state.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Priority, Scheduler, SchedulingDecision, SnapshotScheduler, StopReason, Syscall};
+/// use std::num::NonZeroUsize;
+///
+/// let mut scheduler = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+/// scheduler.stop(StopReason::syscall(Syscall::Fork(Priority::new(0))));
+/// let snapshot = scheduler.save();
+///
+/// let mut copy = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+/// copy.restore(&snapshot).unwrap();
+/// assert_eq!(scheduler.next(), copy.next());
+/// ```
+pub trait SnapshotScheduler: Scheduler {
+    /// Returns the complete state of the scheduler, including its parameters.
+    fn save(&self) -> Vec<u8>;
+
+    /// Replaces the state of the scheduler with a state returned by [`SnapshotScheduler::save`].
+    ///
+    /// The state is left unchanged if the snapshot is not valid.
+    ///
+    /// * `snapshot` - a snapshot saved by a scheduler of the same type.
+    fn restore(&mut self, snapshot: &[u8]) -> Result<(), SnapshotError>;
+}
+
+/// The error returned when a snapshot cannot be restored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotError {
+    message: String,
+}
+
+impl SnapshotError {
+    pub(crate) fn new(message: impl Into<String>) -> SnapshotError {
+        SnapshotError {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid snapshot: {}", self.message)
+    }
+}
+
+impl Error for SnapshotError {}
+
+/// Writes the values of a snapshot.
+pub(crate) struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    /// Starts a snapshot for the scheduler identified by `tag`.
+    pub(crate) fn new(tag: &[u8]) -> Encoder {
+        let mut encoder = Encoder { bytes: vec![] };
+        encoder.usize(tag.len());
+        encoder.bytes.extend_from_slice(tag);
+        encoder
+    }
+
+    pub(crate) fn usize(&mut self, value: usize) {
+        self.bytes.extend_from_slice(&(value as u64).to_le_bytes());
+    }
+
+    pub(crate) fn i8(&mut self, value: i8) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn bool(&mut self, value: bool) {
+        self.bytes.push(value as u8);
+    }
+
+    pub(crate) fn state(&mut self, state: ProcessState) {
+        match state {
+            ProcessState::Ready => self.bytes.push(0),
+            ProcessState::Running => self.bytes.push(1),
+            ProcessState::Waiting { event: None } => self.bytes.push(2),
+            ProcessState::Waiting { event: Some(event) } if event.namespace() == EventNamespace::User => {
+                self.bytes.push(3);
+                self.usize(event.number());
+            }
+            ProcessState::Waiting { event: Some(event) } => {
+                self.bytes.push(6);
+                self.bytes.push(match event.namespace() {
+                    EventNamespace::User => 0,
+                    EventNamespace::Io => 1,
+                    EventNamespace::ChildExit => 2,
+                });
+                self.usize(event.number());
+            }
+            ProcessState::Stopped => self.bytes.push(4),
+            ProcessState::Zombie => self.bytes.push(5),
+        }
+    }
+
+    pub(crate) fn event(&mut self, event: Event) {
+        self.bytes.push(match event.namespace() {
+            EventNamespace::User => 0,
+            EventNamespace::Io => 1,
+            EventNamespace::ChildExit => 2,
+        });
+        self.usize(event.number());
+    }
+
+    pub(crate) fn class(&mut self, class: Class) {
+        self.bytes.push(match class {
+            Class::Idle => 0,
+            Class::Normal => 1,
+            Class::RealTime => 2,
+        });
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads the values of a snapshot, in the order they were written by an [`Encoder`].
+pub(crate) struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    /// Starts reading a snapshot, verifying that it was saved by
+    /// the scheduler identified by `tag`.
+    pub(crate) fn new(bytes: &'a [u8], tag: &[u8]) -> Result<Decoder<'a>, SnapshotError> {
+        let mut decoder = Decoder { bytes };
+        let length = decoder.usize()?;
+        if decoder.take(length)? != tag {
+            return Err(SnapshotError::new(format!(
+                "not saved by a {} scheduler",
+                String::from_utf8_lossy(tag)
+            )));
+        }
+        Ok(decoder)
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.bytes.len() < length {
+            return Err(SnapshotError::new("unexpected end of data"));
+        }
+        let (value, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    pub(crate) fn usize(&mut self) -> Result<usize, SnapshotError> {
+        let value = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+        usize::try_from(value).map_err(|_| SnapshotError::new("value out of range"))
+    }
+
+    pub(crate) fn non_zero(&mut self) -> Result<NonZeroUsize, SnapshotError> {
+        NonZeroUsize::new(self.usize()?).ok_or_else(|| SnapshotError::new("unexpected zero value"))
+    }
+
+    pub(crate) fn i8(&mut self) -> Result<i8, SnapshotError> {
+        Ok(i8::from_le_bytes(self.take(1)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, SnapshotError> {
+        match self.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SnapshotError::new("invalid boolean")),
+        }
+    }
+
+    pub(crate) fn state(&mut self) -> Result<ProcessState, SnapshotError> {
+        match self.take(1)?[0] {
+            0 => Ok(ProcessState::Ready),
+            1 => Ok(ProcessState::Running),
+            2 => Ok(ProcessState::Waiting { event: None }),
+            3 => Ok(ProcessState::Waiting {
+                event: Some(Event::user(self.usize()?)),
+            }),
+            4 => Ok(ProcessState::Stopped),
+            5 => Ok(ProcessState::Zombie),
+            6 => {
+                let event = match self.take(1)?[0] {
+                    1 => Event::io(self.usize()?),
+                    2 => Event::child_exit(Pid::new(self.non_zero()?.get())),
+                    _ => return Err(SnapshotError::new("invalid event namespace")),
+                };
+                Ok(ProcessState::Waiting { event: Some(event) })
+            }
+            _ => Err(SnapshotError::new("invalid process state")),
+        }
+    }
+
+    pub(crate) fn event(&mut self) -> Result<Event, SnapshotError> {
+        match self.take(1)?[0] {
+            0 => Ok(Event::user(self.usize()?)),
+            1 => Ok(Event::io(self.usize()?)),
+            2 => Ok(Event::child_exit(Pid::new(self.non_zero()?.get()))),
+            _ => Err(SnapshotError::new("invalid event namespace")),
+        }
+    }
+
+    pub(crate) fn class(&mut self) -> Result<Class, SnapshotError> {
+        match self.take(1)?[0] {
+            0 => Ok(Class::Idle),
+            1 => Ok(Class::Normal),
+            2 => Ok(Class::RealTime),
+            _ => Err(SnapshotError::new("invalid scheduling class")),
+        }
+    }
+
+    /// Verifies that all the data was read.
+    pub(crate) fn finish(self) -> Result<(), SnapshotError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(SnapshotError::new("unexpected data at the end"))
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+
+// every level has 64 slots, a slot of a level covers as much time as
+// all the slots of the level below it
+const BITS: u32 = 6;
+const SLOTS: usize = 1 << BITS;
+const LEVELS: usize = usize::BITS.div_ceil(BITS) as usize;
+
+struct Timer<P> {
+    wake_at: usize,
+    sequence: usize,
+    process: P,
+}
+
+/// The timers of a scheduler, such as its sleeping processes, ordered
+/// by the time at which they expire.
+///
+/// The timers are kept in a hierarchical timer wheel. A timer is
+/// stored in the slot of the highest level whose time differs from the
+/// current time, and moves to the levels below as the time passes, so
+/// adding a timer and releasing the due ones does not depend on the
+/// number of timers.
+///
+/// Timers that expire at the same time are released in the order in
+/// which they were added.
+pub(crate) struct TimerWheel<P> {
+    // the slots of a level are allocated when it gets its first timer
+    slots: Vec<VecDeque<Timer<P>>>,
+    // the slots of every level that hold timers, one bit per slot
+    occupied: [u64; LEVELS],
+    now: usize,
+    sequence: usize,
+    len: usize,
+}
+
+impl<P> TimerWheel<P> {
+    pub(crate) fn new() -> TimerWheel<P> {
+        TimerWheel {
+            slots: Vec::new(),
+            occupied: [0; LEVELS],
+            now: 0,
+            sequence: 0,
+            len: 0,
+        }
+    }
+
+    /// Adds a process that wakes up at the absolute time `wake_at`. A
+    /// time that has already passed is the current time of the wheel.
+    pub(crate) fn push(&mut self, wake_at: usize, process: P) {
+        let wake_at = wake_at.max(self.now);
+        self.insert(Timer {
+            wake_at,
+            sequence: self.sequence,
+            process,
+        });
+        self.sequence += 1;
+        self.len += 1;
+    }
+
+    /// Returns the time at which the first process wakes up.
+    pub(crate) fn next_wake(&self) -> Option<usize> {
+        for level in 0..LEVELS {
+            // the timers of the slot of the current time are in the
+            // levels below, except for the lowest level
+            let first = Self::index(self.now, level) + usize::from(level > 0);
+            let pending = self.occupied[level] & u64::MAX.checked_shl(first as u32).unwrap_or(0);
+            if pending != 0 {
+                let slot = level * SLOTS + pending.trailing_zeros() as usize;
+                return self.slots[slot].front().map(|timer| timer.wake_at);
+            }
+        }
+        None
+    }
+
+    /// Removes the first process and the time at which it wakes up, if
+    /// it wakes up at `clock` or earlier.
+    pub(crate) fn pop_due(&mut self, clock: usize) -> Option<(usize, P)> {
+        match self.next_wake() {
+            Some(wake_at) if wake_at <= clock => {
+                self.advance(wake_at);
+                // the timers that expire at the current time are in the lowest level
+                let slot = Self::index(wake_at, 0);
+                let timer = self.slots[slot].pop_front()?;
+                if self.slots[slot].is_empty() {
+                    self.occupied[0] &= !(1 << slot);
+                }
+                self.len -= 1;
+                Some((timer.wake_at, timer.process))
+            }
+            _ => {
+                self.advance(clock);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the processes and the times at which they wake up, in
+    /// the order in which they are released.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &P)> {
+        // the slots before the one of the current time are empty, so the
+        // slots of the lower levels and the lower slots expire first
+        self.occupied_slots().flatten().map(|timer| (timer.wake_at, &timer.process))
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &P> {
+        self.iter().map(|(_, process)| process)
+    }
+
+    fn occupied_slots(&self) -> impl Iterator<Item = &VecDeque<Timer<P>>> {
+        (0..self.slots.len() / SLOTS).flat_map(move |level| {
+            let mut occupied = self.occupied[level];
+            std::iter::from_fn(move || {
+                let index = occupied.trailing_zeros() as usize;
+                // clears the lowest bit
+                (occupied != 0).then(|| {
+                    occupied &= occupied - 1;
+                    &self.slots[level * SLOTS + index]
+                })
+            })
+        })
+    }
+
+    // the slot of `time` in a level
+    fn index(time: usize, level: usize) -> usize {
+        (time >> (level as u32 * BITS)) & (SLOTS - 1)
+    }
+
+    fn insert(&mut self, timer: Timer<P>) {
+        // the highest group of bits in which the time differs from the current time
+        let level = match timer.wake_at ^ self.now {
+            0 => 0,
+            difference => ((usize::BITS - 1 - difference.leading_zeros()) / BITS) as usize,
+        };
+        let index = Self::index(timer.wake_at, level);
+        if self.slots.len() <= level * SLOTS {
+            self.slots.resize_with((level + 1) * SLOTS, VecDeque::new);
+        }
+        self.occupied[level] |= 1 << index;
+        // a slot is ordered like the timers are released
+        let slot = &mut self.slots[level * SLOTS + index];
+        let position = slot.partition_point(|other| (other.wake_at, other.sequence) < (timer.wake_at, timer.sequence));
+        slot.insert(position, timer);
+    }
+
+    // moves the current time to `now`, which is not later than any
+    // timer, the timers of the slots that it enters move to the levels
+    // below, after the timers that are already there
+    fn advance(&mut self, now: usize) {
+        if now <= self.now {
+            return;
+        }
+        self.now = now;
+        for level in (1..LEVELS).rev() {
+            let index = Self::index(now, level);
+            if self.occupied[level] & (1 << index) == 0 {
+                continue;
+            }
+            self.occupied[level] &= !(1 << index);
+            // the timers move to lower levels, the slot keeps its memory
+            let mut timers = std::mem::take(&mut self.slots[level * SLOTS + index]);
+            for timer in timers.drain(..) {
+                self.insert(timer);
+            }
+            self.slots[level * SLOTS + index] = timers;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// The time units that a process has spent since it was created.
+///
+/// The time that is not spent in system calls or executing is
+/// spent waiting, either in the ready queue or sleeping.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::Timings;
+///
+/// let mut timings = Timings::new(5, 1, 3);
+/// timings += Timings::new(2, 1, 1);
+/// assert_eq!(timings, Timings { total: 7, syscall: 2, execution: 4 });
+/// assert_eq!(timings.to_string(), "total 7, syscall 2, execution 4");
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timings {
+    /// The total time since the process was created.
+    pub total: usize,
+
+    /// The time spent sending system calls.
+    pub syscall: usize,
+
+    /// The time spent executing.
+    pub execution: usize,
+}
+
+impl Timings {
+    pub fn new(total: usize, syscall: usize, execution: usize) -> Timings {
+        Timings {
+            total,
+            syscall,
+            execution,
+        }
+    }
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "total {}, syscall {}, execution {}",
+            self.total, self.syscall, self.execution
+        )
+    }
+}
+
+impl Add for Timings {
+    type Output = Timings;
+
+    fn add(self, rhs: Timings) -> Timings {
+        Timings::new(
+            self.total + rhs.total,
+            self.syscall + rhs.syscall,
+            self.execution + rhs.execution,
+        )
+    }
+}
+
+impl AddAssign for Timings {
+    fn add_assign(&mut self, rhs: Timings) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Timings {
+    type Output = Timings;
+
+    /// Returns the time spent between two timings of the same process.
+    ///
+    /// ## Panics
+    ///
+    /// Panics on overflow if any value of `rhs` is greater than the one in `self`.
+    fn sub(self, rhs: Timings) -> Timings {
+        Timings::new(
+            self.total - rhs.total,
+            self.syscall - rhs.syscall,
+            self.execution - rhs.execution,
+        )
+    }
+}
+
+impl SubAssign for Timings {
+    fn sub_assign(&mut self, rhs: Timings) {
+        *self = *self - rhs;
+    }
+}
+
+impl From<(usize, usize, usize)> for Timings {
+    /// Converts a `(total, syscall, execution)` tuple.
+    fn from((total, syscall, execution): (usize, usize, usize)) -> Timings {
+        Timings::new(total, syscall, execution)
+    }
+}
+
+impl From<Timings> for (usize, usize, usize) {
+    fn from(timings: Timings) -> (usize, usize, usize) {
+        (timings.total, timings.syscall, timings.execution)
+    }
+}
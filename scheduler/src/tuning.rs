@@ -0,0 +1,43 @@
+use std::num::NonZeroUsize;
+
+use crate::Scheduler;
+
+/// A scheduler parameter that can be changed during a simulation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Parameter {
+    /// The time quanta that a process can run before it is preempted.
+    Timeslice(NonZeroUsize),
+
+    /// The minimum remaining timeslice that allows a process to be
+    /// scheduled again after a system call.
+    MinimumRemainingTimeslice(usize),
+
+    /// The total time units that the cpu has for an iteration, used
+    /// by the cfs policy to compute the timeslice of each process.
+    CpuTime(NonZeroUsize),
+}
+
+/// A scheduler whose parameters can be changed during a simulation.
+///
+/// Changes apply to the next scheduling decisions. A process that is
+/// already running keeps the timeslice it has received.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Parameter, Scheduler};
+/// use std::num::NonZeroUsize;
+///
+/// let mut scheduler = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+/// let tunable = scheduler.as_tunable().unwrap();
+/// assert!(tunable.tune(Parameter::Timeslice(NonZeroUsize::new(5).unwrap())));
+/// assert!(!tunable.tune(Parameter::CpuTime(NonZeroUsize::new(5).unwrap())));
+/// ```
+pub trait TunableScheduler: Scheduler {
+    /// Changes a parameter of the scheduler.
+    ///
+    /// Returns `false` if the scheduler does not use the parameter.
+    ///
+    /// * `parameter` - the parameter and its new value.
+    fn tune(&mut self, parameter: Parameter) -> bool;
+}